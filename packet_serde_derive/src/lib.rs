@@ -5,7 +5,7 @@ use proc_macro::TokenStream;
 use quote::{ToTokens, quote};
 use syn::{self, parse_macro_input, Data, Fields, Ident, Attribute};
 
-#[proc_macro_derive(PacketSerde, attributes(discriminant_type))]
+#[proc_macro_derive(PacketSerde, attributes(discriminant_type, discriminant))]
 pub fn packet_serde_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as syn::DeriveInput);
     let name = &ast.ident;
@@ -121,20 +121,35 @@ fn get_discriminant_type(attrs: &[Attribute]) -> proc_macro2::TokenStream {
     quote! { types::VarInt }
 }
 
+// the discriminant of a variant, in order of precedence:
+// #[discriminant(...)] (e.g. #[discriminant("always")] for string discriminants),
+// an explicit `= value`, or the index of the variant
+fn get_variant_discriminant(variant: &syn::Variant, index: usize) -> proc_macro2::TokenStream {
+    for attr in &variant.attrs {
+        if attr.path().is_ident("discriminant") {
+            if let Ok(lit) = attr.parse_args::<syn::LitStr>() {
+                return lit.to_token_stream();
+            }
+            if let Ok(exp) = attr.parse_args::<syn::Expr>() {
+                return quote! { ((#exp) as i32) };
+            }
+        }
+    }
+    match &variant.discriminant {
+        Some((_, exp)) => quote! { ((#exp) as i32) },
+        None => {
+            let enumerator = index as i32;
+            quote! { #enumerator }
+        }
+    }
+}
+
 fn generate_enum_impl(e: &syn::DataEnum, attrs: &[Attribute]) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
     let discriminant_type = get_discriminant_type(attrs);
     
     let write_arms = e.variants.iter().enumerate().map(|(i, variant)| {
         let variant_name = &variant.ident;
-        let discriminant = match &variant.discriminant {
-            Some((_, exp)) => {
-                exp.to_token_stream()
-            },
-            None => {
-                let enumerator = i as i32;
-                quote! { #enumerator }
-            },
-        };
+        let discriminant = get_variant_discriminant(variant, i);
 
         match &variant.fields {
             Fields::Named(fields) => {
@@ -177,16 +192,8 @@ fn generate_enum_impl(e: &syn::DataEnum, attrs: &[Attribute]) -> (proc_macro2::T
     
     let read_arms = e.variants.iter().enumerate().map(|(i, variant)| {
         let variant_name = &variant.ident;
-        let discriminant = match &variant.discriminant {
-            Some((_, exp)) => {
-                exp.to_token_stream()
-            },
-            None => {
-                let enumerator = i as i32;
-                quote! { #enumerator }
-            },
-        };
-        
+        let discriminant = get_variant_discriminant(variant, i);
+
         match &variant.fields {
             Fields::Named(fields) => {
                 let field_reads = fields.named.iter().map(|f| {
@@ -195,7 +202,9 @@ fn generate_enum_impl(e: &syn::DataEnum, attrs: &[Attribute]) -> (proc_macro2::T
                     quote! { #field_name: <#field_type as PacketReadable>::read(stream) }
                 });
                 quote! {
-                    #discriminant => Self::#variant_name { #( #field_reads ),* },
+                    if discriminant == <#discriminant_type>::from(#discriminant) {
+                        return Self::#variant_name { #( #field_reads ),* };
+                    }
                 }
             }
             Fields::Unnamed(fields) => {
@@ -204,12 +213,16 @@ fn generate_enum_impl(e: &syn::DataEnum, attrs: &[Attribute]) -> (proc_macro2::T
                     quote! { <#field_type as PacketReadable>::read(stream) }
                 });
                 quote! {
-                    #discriminant => Self::#variant_name( #( #field_reads ),* ),
+                    if discriminant == <#discriminant_type>::from(#discriminant) {
+                        return Self::#variant_name( #( #field_reads ),* );
+                    }
                 }
             }
             Fields::Unit => {
                 quote! {
-                    #discriminant => Self::#variant_name,
+                    if discriminant == <#discriminant_type>::from(#discriminant) {
+                        return Self::#variant_name;
+                    }
                 }
             }
         }
@@ -224,11 +237,10 @@ fn generate_enum_impl(e: &syn::DataEnum, attrs: &[Attribute]) -> (proc_macro2::T
     };
     
     let read_impl = quote! {
+        // compared with == rather than matched, so non-integer discriminants (like strings) work too
         let discriminant = <#discriminant_type as PacketReadable>::read(stream);
-        match discriminant.into() {
-            #( #read_arms )*
-            val => panic!("Invalid enum discriminant: {}", val)
-        }
+        #( #read_arms )*
+        panic!("Invalid enum discriminant: {:?}", discriminant)
     };
 
     (write_impl, read_impl)
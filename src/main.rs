@@ -2,7 +2,10 @@ mod minecraft;
 mod utils;
 
 use minecraft::application::Client;
+use minecraft::registry::blocks::{BlockRegistry, set_block_registry};
+use minecraft::registry::builtin::{BuiltinRegistries, set_builtin_registries};
 
+use crate::utils::language::{Language, set_language};
 use crate::utils::logging::{get_logger, set_log_level};
 
 fn main() {
@@ -11,6 +14,44 @@ fn main() {
         set_log_level(3);
     }
 
+    // load the block states generated by the vanilla data generator, if available
+    match BlockRegistry::from_file("generated/reports/blocks.json") {
+        Ok(mut registry) => {
+            get_logger().info(format!("Loaded {} block states", registry.len()));
+            // from minecraft-data, every block except air and fluids is a full cube without them
+            if let Err(e) = registry
+                .load_collision_shapes_file("generated/minecraft-data/blockCollisionShapes.json")
+            {
+                get_logger().warn(format!("Block collision shapes were not loaded: {}", e));
+            }
+            // from minecraft-data too, blocks can not be broken without it
+            if let Err(e) = registry.load_hardness_file("generated/minecraft-data/blocks.json") {
+                get_logger().warn(format!("Block hardness was not loaded: {}", e));
+            }
+            set_block_registry(registry);
+        }
+        Err(e) => get_logger().warn(format!("Block registry was not loaded: {}", e)),
+    }
+    match BuiltinRegistries::from_file("generated/reports/registries.json") {
+        Ok(mut registries) => {
+            // default item components, only used for max stack sizes
+            if let Err(e) = registries.load_item_components_file("generated/reports/items.json") {
+                get_logger().warn(format!("Item components were not loaded: {}", e));
+            }
+            set_builtin_registries(registries);
+        }
+        Err(e) => get_logger().warn(format!("Built-in registries were not loaded: {}", e)),
+    }
+    // copied from `assets/minecraft/lang/en_us.json` of the client jar, translated messages
+    // are rendered as their translation keys without it
+    match Language::from_file("generated/reports/en_us.json") {
+        Ok(language) => {
+            get_logger().info(format!("Loaded {} translations", language.len()));
+            set_language(language);
+        }
+        Err(e) => get_logger().warn(format!("Language file was not loaded: {}", e)),
+    }
+
     // initialize the client
     let mut client = Client::new("localhost", 55916, "Entity1");
 
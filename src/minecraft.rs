@@ -1,4 +1,7 @@
 pub mod application;
+pub mod chunk;
+pub mod light;
+pub mod registry;
 pub mod packet;
 pub mod types;
 pub mod serverbound;
@@ -1,15 +1,29 @@
 // mod events;
-
-use std::io::Write;
+pub mod advancements;
+pub mod bot;
+pub mod chat;
+pub mod commands;
+pub mod digging;
+pub mod entities;
+pub mod interaction;
+pub mod inventory;
+pub mod pathfinding;
+pub mod physics;
+pub mod player_list;
+pub mod scheduler;
+pub mod world;
+
+use std::io::{self, ErrorKind, Write};
 // implements a connection loop
-use std::net::{TcpStream, ToSocketAddrs};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::minecraft::clientbound::status::deseralize_status_response;
 use crate::minecraft::packet::GenericPacket;
 use crate::minecraft::serverbound::configuration::ServerboundKnownPack;
-use crate::minecraft::types::{self, Optional};
+use crate::minecraft::types::Optional;
+use crate::utils::language::get_language;
 use crate::utils::logging::Logger;
 
 use super::clientbound::ClientboundConfigurationPacket;
@@ -24,9 +38,25 @@ use super::serverbound::ServerboundLoginPacket;
 use super::serverbound::ServerboundPlayPacket;
 use super::serverbound::ServerboundStatusPacket;
 
+use super::chunk::{DEFAULT_HEIGHT, DEFAULT_MIN_Y, set_biome_registry_size};
 use super::packet::{ConnectionState, set_compression_threshold};
-use super::serverbound::configuration::ClientMainHand;
+use super::registry::blocks::describe_block_state;
+use super::registry::builtin;
+use super::registry::dynamic::{self, RegistryStore};
+use super::serverbound::configuration::{ClientMainHand, SkinParts};
 use super::{PROTOCOL_VERSION, clientbound, serverbound};
+use advancements::AdvancementTree;
+use bot::{ActionHandle, ActionTracker, Bot};
+use chat::{Chat, ChatTypes};
+use commands::Commands;
+use digging::DiggingController;
+use entities::EntityTracker;
+use inventory::Inventory;
+use pathfinding::Navigator;
+use physics::PlayerPhysics;
+use player_list::PlayerList;
+use scheduler::TickScheduler;
+use world::World;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Location {
@@ -46,9 +76,18 @@ pub struct Velocity {
 
 #[derive(Debug)]
 pub struct ClientPlayStates {
-    location: Location,
-    velocity: Velocity,
+    physics: PlayerPhysics,
+    navigator: Navigator,
+    digging: DiggingController,
+    actions: ActionTracker,
     entity_id: i32,
+    world: World,
+    entities: EntityTracker,
+    player_list: PlayerList,
+    inventory: Inventory,
+    chat: Chat,
+    commands: Commands,
+    advancements: AdvancementTree,
 }
 
 #[derive(Debug)]
@@ -58,6 +97,7 @@ pub struct Client<'a> {
     hostname: String,
     port: u16,
     username: String,
+    uuid: Option<u128>, // assigned by the server on login
     state: ConnectionState,
 
     locale: String,              // String: max 16 characters
@@ -65,7 +105,13 @@ pub struct Client<'a> {
     main_hand: ClientMainHand,   // VarInt Enum: 0: left, 1: right
     allow_server_listings: bool, // Boolean: Servers usually list online players, this option should let you not show up in that list
 
+    registries: RegistryStore, // received in the configuration state
+
     play_states: Option<ClientPlayStates>,
+
+    scheduler: TickScheduler<Client<'a>>,
+    outgoing_packets: Vec<ServerboundPlayPacket>, // sent at the end of the current tick
+    disconnect_handle: Option<ActionHandle>,      // the connection is closed after the tick
 }
 
 static mut NOT_IMPLEMENTED_PACKET_IDS: Vec<i32> = Vec::new();
@@ -80,12 +126,17 @@ impl<'a> Client<'a> {
             hostname: hostname.to_string(),
             port: port,
             username: username.to_string(),
+            uuid: None,
             state: Self::INITIAL_STATE,
             locale: "en_GB".to_string(),
             view_distance: 8,
             main_hand: ClientMainHand::Right,
             allow_server_listings: true,
+            registries: RegistryStore::new(),
             play_states: None,
+            scheduler: TickScheduler::new(),
+            outgoing_packets: Vec::new(),
+            disconnect_handle: None,
         }
     }
 
@@ -157,14 +208,101 @@ impl<'a> Client<'a> {
         self.allow_server_listings
     }
 
+    pub fn get_uuid(&self) -> Option<u128> {
+        self.uuid
+    }
+
     pub fn get_location(&self) -> Option<&Location> {
         if let Some(play_states) = &self.play_states {
-            return Some(&play_states.location);
+            return Some(play_states.physics.get_location());
         } else {
             return None;
         }
     }
 
+    pub fn get_physics(&self) -> Option<&PlayerPhysics> {
+        self.play_states.as_ref().map(|play_states| &play_states.physics)
+    }
+
+    pub fn get_physics_mut(&mut self) -> Option<&mut PlayerPhysics> {
+        self.play_states.as_mut().map(|play_states| &mut play_states.physics)
+    }
+
+    pub fn get_navigator(&self) -> Option<&Navigator> {
+        self.play_states.as_ref().map(|play_states| &play_states.navigator)
+    }
+
+    pub fn get_navigator_mut(&mut self) -> Option<&mut Navigator> {
+        self.play_states.as_mut().map(|play_states| &mut play_states.navigator)
+    }
+
+    pub fn get_digging(&self) -> Option<&DiggingController> {
+        self.play_states.as_ref().map(|play_states| &play_states.digging)
+    }
+
+    pub fn get_digging_mut(&mut self) -> Option<&mut DiggingController> {
+        self.play_states.as_mut().map(|play_states| &mut play_states.digging)
+    }
+
+    pub fn get_scheduler(&self) -> &TickScheduler<Client<'a>> {
+        &self.scheduler
+    }
+
+    // callbacks and tasks can be added before joining, they run once the client is playing
+    pub fn get_scheduler_mut(&mut self) -> &mut TickScheduler<Client<'a>> {
+        &mut self.scheduler
+    }
+
+    // high level actions, meant to be used from tick callbacks
+    pub fn get_bot(&mut self) -> Bot<'_, 'a> {
+        Bot::new(self)
+    }
+
+    // queues a packet, it is sent at the end of the current tick
+    pub fn send_packet(&mut self, packet: ServerboundPlayPacket) {
+        self.outgoing_packets.push(packet);
+    }
+
+    pub fn get_world(&self) -> Option<&World> {
+        self.play_states.as_ref().map(|play_states| &play_states.world)
+    }
+
+    pub fn get_entities(&self) -> Option<&EntityTracker> {
+        self.play_states.as_ref().map(|play_states| &play_states.entities)
+    }
+
+    pub fn get_player_list(&self) -> Option<&PlayerList> {
+        self.play_states.as_ref().map(|play_states| &play_states.player_list)
+    }
+
+    pub fn get_inventory(&self) -> Option<&Inventory> {
+        self.play_states.as_ref().map(|play_states| &play_states.inventory)
+    }
+
+    pub fn get_commands(&self) -> Option<&Commands> {
+        self.play_states.as_ref().map(|play_states| &play_states.commands)
+    }
+
+    pub fn get_advancements(&self) -> Option<&AdvancementTree> {
+        self.play_states.as_ref().map(|play_states| &play_states.advancements)
+    }
+
+    pub fn get_registries(&self) -> &RegistryStore {
+        &self.registries
+    }
+
+    // min_y and height of a dimension type, the overworld's if it is not in the registry
+    fn get_dimension_bounds(&self, dimension_type: i32) -> (i32, i32) {
+        match self.registries.get_dimension_type(dimension_type) {
+            Some(dimension) => (dimension.get_min_y(), dimension.get_height()),
+            None => (DEFAULT_MIN_Y, DEFAULT_HEIGHT),
+        }
+    }
+
+    pub fn get_chat(&self) -> Option<&Chat> {
+        self.play_states.as_ref().map(|play_states| &play_states.chat)
+    }
+
     pub fn get_entity_id(&self) -> Option<&i32> {
         if let Some(play_states) = &self.play_states {
             return Some(&play_states.entity_id);
@@ -231,6 +369,7 @@ impl<'a> Client<'a> {
                     properties,
                 } => {
                     self.logger.info(format!("Login Success: {:?}", packet));
+                    self.uuid = Some(uuid.get_value());
                     // send login acknowledged packet and move on to configuration state
                     ServerboundLoginPacket::LoginAcknowledged.send(stream);
                     self.state = ConnectionState::Configuration;
@@ -292,7 +431,7 @@ impl<'a> Client<'a> {
             view_distance: self.get_view_distance().into(),
             chat_mode: serverbound::configuration::ClientChatMode::Enabled,
             chat_colors: true.into(),
-            skin_parts: 0x7F.into(),
+            skin_parts: SkinParts::all(),
             main_hand: self.get_main_hand(),
             text_filtering: false.into(),
             allow_server_listings: self.allows_server_listings().into(),
@@ -351,8 +490,15 @@ impl<'a> Client<'a> {
                     registry_id,
                     entries,
                 } => {
-                    self.logger
-                        .warn(format!("Ignored registry data packet: {:?}", registry_id));
+                    self.logger.info(format!(
+                        "Registry {:?} with {} entries",
+                        registry_id,
+                        entries.len()
+                    ));
+                    self.registries.apply_packet(&packet);
+                    if registry_id.to_string() == dynamic::BIOME {
+                        set_biome_registry_size(entries.len());
+                    }
                 }
                 ClientboundConfigurationPacket::RemoveResourcePack { uuid } => {
                     self.logger
@@ -379,22 +525,16 @@ impl<'a> Client<'a> {
                     self.logger.info(format!("Feature Flags: {:?}", flags));
                 }
                 ClientboundConfigurationPacket::UpdateTags { tags } => {
-                    self.logger.warn(format!(
-                        "Ignored UpdateTagsPacket with {} entries.",
-                        tags.len()
-                    ));
+                    self.logger
+                        .info(format!("Received tags of {} registries", tags.len()));
+                    self.registries.apply_packet(&packet);
                 }
                 ClientboundConfigurationPacket::KnownServerPacks { packs } => {
                     self.logger.info(format!("Known Server Packs: {:?}", packs));
 
-                    // respond with the default notchain response
+                    // claim no known packs, so the server sends the data of every registry entry
                     ServerboundConfigurationPacket::KnownClientPacks {
-                        packs: vec![ServerboundKnownPack {
-                            namespace: "minecraft".into(),
-                            id: "core".into(),
-                            version: "1.21.1".into(),
-                        }]
-                        .into(),
+                        packs: Vec::<ServerboundKnownPack>::new().into(),
                     }
                     .send(stream);
                 }
@@ -425,6 +565,18 @@ impl<'a> Client<'a> {
                 }
                 ClientboundPlayPacket::SetHeldItem { slot } => {
                     self.logger.info(format!("Held Slot Changed: {:?}", packet));
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.inventory.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::OpenScreen { .. }
+                | ClientboundPlayPacket::CloseContainer { .. }
+                | ClientboundPlayPacket::SetContainerContent { .. }
+                | ClientboundPlayPacket::SetContainerSlot { .. }
+                | ClientboundPlayPacket::SetContainerProperty { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.inventory.apply_packet(&packet);
+                    }
                 }
                 ClientboundPlayPacket::SpawnEntity {
                     entity_id,
@@ -437,7 +589,149 @@ impl<'a> Client<'a> {
                     data,
                     velocity,
                 } => {
-                    self.logger.info(format!("SpawnEntityPacket: {:?}", packet));
+                    self.logger.info(format!(
+                        "Spawned {} with ID={}: {:?}",
+                        builtin::describe(builtin::ENTITY_TYPE, entity_type.get_value()),
+                        entity_id.get_value(),
+                        packet
+                    ));
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.entities.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::BlockUpdate { location, block_id } => {
+                    self.logger.debug(format!(
+                        "Block Update: {} at {:?}",
+                        describe_block_state(block_id.get_value()),
+                        location
+                    ));
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.world.apply_packet(&packet);
+                        play_states.navigator.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::ChunkDataAndUpdateLight { .. }
+                | ClientboundPlayPacket::UpdateLight { .. }
+                | ClientboundPlayPacket::UnloadChunk { .. }
+                | ClientboundPlayPacket::UpdateSectionBlocks { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.world.apply_packet(&packet);
+                        play_states.navigator.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::Explosion { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.world.apply_packet(&packet);
+                        play_states.physics.apply_packet(&packet);
+                        play_states.navigator.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::SpawnExperienceOrb { .. }
+                | ClientboundPlayPacket::UpdateEntityPosition { .. }
+                | ClientboundPlayPacket::UpdateEntityPositionAndRotation { .. }
+                | ClientboundPlayPacket::UpdateEntityRotation { .. }
+                | ClientboundPlayPacket::TeleportEntity { .. }
+                | ClientboundPlayPacket::SetHeadRotation { .. }
+                | ClientboundPlayPacket::SetEntityMetadata { .. }
+                | ClientboundPlayPacket::SetPassengers { .. }
+                | ClientboundPlayPacket::RemoveEntities { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.entities.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::SetEntityVelocity { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        // knockback if the entity is the player
+                        play_states.physics.apply_packet(&packet);
+                        play_states.entities.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::PlayerChatMessage { .. }
+                | ClientboundPlayPacket::DisguisedChatMessage { .. }
+                | ClientboundPlayPacket::SystemChatMessage { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.chat.apply_packet(&packet);
+                        play_states.actions.apply_packet(&packet);
+                        if let Some(message) = play_states.chat.get_last_message() {
+                            self.logger.info(format!("[Chat] {}", message.get_text(get_language())));
+                        }
+                        if let Some(acknowledgement) = play_states.chat.take_acknowledgement() {
+                            acknowledgement.send(stream);
+                        }
+                    }
+                }
+                ClientboundPlayPacket::PlayerInfoUpdate { .. }
+                | ClientboundPlayPacket::PlayerInfoRemove { .. }
+                | ClientboundPlayPacket::SetTabListHeaderAndFooter { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.player_list.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::Commands { nodes, .. } => {
+                    self.logger
+                        .info(format!("Received {} command nodes", nodes.len()));
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.commands.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::UpdateAdvancements { .. }
+                | ClientboundPlayPacket::SelectAdvancementsTab { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.advancements.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::CommandSuggestionsResponse { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.commands.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::SetTickingState { .. }
+                | ClientboundPlayPacket::StepTick { .. } => {
+                    self.logger.info(format!("Ticking State: {:?}", packet));
+                    self.scheduler.apply_packet(&packet);
+                }
+                ClientboundPlayPacket::UpdateTags { tags } => {
+                    self.logger
+                        .info(format!("Received tags of {} registries", tags.len()));
+                    self.registries.update_tags(tags);
+                }
+                ClientboundPlayPacket::Respawn {
+                    dimension_type,
+                    dimention_name,
+                    ..
+                } => {
+                    self.logger.info(format!("Respawn: {:?}", packet));
+                    let (min_y, height) = self.get_dimension_bounds(dimension_type.get_value());
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        // entities of the previous dimension are never removed by the server
+                        if play_states.world.get_dimension_name() != dimention_name.to_string() {
+                            play_states.entities.clear();
+                        }
+                        play_states
+                            .world
+                            .set_dimension(&dimention_name.to_string(), min_y, height);
+                        play_states.physics.apply_packet(&packet);
+                        play_states.navigator.apply_packet(&packet);
+                        play_states.digging.apply_packet(&packet);
+                        play_states.actions.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::AcknowledgeBlockChange { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        // restores the blocks the server did not change as predicted
+                        play_states.world.apply_packet(&packet);
+                        play_states.actions.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::DamageEvent { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.actions.apply_packet(&packet);
+                    }
+                }
+                ClientboundPlayPacket::GameEvent { .. } => {
+                    if let Some(play_states) = self.play_states.as_mut() {
+                        play_states.digging.apply_packet(&packet);
+                    }
                 }
                 packet => {
                     let id = packet.get_id();
@@ -459,13 +753,103 @@ impl<'a> Client<'a> {
         }
     }
 
+    // runs one client tick if it is due
+    fn tick(&mut self, stream: &mut impl Write) {
+        let run_game_tick = match self.scheduler.start_tick(Instant::now()) {
+            Some(run_game_tick) => run_game_tick,
+            None => return,
+        };
+        if run_game_tick {
+            TickScheduler::run(self, |client| &mut client.scheduler);
+        }
+        // the player keeps moving while the server has frozen the game, like in vanilla
+        if let Some(play_states) = self.play_states.as_mut() {
+            play_states
+                .navigator
+                .tick(&play_states.world, &mut play_states.physics);
+            play_states.physics.tick(&play_states.world);
+            self.outgoing_packets
+                .extend(play_states.physics.take_packets());
+            play_states.digging.tick(
+                &mut play_states.world,
+                &play_states.inventory,
+                &play_states.physics,
+                &self.registries,
+            );
+            self.outgoing_packets
+                .extend(play_states.digging.take_packets());
+        }
+        for packet in std::mem::take(&mut self.outgoing_packets) {
+            packet.send(stream);
+        }
+        if let Some(play_states) = self.play_states.as_mut() {
+            play_states.actions.tick(
+                &play_states.world,
+                &play_states.navigator,
+                &play_states.digging,
+            );
+        }
+    }
+
+    // returns true when a packet can be read without waiting longer than the timeout,
+    // an error when the connection was closed
+    fn wait_for_packet(stream: &mut TcpStream, timeout: Duration) -> io::Result<bool> {
+        if timeout.is_zero() {
+            return Ok(false);
+        }
+        stream
+            .set_read_timeout(Some(timeout))
+            .expect("set_read_timeout call failed");
+        let available = match stream.peek(&mut [0u8; 1]) {
+            // the server closed the connection
+            Ok(0) => Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "the connection was closed by the server",
+            )),
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(false),
+            Err(e) => Err(e),
+        };
+        stream
+            .set_read_timeout(Some(Duration::from_secs(15)))
+            .expect("set_read_timeout call failed");
+        available
+    }
+
     fn play(&mut self, stream: &mut TcpStream) {
         assert_eq!(self.state, ConnectionState::Play);
 
         let mut bundle_packets: Vec<ClientboundPlayPacket> = Vec::new();
+        // packets between two bundle delimiters are processed together
+        let mut in_bundle = false;
 
         // play phase loop
         loop {
+            // run the tick if it is due, then wait for a packet until the next one
+            let timeout = self.scheduler.get_time_until_tick(Instant::now());
+            if timeout.is_zero() {
+                self.tick(stream);
+                if self.disconnect_handle.is_some() {
+                    self.logger
+                        .info(format!("Disconnecting from {}:{}", self.hostname, self.port));
+                    let _ = stream.shutdown(Shutdown::Both);
+                    self.state = ConnectionState::Handshaking;
+                    Bot::finish_disconnect(self);
+                    break;
+                }
+                continue;
+            }
+            match Self::wait_for_packet(stream, timeout) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    self.logger.error(format!("Connection Error: {}", e));
+                    self.state = ConnectionState::Handshaking;
+                    Bot::finish_disconnect(self);
+                    break;
+                }
+            }
+
             // read one packet from the stream
             let packet = ClientboundPlayPacket::recv(stream);
 
@@ -473,8 +857,11 @@ impl<'a> Client<'a> {
                 // packets that are bundled when processing
                 ClientboundPlayPacket::BundleDelimiter => {
                     self.logger.debug(format!("BundleDelimiterPacket"));
-                    self.process_play_bundle_packets(bundle_packets, stream);
-                    bundle_packets = Vec::new();
+                    if in_bundle {
+                        self.process_play_bundle_packets(bundle_packets, stream);
+                        bundle_packets = Vec::new();
+                    }
+                    in_bundle = !in_bundle;
                 }
 
                 ClientboundPlayPacket::HurtAnimation {
@@ -483,66 +870,33 @@ impl<'a> Client<'a> {
                 } => {
                     self.logger.info(format!("Hurt Animation: {:?}", packet));
                     if entity_id.get_value() == *self.get_entity_id().unwrap() {
-                        ServerboundPlayPacket::ChatMessage {
-                            message: "Ouch!".into(),
-                            timestamp: 0.into(),
-                            salt: 0.into(),
-                            signature: Optional::None,
-                            message_count: 0.into(),
-                            acknowledged: types::FixedSizeBitSet::<3>::empty(),
-                        }
-                        .send(stream);
+                        let play_states = self.play_states.as_mut().unwrap();
+                        play_states
+                            .chat
+                            .send_chat("Ouch!", play_states.commands.get_graph())
+                            .send(stream);
                     }
                 }
 
                 // packets that are not bundled when processing
-                ClientboundPlayPacket::SynchronizePlayerPosition {
-                    location,
-                    flags,
-                    teleport_id,
-                } => {
+                ClientboundPlayPacket::SynchronizePlayerPosition { .. } => {
                     let play_states = self.play_states.as_mut().unwrap();
-
-                    let flags_byte: i8 = flags.get_value();
-                    play_states.location.x = if (flags_byte & 0x01) == 0 {
-                        location.x.get_value()
-                    } else {
-                        play_states.location.x + location.x.get_value()
-                    };
-                    play_states.location.y = if (flags_byte & 0x02) == 0 {
-                        location.y.get_value()
-                    } else {
-                        play_states.location.y + location.y.get_value()
-                    };
-                    play_states.location.z = if (flags_byte & 0x04) == 0 {
-                        location.z.get_value()
-                    } else {
-                        play_states.location.z + location.z.get_value()
-                    };
-                    play_states.location.yaw = if (flags_byte & 0x08) == 0 {
-                        location.yaw.get_value()
-                    } else {
-                        play_states.location.yaw + location.yaw.get_value()
-                    };
-                    play_states.location.pitch = if (flags_byte & 0x10) == 0 {
-                        location.pitch.get_value()
-                    } else {
-                        play_states.location.pitch + location.pitch.get_value()
-                    };
-
-                    self.logger
-                        .info(format!("Teleported by server: {:?}", play_states.location));
-                    // send teleport confirmation packet
-                    ServerboundPlayPacket::ConfirmTeleportation {
-                        teleport_id: teleport_id.clone(),
+                    play_states.physics.apply_packet(&packet);
+                    self.logger.info(format!(
+                        "Teleported by server: {:?}",
+                        play_states.physics.get_location()
+                    ));
+                    // send teleport confirmation and the new position
+                    for packet in play_states.physics.take_packets() {
+                        packet.send(stream);
                     }
-                    .send(stream);
                 }
 
                 // excluded from bundle delimiter because the server closes the connection after this packet
                 ClientboundPlayPacket::Disconnect { reason } => {
                     self.logger.error(format!("Disconnected: {:?}", reason));
                     self.state = ConnectionState::Handshaking;
+                    Bot::finish_disconnect(self);
                     break;
                 }
 
@@ -564,10 +918,10 @@ impl<'a> Client<'a> {
                     reduced_debug_info: _,
                     enable_respawn_screen: _,
                     do_limited_crafting: _,
-                    dimension_type: _,
-                    dimension_name: _,
+                    dimension_type,
+                    dimension_name,
                     hashed_seed: _,
-                    game_mode: _,
+                    game_mode,
                     previous_game_mode: _,
                     is_debug: _,
                     is_flat: _,
@@ -577,20 +931,20 @@ impl<'a> Client<'a> {
                 } => {
                     self.logger
                         .info(format!("Successfully Logged In!: {:?}", packet));
+                    let (min_y, height) = self.get_dimension_bounds(dimension_type.get_value());
                     self.play_states = Some(ClientPlayStates {
-                        location: Location {
-                            x: 0.0,
-                            y: 0.0,
-                            z: 0.0,
-                            yaw: 180.0,
-                            pitch: 0.0,
-                        },
-                        velocity: Velocity {
-                            x: 0.0,
-                            y: 0.0,
-                            z: 0.0,
-                        },
+                        physics: PlayerPhysics::new(entity_id.get_value()),
+                        navigator: Navigator::default(),
+                        digging: DiggingController::new(*game_mode),
+                        actions: ActionTracker::new(self.uuid),
                         entity_id: (*entity_id).into(),
+                        world: World::new(&dimension_name.get_value(), min_y, height),
+                        entities: EntityTracker::new(),
+                        player_list: PlayerList::new(),
+                        inventory: Inventory::new(),
+                        chat: Chat::new(ChatTypes::from_registries(&self.registries)),
+                        commands: Commands::new(),
+                        advancements: AdvancementTree::new(),
                     });
                     self.view_distance = (*view_distance).get_value() as i8;
                 }
@@ -604,7 +958,11 @@ impl<'a> Client<'a> {
                 }
 
                 _ => {
-                    bundle_packets.push(packet);
+                    if in_bundle {
+                        bundle_packets.push(packet);
+                    } else {
+                        self.process_play_bundle_packets(vec![packet], stream);
+                    }
                 }
             }
         }
@@ -0,0 +1,294 @@
+// the advancement tree, built from update advancements packets
+use std::collections::HashMap;
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::{
+    Advancement, AdvancementDisplay, AdvancementFrameType, AdvancementProgressMapping,
+};
+use crate::minecraft::types;
+use crate::utils::language::get_language;
+use crate::utils::render_text_component_plain;
+
+#[derive(Debug, Clone)]
+pub struct AdvancementNode {
+    id: String,
+    parent: Option<String>,
+    children: Vec<String>,
+    display: Option<AdvancementDisplay>, // advancements without display are not shown in any tab
+    requirements: Vec<Vec<String>>,      // every group needs at least one of its criteria done
+    sends_telemetry_data: bool,
+    criteria: HashMap<String, Option<i64>>, // criterion name -> milliseconds since the epoch it was achieved at
+}
+
+impl AdvancementNode {
+    fn new(id: &str, advancement: &Advancement) -> Self {
+        let parent = match &advancement.parent_id {
+            types::Optional::Some(parent) => Some(parent.to_string()),
+            types::Optional::None => None,
+        };
+        let display = match &advancement.display {
+            types::Optional::Some(display) => Some(display.clone()),
+            types::Optional::None => None,
+        };
+        let requirements = advancement
+            .requirements
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|criterion| criterion.get_value())
+                    .collect()
+            })
+            .collect();
+        Self {
+            id: id.to_string(),
+            parent,
+            children: Vec::new(),
+            display,
+            requirements,
+            sends_telemetry_data: advancement.sends_telemetry_data.get_value(),
+            criteria: HashMap::new(),
+        }
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    pub fn get_children(&self) -> &Vec<String> {
+        &self.children
+    }
+
+    // roots are the tabs of the advancements screen
+    pub fn is_root(&self) -> bool {
+        self.parent.is_none()
+    }
+
+    pub fn get_display(&self) -> Option<&AdvancementDisplay> {
+        self.display.as_ref()
+    }
+
+    pub fn get_title_text(&self) -> Option<String> {
+        let display = self.display.as_ref()?;
+        Some(render_text_component_plain(
+            &display.title.to_json(),
+            get_language(),
+        ))
+    }
+
+    pub fn get_description_text(&self) -> Option<String> {
+        let display = self.display.as_ref()?;
+        Some(render_text_component_plain(
+            &display.description.to_json(),
+            get_language(),
+        ))
+    }
+
+    pub fn get_frame_type(&self) -> Option<AdvancementFrameType> {
+        self.display.as_ref().map(|display| display.frame_type)
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.display
+            .as_ref()
+            .is_some_and(|display| display.flags.get_value() & 0x04 != 0)
+    }
+
+    pub fn get_requirements(&self) -> &Vec<Vec<String>> {
+        &self.requirements
+    }
+
+    pub fn sends_telemetry_data(&self) -> bool {
+        self.sends_telemetry_data
+    }
+
+    pub fn get_criteria(&self) -> &HashMap<String, Option<i64>> {
+        &self.criteria
+    }
+
+    // milliseconds since the epoch, None if the criterion is not done
+    pub fn get_criterion_date(&self, criterion: &str) -> Option<i64> {
+        self.criteria.get(criterion).copied().flatten()
+    }
+
+    pub fn is_criterion_done(&self, criterion: &str) -> bool {
+        self.get_criterion_date(criterion).is_some()
+    }
+
+    // the number of requirement groups with at least one criterion done
+    pub fn get_completed_requirements(&self) -> usize {
+        self.requirements
+            .iter()
+            .filter(|group| {
+                group
+                    .iter()
+                    .any(|criterion| self.is_criterion_done(criterion))
+            })
+            .count()
+    }
+
+    pub fn is_done(&self) -> bool {
+        !self.requirements.is_empty()
+            && self.get_completed_requirements() == self.requirements.len()
+    }
+
+    // the date the last criterion was done at, like the tooltip of the advancements screen
+    pub fn get_done_date(&self) -> Option<i64> {
+        if !self.is_done() {
+            return None;
+        }
+        self.criteria.values().filter_map(|date| *date).max()
+    }
+
+    fn set_progress(&mut self, progress: &AdvancementProgressMapping) {
+        // the server sends every criterion of the advancement, not only the updated ones
+        self.criteria = progress
+            .criteria
+            .iter()
+            .map(|criterion| {
+                let date = match &criterion.date_of_achieving {
+                    types::Optional::Some(date) => Some(date.get_value()),
+                    types::Optional::None => None,
+                };
+                (criterion.criterion.get_value(), date)
+            })
+            .collect();
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AdvancementTree {
+    advancements: HashMap<String, AdvancementNode>,
+    selected_tab: Option<String>,
+}
+
+impl AdvancementTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&AdvancementNode> {
+        self.advancements.get(id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &AdvancementNode> {
+        self.advancements.values()
+    }
+
+    pub fn get_roots(&self) -> Vec<&AdvancementNode> {
+        self.iter()
+            .filter(|advancement| advancement.is_root())
+            .collect()
+    }
+
+    pub fn get_children(&self, id: &str) -> Vec<&AdvancementNode> {
+        match self.get(id) {
+            Some(advancement) => advancement
+                .children
+                .iter()
+                .filter_map(|child| self.get(child))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_done(&self) -> Vec<&AdvancementNode> {
+        self.iter()
+            .filter(|advancement| advancement.is_done())
+            .collect()
+    }
+
+    // the tab the server asked the client to open
+    pub fn get_selected_tab(&self) -> Option<&str> {
+        self.selected_tab.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.advancements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advancements.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.advancements.clear();
+        self.selected_tab = None;
+    }
+
+    fn add(&mut self, id: &str, advancement: &Advancement) {
+        let mut node = AdvancementNode::new(id, advancement);
+        // the progress of a replaced advancement is kept
+        if let Some(previous) = self.advancements.remove(id) {
+            node.criteria = previous.criteria;
+        }
+        // the children may have been sent before their parent
+        node.children = self
+            .iter()
+            .filter(|advancement| advancement.get_parent() == Some(id))
+            .map(|advancement| advancement.id.clone())
+            .collect();
+        if let Some(parent) = node.get_parent()
+            && let Some(parent) = self.advancements.get_mut(parent)
+            && !parent.children.iter().any(|child| child == id)
+        {
+            parent.children.push(id.to_string());
+        }
+        self.advancements.insert(id.to_string(), node);
+    }
+
+    // removing an advancement also removes its descendants
+    fn remove(&mut self, id: &str) {
+        let advancement = match self.advancements.remove(id) {
+            Some(advancement) => advancement,
+            None => return,
+        };
+        if let Some(parent) = advancement.get_parent()
+            && let Some(parent) = self.advancements.get_mut(parent)
+        {
+            parent.children.retain(|child| child != id);
+        }
+        for child in &advancement.children {
+            self.remove(child);
+        }
+    }
+
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::UpdateAdvancements {
+                reset,
+                advancements,
+                removed,
+                progress,
+            } => {
+                if reset.get_value() {
+                    self.advancements.clear();
+                }
+                for id in removed.iter() {
+                    self.remove(&id.to_string());
+                }
+                for mapping in advancements.iter() {
+                    self.add(&mapping.id.to_string(), &mapping.advancement);
+                }
+                for progress in progress.iter() {
+                    if let Some(advancement) = self.advancements.get_mut(&progress.id.to_string()) {
+                        advancement.set_progress(progress);
+                    }
+                }
+                true
+            }
+            ClientboundPlayPacket::SelectAdvancementsTab { id } => {
+                self.selected_tab = match id {
+                    types::Optional::Some(id) => Some(id.to_string()),
+                    types::Optional::None => None,
+                };
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,667 @@
+// high level actions on top of the client, every action returns a handle that completes
+// when the server confirms the effect (or when the packets were sent, if the server never does)
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use crate::minecraft::chunk::AIR_BLOCK_STATE;
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::GameMode;
+use crate::minecraft::serverbound::ServerboundPlayPacket;
+use crate::minecraft::serverbound::play::{
+    BlockFace, ClientStatusAction, HandEnum, InteractionEnum,
+};
+use crate::minecraft::types;
+
+use super::Client;
+use super::digging::{DiggingController, get_block_hardness};
+use super::interaction::{self, BlockHit, DEFAULT_BLOCK_REACH};
+use super::pathfinding::{BlockPos, NavigationState, Navigator};
+use super::world::World;
+
+// ticks to wait for the server before an action fails
+pub const DEFAULT_ACTION_TIMEOUT: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    NotPlaying,   // the client has not joined the game yet
+    TimedOut,     // the server did not confirm the action in time
+    Rejected,     // the server acknowledged the action without applying it
+    Unreachable,  // no path to the goal was found
+    Cancelled,    // replaced by another action of the same kind
+    Disconnected, // the connection was closed before the action was confirmed
+    InvalidSlot,  // the slot does not exist
+    UnknownBlock, // the hardness of the block is not known, the block data was not loaded
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionStatus {
+    Pending,
+    Completed,
+    Failed(ActionError),
+}
+
+#[derive(Debug)]
+struct ActionState {
+    status: ActionStatus,
+    waker: Option<Waker>,
+}
+
+// shared between the bot and the action tracker, can be polled every tick or awaited
+#[derive(Debug, Clone)]
+pub struct ActionHandle {
+    state: Rc<RefCell<ActionState>>,
+}
+
+impl ActionHandle {
+    fn new(status: ActionStatus) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ActionState {
+                status,
+                waker: None,
+            })),
+        }
+    }
+
+    fn failed(error: ActionError) -> Self {
+        Self::new(ActionStatus::Failed(error))
+    }
+
+    pub fn get_status(&self) -> ActionStatus {
+        self.state.borrow().status
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.get_status() == ActionStatus::Pending
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.get_status() == ActionStatus::Completed
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.get_status(), ActionStatus::Failed(_))
+    }
+
+    // only the first result is kept
+    fn finish(&self, status: ActionStatus) {
+        let mut state = self.state.borrow_mut();
+        if state.status != ActionStatus::Pending {
+            return;
+        }
+        state.status = status;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn complete(&self) {
+        self.finish(ActionStatus::Completed);
+    }
+
+    fn fail(&self, error: ActionError) {
+        self.finish(ActionStatus::Failed(error));
+    }
+}
+
+impl Future for ActionHandle {
+    type Output = Result<(), ActionError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.borrow_mut();
+        match state.status {
+            ActionStatus::Pending => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            ActionStatus::Completed => Poll::Ready(Ok(())),
+            ActionStatus::Failed(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
+
+// what completes an action
+#[derive(Debug, Clone)]
+enum Confirmation {
+    Sent, // the server does not confirm the action, done once the packets are sent
+    ChatEcho {
+        message: String,
+    }, // the server sends the message back to the player
+    Arrival {
+        goal: BlockPos,
+    },
+    BlockChange {
+        location: types::Position,
+        previous: i32,
+        sequence: i32,
+    },
+    Dig {
+        location: types::Position,
+        previous: i32,
+        predicted: bool, // the block was broken locally before the server acknowledged it
+    },
+    Acknowledgement {
+        sequence: i32,
+    },
+    Damage {
+        entity_id: i32,
+    },
+    Respawn,
+}
+
+#[derive(Debug)]
+struct PendingAction {
+    handle: ActionHandle,
+    confirmation: Confirmation,
+    remaining_ticks: Option<u32>,
+    acknowledged: bool, // the block change was acknowledged, checked on the next tick
+}
+
+// completes the actions of the bot from play packets and the state of the client
+#[derive(Debug)]
+pub struct ActionTracker {
+    uuid: Option<u128>, // of the player, to recognize its own chat messages
+    pending: Vec<PendingAction>,
+}
+
+impl ActionTracker {
+    pub fn new(uuid: Option<u128>) -> Self {
+        Self {
+            uuid,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn get_pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn track(&mut self, confirmation: Confirmation, timeout: Option<u32>) -> ActionHandle {
+        let handle = ActionHandle::new(ActionStatus::Pending);
+        self.pending.push(PendingAction {
+            handle: handle.clone(),
+            confirmation,
+            remaining_ticks: timeout,
+            acknowledged: false,
+        });
+        handle
+    }
+
+    pub fn fail_all(&mut self, error: ActionError) {
+        for action in self.pending.drain(..) {
+            action.handle.fail(error);
+        }
+    }
+
+    // runs after the packets of the tick were sent
+    pub fn tick(&mut self, world: &World, navigator: &Navigator, digging: &DiggingController) {
+        for action in self.pending.iter_mut() {
+            match &mut action.confirmation {
+                Confirmation::Sent => action.handle.complete(),
+                // another walk replaced the goal
+                Confirmation::Arrival { goal } if navigator.get_goal() != Some(goal) => {
+                    action.handle.fail(ActionError::Cancelled);
+                }
+                Confirmation::Arrival { .. } => match navigator.get_state() {
+                    NavigationState::Navigating => {}
+                    NavigationState::Arrived => action.handle.complete(),
+                    NavigationState::Failed => action.handle.fail(ActionError::Unreachable),
+                    NavigationState::Idle => action.handle.fail(ActionError::Cancelled),
+                },
+                // predicted changes are kept or rolled back by the world when acknowledged
+                Confirmation::BlockChange {
+                    location, previous, ..
+                } if action.acknowledged => {
+                    if world
+                        .get_block(location)
+                        .is_some_and(|state| state != *previous)
+                    {
+                        action.handle.complete();
+                    } else {
+                        action.handle.fail(ActionError::Rejected);
+                    }
+                }
+                Confirmation::Dig {
+                    location,
+                    previous,
+                    predicted,
+                } => {
+                    if world.is_predicted(location) {
+                        *predicted = true;
+                    } else if world
+                        .get_block(location)
+                        .is_some_and(|state| state != *previous)
+                    {
+                        action.handle.complete();
+                    } else if *predicted {
+                        // the server restored the block
+                        action.handle.fail(ActionError::Rejected);
+                    } else if digging.get_target() != Some(location) {
+                        action.handle.fail(ActionError::Cancelled);
+                    }
+                }
+                _ => {}
+            }
+            if let Some(remaining_ticks) = action.remaining_ticks.as_mut() {
+                if *remaining_ticks == 0 {
+                    action.handle.fail(ActionError::TimedOut);
+                }
+                *remaining_ticks = remaining_ticks.saturating_sub(1);
+            }
+        }
+        self.pending.retain(|action| action.handle.is_pending());
+    }
+
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::AcknowledgeBlockChange { sequence_id } => {
+                // acknowledges every change up to the sequence number
+                for action in self.pending.iter_mut() {
+                    match action.confirmation {
+                        Confirmation::BlockChange { sequence, .. }
+                            if sequence <= sequence_id.get_value() =>
+                        {
+                            action.acknowledged = true;
+                        }
+                        Confirmation::Acknowledgement { sequence }
+                            if sequence <= sequence_id.get_value() =>
+                        {
+                            action.handle.complete();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            ClientboundPlayPacket::PlayerChatMessage {
+                sender, message, ..
+            } => {
+                if self.uuid != Some(sender.get_value()) {
+                    return true;
+                }
+                for action in self.pending.iter() {
+                    if let Confirmation::ChatEcho { message: sent } = &action.confirmation
+                        && *sent == message.get_value()
+                    {
+                        action.handle.complete();
+                        break;
+                    }
+                }
+            }
+            ClientboundPlayPacket::DamageEvent { entity_id, .. } => {
+                for action in self.pending.iter() {
+                    if let Confirmation::Damage { entity_id: target } = action.confirmation
+                        && target == entity_id.get_value()
+                    {
+                        action.handle.complete();
+                    }
+                }
+            }
+            ClientboundPlayPacket::Respawn { .. } => {
+                for action in self.pending.iter() {
+                    if let Confirmation::Respawn = action.confirmation {
+                        action.handle.complete();
+                    }
+                }
+            }
+            _ => return false,
+        }
+        self.pending.retain(|action| action.handle.is_pending());
+        true
+    }
+}
+
+// a façade over the client, get one with `Client::get_bot` from a tick callback
+pub struct Bot<'c, 'a> {
+    client: &'c mut Client<'a>,
+}
+
+impl<'c, 'a> Bot<'c, 'a> {
+    pub fn new(client: &'c mut Client<'a>) -> Self {
+        Self { client }
+    }
+
+    pub fn get_client(&mut self) -> &mut Client<'a> {
+        self.client
+    }
+
+    // sends a chat message or a command (starting with '/'), messages complete when the server
+    // sends them back, commands once they are sent
+    pub fn chat(&mut self, message: &str) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let packet = play_states
+            .chat
+            .send_chat(message, play_states.commands.get_graph());
+        let confirmation = match &packet {
+            ServerboundPlayPacket::ChatMessage { message, .. } => Confirmation::ChatEcho {
+                message: message.get_value(),
+            },
+            _ => Confirmation::Sent,
+        };
+        let handle = play_states
+            .actions
+            .track(confirmation, Some(DEFAULT_ACTION_TIMEOUT));
+        self.client.send_packet(packet);
+        handle
+    }
+
+    // turns the head towards the point, the rotation is sent with the next movement packet
+    pub fn look_at(&mut self, x: f64, y: f64, z: f64) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let location = *play_states.physics.get_location();
+        let dx = x - location.x;
+        let dy = y - (location.y + play_states.physics.get_eye_height());
+        let dz = z - location.z;
+        let horizontal = (dx * dx + dz * dz).sqrt();
+        let yaw = (-dx).atan2(dz).to_degrees() as f32;
+        let pitch = (-dy).atan2(horizontal).to_degrees() as f32;
+        play_states.physics.set_rotation(yaw, pitch);
+        play_states.actions.track(Confirmation::Sent, None)
+    }
+
+    // walks to the block using the navigator, completes when the player stands in it
+    pub fn walk_to(&mut self, position: &types::Position) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let goal = BlockPos::from(position);
+        play_states.navigator.walk_to(goal);
+        play_states
+            .actions
+            .track(Confirmation::Arrival { goal }, None)
+    }
+
+    // breaks the block with the held item over as many ticks as it takes, completes when the
+    // server acknowledges the broken block, fails if the digging is aborted or replaced
+    pub fn dig(&mut self, position: &types::Position, face: BlockFace) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        // the break time can not be known without the hardness, blocks are broken at once in
+        // creative mode
+        if play_states.digging.get_game_mode() != GameMode::Creative
+            && let Some(state) = play_states.world.get_block_state(position)
+            && get_block_hardness(state.get_name()).is_none()
+        {
+            self.client.logger.warn(format!(
+                "Can not dig {}, its hardness is not known",
+                state.to_state_string()
+            ));
+            return ActionHandle::failed(ActionError::UnknownBlock);
+        }
+        let previous = play_states
+            .world
+            .get_block(position)
+            .unwrap_or(AIR_BLOCK_STATE);
+        play_states.digging.start(position, face);
+        play_states.actions.track(
+            Confirmation::Dig {
+                location: *position,
+                previous,
+                predicted: false,
+            },
+            None,
+        )
+    }
+
+    // stops breaking the block, the pending `dig` fails with `Cancelled`
+    pub fn stop_digging(&mut self) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        play_states.digging.abort();
+        play_states.actions.track(Confirmation::Sent, None)
+    }
+
+    // right clicks the block with the item, the placed block is predicted in the world,
+    // returns the sequence number the server acknowledges the click with
+    fn send_use_item_on(&mut self, hand: HandEnum, hit: &BlockHit) -> Option<i32> {
+        let client = &mut *self.client;
+        let play_states = client.play_states.as_mut()?;
+        let item = match hand {
+            HandEnum::MainHand => play_states.inventory.get_held_item(),
+            HandEnum::OffHand => play_states.inventory.get_offhand_item(),
+        };
+        let placement = match play_states.digging.get_game_mode() {
+            GameMode::Survival | GameMode::Creative => interaction::predict_placement(
+                &play_states.world,
+                &client.registries,
+                &play_states.physics,
+                hit,
+                item,
+            ),
+            GameMode::Adventure | GameMode::Spectator => None,
+        };
+        let sequence = play_states.world.next_sequence();
+        if let Some((position, state)) = placement {
+            play_states.world.predict_block(&position, state, sequence);
+        }
+        let cursor = hit.get_cursor();
+        client.send_packet(ServerboundPlayPacket::UseItemOn {
+            hand,
+            location: *hit.get_position(),
+            face: hit.get_face().get_id().into(),
+            cursor_position: types::FloatVec3 {
+                x: cursor.0.into(),
+                y: cursor.1.into(),
+                z: cursor.2.into(),
+            },
+            inside_block: hit.is_inside_block().into(),
+            sequence: sequence.into(),
+        });
+        client.send_packet(ServerboundPlayPacket::SwingArm { hand });
+        Some(sequence)
+    }
+
+    // uses the item on the block to place a block, completes when the server acknowledges
+    // a change of the block next to the face
+    fn place_with(&mut self, hand: HandEnum, hit: &BlockHit) -> ActionHandle {
+        let play_states = match self.client.play_states.as_ref() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let location =
+            interaction::get_placement_position(&play_states.world, &self.client.registries, hit);
+        let previous = play_states
+            .world
+            .get_block(&location)
+            .unwrap_or(AIR_BLOCK_STATE);
+        let sequence = match self.send_use_item_on(hand, hit) {
+            Some(sequence) => sequence,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let confirmation = Confirmation::BlockChange {
+            location,
+            previous,
+            sequence,
+        };
+        match self.client.play_states.as_mut() {
+            Some(play_states) => play_states
+                .actions
+                .track(confirmation, Some(DEFAULT_ACTION_TIMEOUT)),
+            None => ActionHandle::failed(ActionError::NotPlaying),
+        }
+    }
+
+    // places the held block against the face of the given block, the cursor is the clicked
+    // point on the face from 0 to 1, completes when the server acknowledges the placed block
+    pub fn place_block(
+        &mut self,
+        against: &types::Position,
+        face: BlockFace,
+        cursor: (f32, f32, f32),
+    ) -> ActionHandle {
+        let hit = BlockHit::new(*against, face, cursor, false);
+        self.place_with(HandEnum::MainHand, &hit)
+    }
+
+    // turns towards the block and places the held block against the face that is looked at,
+    // fails with `Unreachable` if the block is out of reach or hidden by another block
+    pub fn place(&mut self, against: &types::Position) -> ActionHandle {
+        let play_states = match self.client.play_states.as_ref() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let hit = match interaction::aim_at_block(
+            &play_states.world,
+            &play_states.physics,
+            against,
+            DEFAULT_BLOCK_REACH,
+        ) {
+            Some(hit) => hit,
+            None => return ActionHandle::failed(ActionError::Unreachable),
+        };
+        let (x, y, z) = hit.get_location();
+        self.look_at(x, y, z);
+        self.place_with(HandEnum::MainHand, &hit)
+    }
+
+    // uses the item in the hand on the block under the crosshair, or in the air if there is
+    // none, completes when the server acknowledges the use, or the placed block when the item
+    // places one
+    pub fn use_item(&mut self, hand: HandEnum) -> ActionHandle {
+        let client = &mut *self.client;
+        let play_states = match client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let hit = interaction::get_target_block(
+            &play_states.world,
+            &play_states.physics,
+            DEFAULT_BLOCK_REACH,
+        );
+        if let Some(hit) = hit {
+            let placed = interaction::predict_placement(
+                &play_states.world,
+                &client.registries,
+                &play_states.physics,
+                &hit,
+                match hand {
+                    HandEnum::MainHand => play_states.inventory.get_held_item(),
+                    HandEnum::OffHand => play_states.inventory.get_offhand_item(),
+                },
+            );
+            let is_interactable = play_states
+                .world
+                .get_block(hit.get_position())
+                .is_some_and(interaction::is_interactable);
+            if placed.is_some() {
+                return self.place_with(hand, &hit);
+            }
+            let sequence = self.send_use_item_on(hand, &hit);
+            if is_interactable && let Some(sequence) = sequence {
+                // the block may not change, e.g. when opening a chest
+                return match self.client.play_states.as_mut() {
+                    Some(play_states) => play_states.actions.track(
+                        Confirmation::Acknowledgement { sequence },
+                        Some(DEFAULT_ACTION_TIMEOUT),
+                    ),
+                    None => ActionHandle::failed(ActionError::NotPlaying),
+                };
+            }
+            // the item is used in the air after the server ignores the click on the block
+        }
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let location = *play_states.physics.get_location();
+        let sequence = play_states.world.next_sequence();
+        let handle = play_states.actions.track(
+            Confirmation::Acknowledgement { sequence },
+            Some(DEFAULT_ACTION_TIMEOUT),
+        );
+        self.client.send_packet(ServerboundPlayPacket::UseItem {
+            hand,
+            sequence: sequence.into(),
+            yaw: location.yaw.into(),
+            pitch: location.pitch.into(),
+        });
+        handle
+    }
+
+    // attacks the entity with the held item, completes when the entity takes damage
+    pub fn attack(&mut self, entity_id: i32) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let is_sneaking = play_states.physics.is_sneaking();
+        let handle = play_states.actions.track(
+            Confirmation::Damage { entity_id },
+            Some(DEFAULT_ACTION_TIMEOUT),
+        );
+        self.client.send_packet(ServerboundPlayPacket::Interact {
+            entity_id: entity_id.into(),
+            interaction: InteractionEnum::Attack,
+            is_sneaking: is_sneaking.into(),
+        });
+        self.client.send_packet(ServerboundPlayPacket::SwingArm {
+            hand: HandEnum::MainHand,
+        });
+        handle
+    }
+
+    // selects a hotbar slot (0-8), the server does not confirm it
+    pub fn select_hotbar(&mut self, slot: usize) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let packet = match play_states.inventory.select_slot(slot) {
+            Some(packet) => packet,
+            None => return ActionHandle::failed(ActionError::InvalidSlot),
+        };
+        let handle = play_states.actions.track(Confirmation::Sent, None);
+        self.client.send_packet(packet);
+        handle
+    }
+
+    // respawns after dying, completes when the server sends the respawn packet
+    pub fn respawn(&mut self) -> ActionHandle {
+        let play_states = match self.client.play_states.as_mut() {
+            Some(play_states) => play_states,
+            None => return ActionHandle::failed(ActionError::NotPlaying),
+        };
+        let handle = play_states
+            .actions
+            .track(Confirmation::Respawn, Some(DEFAULT_ACTION_TIMEOUT));
+        self.client
+            .send_packet(ServerboundPlayPacket::ClientStatus {
+                action: ClientStatusAction::PerformRespawn,
+            });
+        handle
+    }
+
+    // closes the connection at the end of the tick, pending actions fail
+    pub fn disconnect(&mut self) -> ActionHandle {
+        if self.client.play_states.is_none() {
+            return ActionHandle::failed(ActionError::NotPlaying);
+        }
+        let handle = self
+            .client
+            .disconnect_handle
+            .get_or_insert_with(|| ActionHandle::new(ActionStatus::Pending));
+        handle.clone()
+    }
+
+    // called by the client once the connection is closed
+    pub(super) fn finish_disconnect(client: &mut Client<'a>) {
+        if let Some(play_states) = client.play_states.as_mut() {
+            play_states.actions.fail_all(ActionError::Disconnected);
+        }
+        if let Some(handle) = client.disconnect_handle.take() {
+            handle.complete();
+        }
+    }
+}
@@ -0,0 +1,600 @@
+// chat messages received from the server, and the state needed to send new ones
+use std::collections::VecDeque;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::{ChatType, ChatTypeDecoration, ChatTypeParameter};
+use crate::minecraft::registry::dynamic::{
+    CHAT_TYPE, InlineRegistryValue, RegistryStore, RegistryValue,
+};
+use crate::minecraft::serverbound::ServerboundPlayPacket;
+use crate::minecraft::types;
+use crate::utils::language::Language;
+use crate::utils::render_text_component_plain;
+
+use super::commands::CommandGraph;
+
+// longer messages are kicked by the server
+pub const MAX_MESSAGE_LENGTH: usize = 256;
+// number of received messages kept in the history
+const MAX_HISTORY_SIZE: usize = 100;
+// number of signed messages the last seen acknowledgement covers
+const LAST_SEEN_SIZE: usize = 20;
+// the server kicks clients that do not acknowledge messages for too long
+const ACKNOWLEDGE_THRESHOLD: i32 = 64;
+
+const SENDER_AND_CONTENT: [ChatTypeParameter; 2] =
+    [ChatTypeParameter::Sender, ChatTypeParameter::Content];
+const TARGET_AND_CONTENT: [ChatTypeParameter; 2] =
+    [ChatTypeParameter::Target, ChatTypeParameter::Content];
+const TEAM_PARAMETERS: [ChatTypeParameter; 3] = [
+    ChatTypeParameter::Target,
+    ChatTypeParameter::Sender,
+    ChatTypeParameter::Content,
+];
+
+// chat types of the vanilla data pack, the server does not send the data of known packs
+// (name, translation key, parameters, gray and italic)
+const VANILLA_CHAT_TYPES: [(&str, &str, &[ChatTypeParameter], bool); 7] = [
+    (
+        "minecraft:chat",
+        "chat.type.text",
+        &SENDER_AND_CONTENT,
+        false,
+    ),
+    (
+        "minecraft:say_command",
+        "chat.type.announcement",
+        &SENDER_AND_CONTENT,
+        false,
+    ),
+    (
+        "minecraft:msg_command_incoming",
+        "commands.message.display.incoming",
+        &SENDER_AND_CONTENT,
+        true,
+    ),
+    (
+        "minecraft:msg_command_outgoing",
+        "commands.message.display.outgoing",
+        &TARGET_AND_CONTENT,
+        true,
+    ),
+    (
+        "minecraft:team_msg_command_incoming",
+        "chat.type.team.text",
+        &TEAM_PARAMETERS,
+        false,
+    ),
+    (
+        "minecraft:team_msg_command_outgoing",
+        "chat.type.team.sent",
+        &TEAM_PARAMETERS,
+        false,
+    ),
+    (
+        "minecraft:emote_command",
+        "chat.type.emote",
+        &SENDER_AND_CONTENT,
+        false,
+    ),
+];
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// a random salt for a sent message, it only has to be unique
+fn new_salt() -> i64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_i64(now_millis());
+    hasher.finish() as i64
+}
+
+fn parse_parameter(name: &str) -> Option<ChatTypeParameter> {
+    match name {
+        "sender" => Some(ChatTypeParameter::Sender),
+        "target" => Some(ChatTypeParameter::Target),
+        "content" => Some(ChatTypeParameter::Content),
+        _ => None,
+    }
+}
+
+// how a chat type formats a message, e.g. "<%s> %s" for "chat.type.text"
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatDecoration {
+    translation_key: String,
+    parameters: Vec<ChatTypeParameter>,
+    style: Map<String, JsonValue>, // the style fields of a text component, e.g. "color"
+}
+
+impl ChatDecoration {
+    pub fn new(
+        translation_key: &str,
+        parameters: Vec<ChatTypeParameter>,
+        style: Map<String, JsonValue>,
+    ) -> Self {
+        Self {
+            translation_key: translation_key.to_string(),
+            parameters,
+            style,
+        }
+    }
+
+    // the "chat" decoration of a chat_type registry entry, converted from NBT
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        let chat = value.get("chat")?;
+        let mut parameters = Vec::new();
+        if let Some(names) = chat.get("parameters").and_then(|p| p.as_array()) {
+            for name in names {
+                parameters.push(parse_parameter(name.as_str()?)?);
+            }
+        }
+        let style = match chat.get("style").and_then(|s| s.as_object()) {
+            Some(style) => style.clone(),
+            None => Map::new(),
+        };
+        Some(Self::new(
+            chat.get("translation_key")?.as_str()?,
+            parameters,
+            style,
+        ))
+    }
+
+    pub fn from_packet(decoration: &ChatTypeDecoration) -> Self {
+        let style = match decoration.style.to_json() {
+            JsonValue::Object(style) => style,
+            _ => Map::new(),
+        };
+        Self::new(
+            &decoration.translation_key.get_value(),
+            decoration.parameters.to_vec(),
+            style,
+        )
+    }
+
+    fn vanilla(name: &str) -> Option<Self> {
+        let (_, key, parameters, is_gray_italic) =
+            VANILLA_CHAT_TYPES.iter().find(|(n, ..)| *n == name)?;
+        let mut style = Map::new();
+        if *is_gray_italic {
+            style.insert("color".to_string(), JsonValue::from("gray"));
+            style.insert("italic".to_string(), JsonValue::from(true));
+        }
+        Some(Self::new(key, parameters.to_vec(), style))
+    }
+
+    pub fn get_translation_key(&self) -> &str {
+        &self.translation_key
+    }
+
+    pub fn get_parameters(&self) -> &Vec<ChatTypeParameter> {
+        &self.parameters
+    }
+
+    pub fn get_style(&self) -> &Map<String, JsonValue> {
+        &self.style
+    }
+
+    // builds the text component shown in the chat, a missing target is replaced by an empty text
+    pub fn decorate(
+        &self,
+        sender: &JsonValue,
+        target: Option<&JsonValue>,
+        content: &JsonValue,
+    ) -> JsonValue {
+        let with: Vec<JsonValue> = self
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                ChatTypeParameter::Sender => sender.clone(),
+                ChatTypeParameter::Target => target.cloned().unwrap_or(JsonValue::from("")),
+                ChatTypeParameter::Content => content.clone(),
+            })
+            .collect();
+        let mut component = self.style.clone();
+        component.insert(
+            "translate".to_string(),
+            JsonValue::from(self.translation_key.clone()),
+        );
+        component.insert("with".to_string(), JsonValue::Array(with));
+        JsonValue::Object(component)
+    }
+}
+
+impl Default for ChatDecoration {
+    fn default() -> Self {
+        Self::new("chat.type.text", SENDER_AND_CONTENT.to_vec(), Map::new())
+    }
+}
+
+// entries without data use the vanilla chat type with the same name
+impl RegistryValue for ChatDecoration {
+    const REGISTRY: &'static str = CHAT_TYPE;
+
+    fn from_data(name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        match data {
+            Some(data) => Self::from_json(data),
+            None => Self::vanilla(name),
+        }
+    }
+}
+
+impl InlineRegistryValue<ChatType> for ChatDecoration {
+    fn from_inline(value: &ChatType, _registries: &RegistryStore) -> Self {
+        Self::from_packet(&value.chat)
+    }
+}
+
+// the decorations of the minecraft:chat_type registry, the index is the network id
+#[derive(Debug, Clone, Default)]
+pub struct ChatTypes {
+    entries: Vec<(String, ChatDecoration)>,
+}
+
+impl ChatTypes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // entries that can not be read use the default decoration
+    pub fn from_registries(registries: &RegistryStore) -> Self {
+        let entries = match registries.get(CHAT_TYPE) {
+            Some(registry) => registry
+                .iter()
+                .enumerate()
+                .map(|(id, entry)| {
+                    let decoration = registries.get_value(id as i32).unwrap_or_default();
+                    (entry.get_name().to_string(), decoration)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Self { entries }
+    }
+
+    pub fn get(&self, id: i32) -> Option<&ChatDecoration> {
+        self.entries
+            .get(id as usize)
+            .map(|(_, decoration)| decoration)
+    }
+
+    pub fn get_name(&self, id: i32) -> Option<&str> {
+        self.entries.get(id as usize).map(|(name, _)| name.as_str())
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<i32> {
+        self.entries
+            .iter()
+            .position(|(n, _)| n == name)
+            .map(|id| id as i32)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // the decoration of a chat type sent in a chat packet, unknown ids use the default one
+    pub fn resolve(&self, chat_type: &types::IdOr<ChatType>) -> ChatDecoration {
+        match chat_type {
+            types::IdOr::Id(id) => self.get(*id).cloned().unwrap_or_default(),
+            types::IdOr::Value(value) => ChatDecoration::from_packet(&value.chat),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatMessageKind {
+    Player,    // a message sent by a player
+    Disguised, // a message sent by the server in the name of someone else, e.g. the /say command
+    System,    // a message sent by the server, e.g. command feedback
+    Overlay,   // a system message shown above the hotbar
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    kind: ChatMessageKind,
+    sender: Option<u128>, // uuid of the player, only for player messages
+    sender_name: Option<JsonValue>,
+    target_name: Option<JsonValue>,
+    content: JsonValue, // text component of the message itself
+    chat_type: Option<ChatDecoration>,
+    timestamp: Option<i64>, // in milliseconds since the unix epoch, only for player messages
+    message: JsonValue,     // text component of the decorated message as shown in the chat
+}
+
+impl ChatMessage {
+    fn new_decorated(
+        kind: ChatMessageKind,
+        sender: Option<u128>,
+        sender_name: &types::NetworkNBT,
+        target_name: &types::Optional<types::NetworkNBT>,
+        content: JsonValue,
+        chat_type: ChatDecoration,
+        timestamp: Option<i64>,
+    ) -> Self {
+        let sender_name = sender_name.to_json();
+        let target_name = match target_name {
+            types::Optional::Some(name) => Some(name.to_json()),
+            types::Optional::None => None,
+        };
+        let message = chat_type.decorate(&sender_name, target_name.as_ref(), &content);
+        Self {
+            kind,
+            sender,
+            sender_name: Some(sender_name),
+            target_name,
+            content,
+            chat_type: Some(chat_type),
+            timestamp,
+            message,
+        }
+    }
+
+    pub fn get_kind(&self) -> ChatMessageKind {
+        self.kind
+    }
+
+    pub fn get_sender(&self) -> Option<u128> {
+        self.sender
+    }
+
+    pub fn get_sender_name(&self) -> Option<&JsonValue> {
+        self.sender_name.as_ref()
+    }
+
+    pub fn get_target_name(&self) -> Option<&JsonValue> {
+        self.target_name.as_ref()
+    }
+
+    pub fn get_content(&self) -> &JsonValue {
+        &self.content
+    }
+
+    pub fn get_chat_type(&self) -> Option<&ChatDecoration> {
+        self.chat_type.as_ref()
+    }
+
+    pub fn get_timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    pub fn get_message(&self) -> &JsonValue {
+        &self.message
+    }
+
+    // the decorated message as plain text, e.g. "<Steve> hello"
+    pub fn get_text(&self, language: Option<&Language>) -> String {
+        render_text_component_plain(&self.message, language)
+    }
+
+    pub fn get_sender_name_text(&self, language: Option<&Language>) -> Option<String> {
+        Some(render_text_component_plain(
+            self.sender_name.as_ref()?,
+            language,
+        ))
+    }
+
+    pub fn get_content_text(&self, language: Option<&Language>) -> String {
+        render_text_component_plain(&self.content, language)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Chat {
+    chat_types: ChatTypes,
+    history: VecDeque<ChatMessage>,
+    // ring buffer of the last seen signed messages, `tail` is the oldest entry
+    last_seen: Vec<Option<types::FixedSizeByteArray<256>>>,
+    tail: usize,
+    last_signature: Option<types::FixedSizeByteArray<256>>,
+    offset: i32, // number of messages received since the last acknowledgement
+}
+
+impl Chat {
+    pub fn new(chat_types: ChatTypes) -> Self {
+        Self {
+            chat_types,
+            history: VecDeque::new(),
+            last_seen: vec![None; LAST_SEEN_SIZE],
+            tail: 0,
+            last_signature: None,
+            offset: 0,
+        }
+    }
+
+    pub fn get_chat_types(&self) -> &ChatTypes {
+        &self.chat_types
+    }
+
+    // received messages, oldest first
+    pub fn get_history(&self) -> &VecDeque<ChatMessage> {
+        &self.history
+    }
+
+    pub fn get_last_message(&self) -> Option<&ChatMessage> {
+        self.history.back()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    fn push_message(&mut self, message: ChatMessage) {
+        if self.history.len() >= MAX_HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.history.push_back(message);
+    }
+
+    // remembers a signed message for the next last seen acknowledgement
+    fn track_signature(&mut self, signature: &types::FixedSizeByteArray<256>) {
+        if self.last_signature.as_ref() == Some(signature) {
+            return;
+        }
+        self.last_signature = Some(*signature);
+        self.last_seen[self.tail] = Some(*signature);
+        self.tail = (self.tail + 1) % LAST_SEEN_SIZE;
+        self.offset += 1;
+    }
+
+    // the message count and acknowledged bits sent with chat messages and signed commands,
+    // bit 0 is the oldest message
+    fn take_last_seen_update(&mut self) -> (i32, types::FixedSizeBitSet<3>) {
+        let offset = std::mem::take(&mut self.offset);
+        let mut acknowledged = types::FixedSizeBitSet::<3>::empty();
+        for i in 0..LAST_SEEN_SIZE {
+            if self.last_seen[(self.tail + i) % LAST_SEEN_SIZE].is_some() {
+                acknowledged.set_bit(i, true);
+            }
+        }
+        (offset, acknowledged)
+    }
+
+    // the signatures of the last seen messages, oldest first
+    pub fn get_last_seen_signatures(&self) -> Vec<types::FixedSizeByteArray<256>> {
+        (0..LAST_SEEN_SIZE)
+            .filter_map(|i| self.last_seen[(self.tail + i) % LAST_SEEN_SIZE])
+            .collect()
+    }
+
+    // number of signed messages received since the last acknowledgement
+    pub fn get_pending_count(&self) -> i32 {
+        self.offset
+    }
+
+    // an acknowledgement the client has to send after too many messages without sending any
+    pub fn take_acknowledgement(&mut self) -> Option<ServerboundPlayPacket> {
+        if self.offset <= ACKNOWLEDGE_THRESHOLD {
+            return None;
+        }
+        Some(ServerboundPlayPacket::AcknowledgeMessage {
+            message_count: std::mem::take(&mut self.offset).into(),
+        })
+    }
+
+    // builds a chat message packet, messages starting with '/' are sent as commands
+    pub fn send_chat(&mut self, message: &str, commands: &CommandGraph) -> ServerboundPlayPacket {
+        if let Some(command) = message.strip_prefix('/') {
+            return self.send_command(command, commands);
+        }
+        let message: String = message.chars().take(MAX_MESSAGE_LENGTH).collect();
+        let (message_count, acknowledged) = self.take_last_seen_update();
+        ServerboundPlayPacket::ChatMessage {
+            message: message.into(),
+            timestamp: now_millis().into(),
+            salt: new_salt().into(),
+            signature: types::Optional::None,
+            message_count: message_count.into(),
+            acknowledged,
+        }
+    }
+
+    // builds a command packet, the command should not start with '/'
+    // commands with message arguments in the command graph are sent as unsigned signed commands
+    // so that the server receives the last seen messages
+    pub fn send_command(
+        &mut self,
+        command: &str,
+        commands: &CommandGraph,
+    ) -> ServerboundPlayPacket {
+        let command = command.strip_prefix('/').unwrap_or(command);
+        if !commands.is_signable(command) {
+            return ServerboundPlayPacket::ChatCommand {
+                command: command.into(),
+            };
+        }
+        let (message_count, acknowledged) = self.take_last_seen_update();
+        ServerboundPlayPacket::SignedChatCommand {
+            command: command.into(),
+            timestamp: now_millis().into(),
+            salt: new_salt().into(),
+            argument_signatures: types::Array::new(Vec::new()),
+            message_count: message_count.into(),
+            acknowledged,
+        }
+    }
+
+    // applies a play packet to the chat, returns true if the packet was used
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::PlayerChatMessage {
+                sender,
+                message_signature,
+                message,
+                timestamp,
+                unsigned_content,
+                chat_type,
+                sender_name,
+                target_name,
+                ..
+            } => {
+                // servers may replace the signed message with a decorated version
+                let content = match unsigned_content {
+                    types::Optional::Some(content) => content.to_json(),
+                    types::Optional::None => JsonValue::from(message.get_value()),
+                };
+                let decoration = self.chat_types.resolve(chat_type);
+                self.push_message(ChatMessage::new_decorated(
+                    ChatMessageKind::Player,
+                    Some(sender.get_value()),
+                    sender_name,
+                    target_name,
+                    content,
+                    decoration,
+                    Some(timestamp.get_value()),
+                ));
+                if let types::Optional::Some(signature) = message_signature {
+                    self.track_signature(signature);
+                }
+            }
+            ClientboundPlayPacket::DisguisedChatMessage {
+                message,
+                chat_type,
+                sender_name,
+                target_name,
+            } => {
+                let decoration = self.chat_types.resolve(chat_type);
+                self.push_message(ChatMessage::new_decorated(
+                    ChatMessageKind::Disguised,
+                    None,
+                    sender_name,
+                    target_name,
+                    message.to_json(),
+                    decoration,
+                    None,
+                ));
+            }
+            ClientboundPlayPacket::SystemChatMessage { content, overlay } => {
+                let content = content.to_json();
+                self.push_message(ChatMessage {
+                    kind: if overlay.get_value() {
+                        ChatMessageKind::Overlay
+                    } else {
+                        ChatMessageKind::System
+                    },
+                    sender: None,
+                    sender_name: None,
+                    target_name: None,
+                    message: content.clone(),
+                    content,
+                    chat_type: None,
+                    timestamp: None,
+                });
+            }
+            _ => return false,
+        }
+        true
+    }
+}
@@ -0,0 +1,1211 @@
+// the brigadier command graph sent by the server, nodes are referenced by their index in the node list
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde_json::Value as JsonValue;
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::{
+    CommandNode, CommandNodeKind, CommandParser, CommandStringKind,
+};
+use crate::minecraft::serverbound::ServerboundPlayPacket;
+use crate::minecraft::types;
+
+#[derive(Debug, Clone)]
+pub struct CommandGraph {
+    nodes: Vec<CommandNode>,
+    root: usize,
+}
+
+impl Default for CommandGraph {
+    fn default() -> Self {
+        Self::from_nodes(
+            vec![CommandNode {
+                kind: CommandNodeKind::Root,
+                is_executable: false,
+                children: Vec::new().into(),
+                redirect: None,
+            }],
+            0,
+        )
+    }
+}
+
+impl CommandGraph {
+    // a graph with only a root node
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_nodes(nodes: Vec<CommandNode>, root: usize) -> Self {
+        Self { nodes, root }
+    }
+
+    pub fn get_root(&self) -> usize {
+        self.root
+    }
+
+    pub fn get_node(&self, index: usize) -> Option<&CommandNode> {
+        self.nodes.get(index)
+    }
+
+    pub fn get_nodes(&self) -> &Vec<CommandNode> {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // the name of a literal or argument node, None for the root
+    pub fn get_name(&self, index: usize) -> Option<String> {
+        match &self.get_node(index)?.kind {
+            CommandNodeKind::Root => None,
+            CommandNodeKind::Literal { name } => Some(name.get_value()),
+            CommandNodeKind::Argument { name, .. } => Some(name.get_value()),
+        }
+    }
+
+    pub fn get_parser(&self, index: usize) -> Option<&CommandParser> {
+        match &self.get_node(index)?.kind {
+            CommandNodeKind::Argument { parser, .. } => Some(parser),
+            _ => None,
+        }
+    }
+
+    pub fn is_executable(&self, index: usize) -> bool {
+        self.get_node(index).is_some_and(|node| node.is_executable)
+    }
+
+    // indices out of the node list are skipped
+    pub fn get_children(&self, index: usize) -> Vec<usize> {
+        match self.get_node(index) {
+            Some(node) => node
+                .children
+                .iter()
+                .map(|child| child.get_value() as usize)
+                .filter(|child| *child < self.nodes.len())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_redirect(&self, index: usize) -> Option<usize> {
+        let redirect = self.get_node(index)?.redirect.as_ref()?.get_value() as usize;
+        (redirect < self.nodes.len()).then_some(redirect)
+    }
+
+    // a literal or argument child with the given name
+    pub fn find_child(&self, index: usize, name: &str) -> Option<usize> {
+        self.get_children(index)
+            .into_iter()
+            .find(|child| self.get_name(*child).as_deref() == Some(name))
+    }
+
+    // follows literal names from the root, e.g. ["gamerule", "doDaylightCycle"]
+    pub fn find(&self, path: &[&str]) -> Option<usize> {
+        let mut index = self.root;
+        for name in path {
+            if self.get_children(index).is_empty() {
+                index = self.get_redirect(index)?;
+            }
+            index = self.find_child(index, name)?;
+        }
+        Some(index)
+    }
+
+    // names of the commands, i.e. the literal children of the root
+    pub fn get_command_names(&self) -> Vec<String> {
+        self.get_children(self.root)
+            .into_iter()
+            .filter_map(|child| self.get_name(child))
+            .collect()
+    }
+
+    // visits every node reachable from the root through children once, in depth first order,
+    // with the depth of the node (0 for the root)
+    pub fn walk(&self, mut visit: impl FnMut(usize, &CommandNode, usize)) {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![(self.root, 0)];
+        while let Some((index, depth)) = stack.pop() {
+            if index >= self.nodes.len() || visited[index] {
+                continue;
+            }
+            visited[index] = true;
+            visit(index, &self.nodes[index], depth);
+            for child in self.get_children(index).into_iter().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+    }
+
+    // "name" for literals and "<name>" for arguments
+    pub fn get_usage_text(&self, index: usize) -> String {
+        match self.get_node(index).map(|node| &node.kind) {
+            Some(CommandNodeKind::Literal { name }) => name.get_value(),
+            Some(CommandNodeKind::Argument { name, .. }) => format!("<{}>", name.get_value()),
+            _ => String::new(),
+        }
+    }
+
+    // every usage of the node, like brigadier's `getAllUsage`, e.g. "time set <time>"
+    pub fn get_all_usage(&self, index: usize) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut path = Vec::new();
+        self.collect_usage(index, String::new(), &mut path, &mut result);
+        result
+    }
+
+    fn collect_usage(
+        &self,
+        index: usize,
+        prefix: String,
+        path: &mut Vec<usize>,
+        result: &mut Vec<String>,
+    ) {
+        if self.is_executable(index) {
+            result.push(prefix.clone());
+        }
+        if let Some(redirect) = self.get_redirect(index) {
+            let redirect = if redirect == self.root {
+                "...".to_string()
+            } else {
+                format!("-> {}", self.get_usage_text(redirect))
+            };
+            if prefix.is_empty() {
+                result.push(format!("{} {}", self.get_usage_text(index), redirect));
+            } else {
+                result.push(format!("{} {}", prefix, redirect));
+            }
+            return;
+        }
+        // the children of a valid graph never form a cycle, but the packet may be malformed
+        path.push(index);
+        for child in self.get_children(index) {
+            if path.contains(&child) {
+                continue;
+            }
+            let prefix = if prefix.is_empty() {
+                self.get_usage_text(child)
+            } else {
+                format!("{} {}", prefix, self.get_usage_text(child))
+            };
+            self.collect_usage(child, prefix, path, result);
+        }
+        path.pop();
+    }
+
+    // the usage of every command, one per line
+    pub fn get_usage(&self) -> String {
+        self.get_all_usage(self.root)
+            .iter()
+            .map(|usage| format!("/{}", usage))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // parses a command without the leading '/', like the server would
+    pub fn parse(&self, input: &str) -> ParseResults {
+        let input: Vec<char> = input.chars().collect();
+        let mut nodes = Vec::new();
+        self.parse_from(&input, self.root, 0, &mut nodes)
+    }
+
+    // whether the command reaches a message argument (e.g. `/msg`, `/say`), the server expects
+    // these commands as signed commands with the last seen messages
+    pub fn is_signable(&self, command: &str) -> bool {
+        self.parse(command).get_nodes().iter().any(|node| {
+            matches!(
+                self.get_parser(node.get_node()),
+                Some(CommandParser::Message)
+            )
+        })
+    }
+
+    // `index` is the last node matched, it ends at `cursor`
+    fn parse_from(
+        &self,
+        input: &[char],
+        index: usize,
+        cursor: usize,
+        nodes: &mut Vec<ParsedNode>,
+    ) -> ParseResults {
+        if cursor == input.len() {
+            let error = if nodes.is_empty() {
+                Some(CommandParseError::new(UNKNOWN_COMMAND, cursor))
+            } else if !self.is_executable(index) {
+                Some(CommandParseError::new(INCOMPLETE_COMMAND, cursor))
+            } else {
+                None
+            };
+            return ParseResults::new(nodes, error);
+        }
+        let start = if nodes.is_empty() {
+            cursor
+        } else if input[cursor] == ' ' {
+            cursor + 1
+        } else {
+            let error = CommandParseError::new(EXPECTED_SEPARATOR, cursor);
+            return ParseResults::new(nodes, Some(error));
+        };
+        let parent = self.get_redirect(index).unwrap_or(index);
+        let children = self.get_children(parent);
+
+        // like brigadier, a literal that matches the next word hides the arguments
+        let word_end = read_word(input, start);
+        let word: String = input[start..word_end].iter().collect();
+        let literal = children.iter().find(|child| {
+            matches!(
+                self.get_node(**child).map(|node| &node.kind),
+                Some(CommandNodeKind::Literal { name }) if name.get_value() == word
+            )
+        });
+        if let Some(literal) = literal {
+            nodes.push(ParsedNode::new(*literal, start, word_end));
+            let results = self.parse_from(input, *literal, word_end, nodes);
+            nodes.pop();
+            return results;
+        }
+
+        // the first argument that parses the rest of the input wins, otherwise the error
+        // that got the furthest is reported
+        let mut best: Option<ParseResults> = None;
+        for child in children {
+            let parser = match self.get_parser(child) {
+                Some(parser) => parser,
+                None => continue,
+            };
+            let results = match parse_argument(parser, input, start) {
+                Ok(end) => {
+                    nodes.push(ParsedNode::new(child, start, end));
+                    let results = self.parse_from(input, child, end, nodes);
+                    nodes.pop();
+                    results
+                }
+                Err(message) => {
+                    ParseResults::new(nodes, Some(CommandParseError::new(&message, start)))
+                }
+            };
+            if results.is_valid() {
+                return results;
+            }
+            let is_further = match &best {
+                Some(best) => results.get_error_position() > best.get_error_position(),
+                None => true,
+            };
+            if is_further {
+                best = Some(results);
+            }
+        }
+        best.unwrap_or_else(|| {
+            let message = if nodes.is_empty() {
+                UNKNOWN_COMMAND
+            } else {
+                INCORRECT_ARGUMENT
+            };
+            ParseResults::new(nodes, Some(CommandParseError::new(message, start)))
+        })
+    }
+
+    // completions of the last word that can be found without the server,
+    // None if an argument that could be completed asks the server for suggestions
+    pub fn get_local_suggestions(&self, input: &str) -> Option<Suggestions> {
+        let chars: Vec<char> = input.chars().collect();
+        let results = self.parse(input);
+        let nodes = results.get_nodes();
+        // the node whose children complete the input, and where the completed text starts
+        let (parent, start) = match nodes.last() {
+            Some(last) if last.end == chars.len() => match nodes.len() {
+                1 => (self.root, last.start),
+                n => (nodes[n - 2].node, last.start),
+            },
+            Some(last) if chars[last.end] == ' ' => (last.node, last.end + 1),
+            Some(_) => return Some(Suggestions::new(chars.len(), 0, Vec::new())),
+            None => (self.root, 0),
+        };
+        let parent = self.get_redirect(parent).unwrap_or(parent);
+        let remaining: String = chars[start..].iter().collect::<String>().to_lowercase();
+
+        let mut matches = Vec::new();
+        for child in self.get_children(parent) {
+            match self.get_node(child).map(|node| &node.kind) {
+                Some(CommandNodeKind::Literal { name }) => {
+                    let name = name.get_value();
+                    if name.to_lowercase().starts_with(&remaining) {
+                        matches.push(Suggestion::new(&name, None));
+                    }
+                }
+                Some(CommandNodeKind::Argument {
+                    suggestions: Some(_),
+                    ..
+                }) => return None,
+                Some(CommandNodeKind::Argument {
+                    parser: CommandParser::Bool,
+                    ..
+                }) => {
+                    for value in ["true", "false"] {
+                        if value.starts_with(&remaining) {
+                            matches.push(Suggestion::new(value, None));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(Suggestions::new(start, chars.len() - start, matches))
+    }
+
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::Commands { nodes, root_index } => {
+                *self = Self::from_nodes(nodes.to_vec(), root_index.get_value() as usize);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn to_packet(&self) -> ClientboundPlayPacket {
+        ClientboundPlayPacket::Commands {
+            nodes: self.nodes.clone().into(),
+            root_index: types::VarInt::new(self.root as i32),
+        }
+    }
+}
+
+// error messages of brigadier
+const UNKNOWN_COMMAND: &str = "Unknown command";
+const INCOMPLETE_COMMAND: &str = "Unknown or incomplete command";
+const INCORRECT_ARGUMENT: &str = "Incorrect argument for command";
+const EXPECTED_SEPARATOR: &str = "Expected whitespace to end one argument, but found trailing data";
+
+// positions are indices of characters in the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandParseError {
+    message: String,
+    position: usize,
+}
+
+impl CommandParseError {
+    fn new(message: &str, position: usize) -> Self {
+        Self {
+            message: message.to_string(),
+            position,
+        }
+    }
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParsedNode {
+    node: usize,
+    start: usize,
+    end: usize,
+}
+
+impl ParsedNode {
+    fn new(node: usize, start: usize, end: usize) -> Self {
+        Self { node, start, end }
+    }
+
+    pub fn get_node(&self) -> usize {
+        self.node
+    }
+
+    pub fn get_start(&self) -> usize {
+        self.start
+    }
+
+    pub fn get_end(&self) -> usize {
+        self.end
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseResults {
+    nodes: Vec<ParsedNode>, // the nodes matched, in the order of the input
+    error: Option<CommandParseError>,
+}
+
+impl ParseResults {
+    fn new(nodes: &[ParsedNode], error: Option<CommandParseError>) -> Self {
+        Self {
+            nodes: nodes.to_vec(),
+            error,
+        }
+    }
+
+    pub fn get_nodes(&self) -> &Vec<ParsedNode> {
+        &self.nodes
+    }
+
+    // the last node reached, None if the command name was not recognized
+    pub fn get_last_node(&self) -> Option<usize> {
+        self.nodes.last().map(|node| node.node)
+    }
+
+    pub fn get_error(&self) -> Option<&CommandParseError> {
+        self.error.as_ref()
+    }
+
+    fn get_error_position(&self) -> usize {
+        self.error.as_ref().map_or(0, |error| error.position)
+    }
+
+    // the input is a complete command that can be run
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+fn read_word(input: &[char], start: usize) -> usize {
+    let mut end = start;
+    while end < input.len() && input[end] != ' ' {
+        end += 1;
+    }
+    end
+}
+
+// a word that may contain spaces inside brackets and quotes, e.g. `@e[type=cow, limit=1]`
+fn read_token(input: &[char], start: usize) -> usize {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut end = start;
+    while end < input.len() {
+        let c = input[end];
+        match quote {
+            Some(_) if c == '\\' => end += 1,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' | '{' | '(' => depth += 1,
+                ']' | '}' | ')' if depth > 0 => depth -= 1,
+                ' ' if depth == 0 => break,
+                _ => {}
+            },
+        }
+        end += 1;
+    }
+    end.min(input.len())
+}
+
+// characters of brigadier's unquoted strings
+fn read_unquoted(input: &[char], start: usize) -> usize {
+    let mut end = start;
+    while end < input.len()
+        && (input[end].is_ascii_alphanumeric() || matches!(input[end], '_' | '-' | '.' | '+'))
+    {
+        end += 1;
+    }
+    end
+}
+
+fn read_quoted(input: &[char], start: usize) -> Result<usize, String> {
+    let quote = input[start];
+    let mut end = start + 1;
+    while end < input.len() {
+        match input[end] {
+            '\\' => end += 1,
+            c if c == quote => return Ok(end + 1),
+            _ => {}
+        }
+        end += 1;
+    }
+    Err("Unclosed quoted string".to_string())
+}
+
+fn read_number<T: FromStr>(input: &[char], start: usize, name: &str) -> Result<(T, usize), String> {
+    let mut end = start;
+    while end < input.len() && (input[end].is_ascii_digit() || matches!(input[end], '.' | '-')) {
+        end += 1;
+    }
+    let text: String = input[start..end].iter().collect();
+    if text.is_empty() {
+        return Err(format!("Expected {}", name));
+    }
+    match text.parse() {
+        Ok(value) => Ok((value, end)),
+        Err(_) => Err(format!("Invalid {} '{}'", name, text)),
+    }
+}
+
+fn check_range<T: PartialOrd + Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+    name: &str,
+) -> Result<(), String> {
+    if let Some(min) = min
+        && value < min
+    {
+        return Err(format!(
+            "{} must not be less than {}, found {}",
+            name, min, value
+        ));
+    }
+    if let Some(max) = max
+        && value > max
+    {
+        return Err(format!(
+            "{} must not be more than {}, found {}",
+            name, max, value
+        ));
+    }
+    Ok(())
+}
+
+// e.g. `10`, `~-2.5` or `^`
+fn read_coordinates(input: &[char], start: usize, count: usize) -> Result<usize, String> {
+    let incomplete = format!("Incomplete (expected {} coordinates)", count);
+    let mut end = start;
+    for i in 0..count {
+        if i > 0 {
+            if end >= input.len() || input[end] != ' ' {
+                return Err(incomplete);
+            }
+            end += 1;
+        }
+        let word_end = read_word(input, end);
+        let word: String = input[end..word_end].iter().collect();
+        if word.is_empty() {
+            return Err(incomplete);
+        }
+        let number = word.strip_prefix(['~', '^']).unwrap_or(&word);
+        if !number.is_empty() && number.parse::<f64>().is_err() {
+            return Err(format!("Invalid double '{}'", number));
+        }
+        end = word_end;
+    }
+    Ok(end)
+}
+
+// the end of the argument that starts at `start`, or why it is invalid
+fn parse_argument(parser: &CommandParser, input: &[char], start: usize) -> Result<usize, String> {
+    match parser {
+        CommandParser::Bool => {
+            let end = read_word(input, start);
+            let word: String = input[start..end].iter().collect();
+            match word.as_str() {
+                "true" | "false" => Ok(end),
+                "" => Err("Expected bool".to_string()),
+                word => Err(format!(
+                    "Invalid bool, expected true or false but found '{}'",
+                    word
+                )),
+            }
+        }
+        CommandParser::Float(range) => {
+            let (value, end) = read_number::<f32>(input, start, "float")?;
+            let min = range.min.map(|min| min.get_value());
+            let max = range.max.map(|max| max.get_value());
+            check_range(value, min, max, "Float")?;
+            Ok(end)
+        }
+        CommandParser::Double(range) => {
+            let (value, end) = read_number::<f64>(input, start, "double")?;
+            let min = range.min.map(|min| min.get_value());
+            let max = range.max.map(|max| max.get_value());
+            check_range(value, min, max, "Double")?;
+            Ok(end)
+        }
+        CommandParser::Integer(range) => {
+            let (value, end) = read_number::<i32>(input, start, "integer")?;
+            let min = range.min.map(|min| min.get_value());
+            let max = range.max.map(|max| max.get_value());
+            check_range(value, min, max, "Integer")?;
+            Ok(end)
+        }
+        CommandParser::Long(range) => {
+            let (value, end) = read_number::<i64>(input, start, "long")?;
+            let min = range.min.map(|min| min.get_value());
+            let max = range.max.map(|max| max.get_value());
+            check_range(value, min, max, "Long")?;
+            Ok(end)
+        }
+        CommandParser::String(kind) => match kind {
+            CommandStringKind::GreedyPhrase => Ok(input.len()),
+            CommandStringKind::QuotablePhrase
+                if start < input.len() && matches!(input[start], '"' | '\'') =>
+            {
+                read_quoted(input, start)
+            }
+            _ => match read_unquoted(input, start) {
+                end if end == start => Err("Expected string".to_string()),
+                end => Ok(end),
+            },
+        },
+        CommandParser::Message => Ok(input.len()),
+        CommandParser::BlockPos | CommandParser::Vec3 => read_coordinates(input, start, 3),
+        CommandParser::ColumnPos | CommandParser::Vec2 | CommandParser::Rotation => {
+            read_coordinates(input, start, 2)
+        }
+        CommandParser::Time { min } => {
+            // a number of ticks, or of days and seconds with the `d` and `s` units
+            let end = read_word(input, start);
+            let word: String = input[start..end].iter().collect();
+            let (number, multiplier) = match word.chars().last() {
+                Some('d') => (&word[..word.len() - 1], 24000.0),
+                Some('s') => (&word[..word.len() - 1], 20.0),
+                Some('t') => (&word[..word.len() - 1], 1.0),
+                _ => (word.as_str(), 1.0),
+            };
+            let value: f32 = match number.parse() {
+                Ok(value) => value,
+                Err(_) if number.is_empty() => return Err("Expected float".to_string()),
+                Err(_) => return Err(format!("Invalid float '{}'", number)),
+            };
+            let ticks = (value * multiplier).round() as i32;
+            check_range(ticks, Some(min.get_value()), None, "Tick count")?;
+            Ok(end)
+        }
+        _ => match read_token(input, start) {
+            end if end == start => Err(format!("Expected {}", parser.get_name())),
+            end => Ok(end),
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    text: String,
+    tooltip: Option<JsonValue>, // a text component
+}
+
+impl Suggestion {
+    fn new(text: &str, tooltip: Option<JsonValue>) -> Self {
+        Self {
+            text: text.to_string(),
+            tooltip,
+        }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn get_tooltip(&self) -> Option<&JsonValue> {
+        self.tooltip.as_ref()
+    }
+}
+
+// suggestions replace the characters of the input from `start` to `start + length`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestions {
+    start: usize,
+    length: usize,
+    matches: Vec<Suggestion>,
+}
+
+impl Suggestions {
+    fn new(start: usize, length: usize, matches: Vec<Suggestion>) -> Self {
+        Self {
+            start,
+            length,
+            matches,
+        }
+    }
+
+    pub fn get_start(&self) -> usize {
+        self.start
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn get_matches(&self) -> &Vec<Suggestion> {
+        &self.matches
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    // the input with a suggestion applied
+    pub fn apply(&self, input: &str, index: usize) -> Option<String> {
+        let suggestion = self.matches.get(index)?;
+        let chars: Vec<char> = input.chars().collect();
+        let start = self.start.min(chars.len());
+        let end = (self.start + self.length).min(chars.len());
+        let mut result: String = chars[..start].iter().collect();
+        result.push_str(&suggestion.text);
+        result.extend(&chars[end..]);
+        Some(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandCompletion {
+    Done(Suggestions),
+    // the request has to be sent, the suggestions arrive in a CommandSuggestionsResponse
+    Pending {
+        transaction_id: i32,
+        request: Box<ServerboundPlayPacket>,
+    },
+}
+
+// the command graph and the tab completions that are waiting for the server
+#[derive(Debug, Clone, Default)]
+pub struct Commands {
+    graph: CommandGraph,
+    next_transaction_id: i32,
+    pending: HashMap<i32, usize>, // transaction id -> offset of the response positions in the input
+    responses: HashMap<i32, Suggestions>,
+}
+
+impl Commands {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_graph(&self) -> &CommandGraph {
+        &self.graph
+    }
+
+    // the leading '/' is optional
+    pub fn parse(&self, input: &str) -> ParseResults {
+        self.graph.parse(input.strip_prefix('/').unwrap_or(input))
+    }
+
+    // completes the word at the end of the input, positions of the suggestions include the '/'
+    pub fn complete(&mut self, input: &str) -> CommandCompletion {
+        let (command, offset) = match input.strip_prefix('/') {
+            Some(command) => (command, 1),
+            None => (input, 0),
+        };
+        if let Some(suggestions) = self.graph.get_local_suggestions(command) {
+            return CommandCompletion::Done(Suggestions {
+                start: suggestions.start + offset,
+                ..suggestions
+            });
+        }
+        let transaction_id = self.next_transaction_id;
+        self.next_transaction_id = self.next_transaction_id.wrapping_add(1);
+        self.pending.insert(transaction_id, offset);
+        CommandCompletion::Pending {
+            transaction_id,
+            request: Box::new(ServerboundPlayPacket::CommandSuggestionsRequest {
+                transaction_id: types::VarInt::new(transaction_id),
+                text: types::String::from_str(command),
+            }),
+        }
+    }
+
+    pub fn is_pending(&self, transaction_id: i32) -> bool {
+        self.pending.contains_key(&transaction_id)
+    }
+
+    // the suggestions of a completed request, once
+    pub fn take_suggestions(&mut self, transaction_id: i32) -> Option<Suggestions> {
+        self.responses.remove(&transaction_id)
+    }
+
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::Commands { .. } => self.graph.apply_packet(packet),
+            ClientboundPlayPacket::CommandSuggestionsResponse {
+                id,
+                start,
+                length,
+                matches,
+            } => {
+                let offset = match self.pending.remove(&id.get_value()) {
+                    Some(offset) => offset,
+                    None => return false,
+                };
+                let matches = matches
+                    .iter()
+                    .map(|suggestion| {
+                        let tooltip = match &suggestion.tooltip {
+                            types::Optional::Some(tooltip) => Some(tooltip.to_json()),
+                            types::Optional::None => None,
+                        };
+                        Suggestion::new(&suggestion.match_.get_value(), tooltip)
+                    })
+                    .collect();
+                let suggestions = Suggestions::new(
+                    start.get_value() as usize + offset,
+                    length.get_value() as usize,
+                    matches,
+                );
+                self.responses.insert(id.get_value(), suggestions);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::clientbound::play::{CommandNumberRange, CommandSuggestionMatch};
+    use crate::minecraft::packet::{PacketReadable, PacketWritable};
+
+    fn node(kind: CommandNodeKind, is_executable: bool, children: &[i32]) -> CommandNode {
+        CommandNode {
+            kind,
+            is_executable,
+            children: types::Array::new(children.iter().map(|i| (*i).into()).collect()),
+            redirect: None,
+        }
+    }
+
+    fn literal(name: &str, is_executable: bool, children: &[i32]) -> CommandNode {
+        let kind = CommandNodeKind::Literal { name: name.into() };
+        node(kind, is_executable, children)
+    }
+
+    fn argument(name: &str, parser: CommandParser, children: &[i32]) -> CommandNode {
+        let kind = CommandNodeKind::Argument {
+            name: name.into(),
+            parser,
+            suggestions: None,
+        };
+        node(kind, true, children)
+    }
+
+    // /say <message>, /tell (redirects to /say), /seed
+    fn test_graph() -> CommandGraph {
+        let mut tell = literal("tell", false, &[]);
+        tell.redirect = Some(1.into());
+        CommandGraph::from_nodes(
+            vec![
+                node(CommandNodeKind::Root, false, &[1, 3, 4]),
+                literal("say", false, &[2]),
+                argument("message", CommandParser::Message, &[]),
+                tell,
+                literal("seed", true, &[]),
+            ],
+            0,
+        )
+    }
+
+    #[test]
+    fn signable_commands_have_message_arguments() {
+        let graph = test_graph();
+        assert!(graph.is_signable("say hello world"));
+        assert!(graph.is_signable("tell hello"));
+        assert!(!graph.is_signable("say"));
+        assert!(!graph.is_signable("seed"));
+        assert!(!graph.is_signable("me hello"));
+    }
+
+    // /say <message>, /tell (redirects to /say), /kill all|<name>, /pvp <bool>,
+    // /give <1..64>, /tp <entity> (completed by the server)
+    fn parser_graph() -> CommandGraph {
+        let mut tell = literal("tell", false, &[]);
+        tell.redirect = Some(1.into());
+        let mut target = argument("target", CommandParser::Entity { flags: 0.into() }, &[]);
+        if let CommandNodeKind::Argument { suggestions, .. } = &mut target.kind {
+            *suggestions = Some("minecraft:ask_server".into());
+        }
+        let count = CommandParser::Integer(CommandNumberRange {
+            min: Some(1.into()),
+            max: Some(64.into()),
+        });
+        CommandGraph::from_nodes(
+            vec![
+                node(CommandNodeKind::Root, false, &[1, 3, 4, 7, 9, 11]),
+                literal("say", false, &[2]),
+                argument("message", CommandParser::Message, &[]),
+                tell,
+                literal("kill", false, &[5, 6]),
+                literal("all", true, &[]),
+                argument(
+                    "name",
+                    CommandParser::String(CommandStringKind::SingleWord),
+                    &[],
+                ),
+                literal("pvp", false, &[8]),
+                argument("value", CommandParser::Bool, &[]),
+                literal("give", false, &[10]),
+                argument("count", count, &[]),
+                literal("tp", false, &[12]),
+                target,
+            ],
+            0,
+        )
+    }
+
+    fn assert_error(graph: &CommandGraph, input: &str, message: &str, position: usize) {
+        let results = graph.parse(input);
+        let error = results.get_error().expect(input);
+        assert_eq!(error.get_message(), message, "{}", input);
+        assert_eq!(error.get_position(), position, "{}", input);
+    }
+
+    fn suggestion_texts(suggestions: &Suggestions) -> Vec<&str> {
+        suggestions
+            .get_matches()
+            .iter()
+            .map(|s| s.get_text())
+            .collect()
+    }
+
+    #[test]
+    fn parse_errors() {
+        let graph = parser_graph();
+        assert_error(&graph, "", UNKNOWN_COMMAND, 0);
+        assert_error(&graph, "foo", UNKNOWN_COMMAND, 0);
+        assert_error(&graph, "kill", INCOMPLETE_COMMAND, 4);
+        assert_error(&graph, "kill all extra", INCORRECT_ARGUMENT, 9);
+        assert_error(&graph, "give 5x", EXPECTED_SEPARATOR, 6);
+        assert_error(&graph, "give x", "Expected integer", 5);
+        assert_error(
+            &graph,
+            "give 100",
+            "Integer must not be more than 64, found 100",
+            5,
+        );
+        assert_error(
+            &graph,
+            "pvp maybe",
+            "Invalid bool, expected true or false but found 'maybe'",
+            4,
+        );
+        assert!(graph.parse("give 64").is_valid());
+    }
+
+    #[test]
+    fn literals_come_before_arguments() {
+        let graph = parser_graph();
+        let results = graph.parse("kill all");
+        assert!(results.is_valid());
+        assert_eq!(results.get_last_node(), Some(5));
+        let results = graph.parse("kill alex");
+        assert!(results.is_valid());
+        assert_eq!(results.get_last_node(), Some(6));
+    }
+
+    #[test]
+    fn redirects_continue_at_the_target() {
+        let graph = parser_graph();
+        let results = graph.parse("tell hello there");
+        assert!(results.is_valid());
+        let nodes: Vec<(usize, usize, usize)> = results
+            .get_nodes()
+            .iter()
+            .map(|n| (n.get_node(), n.get_start(), n.get_end()))
+            .collect();
+        assert_eq!(nodes, vec![(3, 0, 4), (2, 5, 16)]);
+        assert_error(&graph, "tell", INCOMPLETE_COMMAND, 4);
+    }
+
+    #[test]
+    fn local_completions() {
+        let graph = parser_graph();
+        let suggestions = graph.get_local_suggestions("").unwrap();
+        assert_eq!(
+            suggestion_texts(&suggestions),
+            vec!["say", "tell", "kill", "pvp", "give", "tp"]
+        );
+        let suggestions = graph.get_local_suggestions("KI").unwrap();
+        assert_eq!(suggestion_texts(&suggestions), vec!["kill"]);
+        assert_eq!((suggestions.get_start(), suggestions.get_length()), (0, 2));
+        // the argument of /kill has nothing to suggest, the literal does
+        let suggestions = graph.get_local_suggestions("kill a").unwrap();
+        assert_eq!(suggestion_texts(&suggestions), vec!["all"]);
+        assert_eq!((suggestions.get_start(), suggestions.get_length()), (5, 1));
+
+        let suggestions = graph.get_local_suggestions("pvp ").unwrap();
+        assert_eq!(suggestion_texts(&suggestions), vec!["true", "false"]);
+        let suggestions = graph.get_local_suggestions("pvp t").unwrap();
+        assert_eq!(suggestion_texts(&suggestions), vec!["true"]);
+        assert_eq!(suggestions.apply("pvp t", 0).as_deref(), Some("pvp true"));
+
+        // suggestions of /tp come from the server
+        assert!(graph.get_local_suggestions("tp Al").is_none());
+    }
+
+    #[test]
+    fn completions_include_the_slash() {
+        let mut commands = Commands::new();
+        assert!(commands.apply_packet(&parser_graph().to_packet()));
+        let suggestions = match commands.complete("/ki") {
+            CommandCompletion::Done(suggestions) => suggestions,
+            completion => panic!("{:?}", completion),
+        };
+        assert_eq!((suggestions.get_start(), suggestions.get_length()), (1, 2));
+        assert_eq!(suggestions.apply("/ki", 0).as_deref(), Some("/kill"));
+    }
+
+    #[test]
+    fn server_completions() {
+        let mut commands = Commands::new();
+        commands.apply_packet(&parser_graph().to_packet());
+        let transaction_id = match commands.complete("/tp Al") {
+            CommandCompletion::Pending {
+                transaction_id,
+                request,
+            } => {
+                match *request {
+                    ServerboundPlayPacket::CommandSuggestionsRequest {
+                        transaction_id: id,
+                        text,
+                    } => {
+                        assert_eq!(id.get_value(), transaction_id);
+                        // the server gets the command without the '/'
+                        assert_eq!(text.get_value(), "tp Al");
+                    }
+                    request => panic!("{:?}", request),
+                }
+                transaction_id
+            }
+            completion => panic!("{:?}", completion),
+        };
+        assert!(commands.is_pending(transaction_id));
+
+        let response = |id: i32| ClientboundPlayPacket::CommandSuggestionsResponse {
+            id: id.into(),
+            start: 3.into(),
+            length: 2.into(),
+            matches: types::Array::new(vec![CommandSuggestionMatch {
+                match_: "Alex".into(),
+                tooltip: types::Optional::None,
+            }]),
+        };
+        // responses to other requests are not used
+        assert!(!commands.apply_packet(&response(transaction_id + 1)));
+        assert!(commands.apply_packet(&response(transaction_id)));
+        assert!(!commands.is_pending(transaction_id));
+        let suggestions = commands.take_suggestions(transaction_id).unwrap();
+        // the positions of the server do not count the '/'
+        assert_eq!((suggestions.get_start(), suggestions.get_length()), (4, 2));
+        assert_eq!(suggestions.apply("/tp Al", 0).as_deref(), Some("/tp Alex"));
+        assert!(commands.take_suggestions(transaction_id).is_none());
+    }
+
+    #[test]
+    fn graph_round_trip() {
+        // /execute as <targets> run ... (redirects to the root), /scoreboard <holder> <score>
+        // [<limit>], /wait <duration> [<repeat>] [<offset>]
+        let mut targets = argument(
+            "targets",
+            CommandParser::Entity { flags: 0x03.into() },
+            &[4],
+        );
+        if let CommandNodeKind::Argument { suggestions, .. } = &mut targets.kind {
+            *suggestions = Some("minecraft:ask_server".into());
+        }
+        let mut run = literal("run", false, &[]);
+        run.redirect = Some(0.into());
+        let score = CommandParser::Double(CommandNumberRange {
+            min: Some((-1.5).into()),
+            max: Some(2.5.into()),
+        });
+        let limit = CommandParser::Float(CommandNumberRange {
+            min: None,
+            max: Some(100.0.into()),
+        });
+        let repeat = CommandParser::Integer(CommandNumberRange {
+            min: Some(1.into()),
+            max: Some(64.into()),
+        });
+        let offset = CommandParser::Long(CommandNumberRange {
+            min: Some((-5).into()),
+            max: None,
+        });
+        let graph = CommandGraph::from_nodes(
+            vec![
+                node(CommandNodeKind::Root, false, &[1, 5, 9]),
+                literal("execute", false, &[2]),
+                literal("as", false, &[3]),
+                targets,
+                run,
+                literal("scoreboard", false, &[6]),
+                argument(
+                    "holder",
+                    CommandParser::ScoreHolder { flags: 0x01.into() },
+                    &[7],
+                ),
+                argument("score", score, &[8]),
+                argument("limit", limit, &[]),
+                literal("wait", false, &[10]),
+                argument("duration", CommandParser::Time { min: 20.into() }, &[11]),
+                argument("repeat", repeat, &[12]),
+                argument("offset", offset, &[]),
+            ],
+            0,
+        );
+
+        let bytes = graph.to_packet().to_bytes();
+        let mut read = CommandGraph::new();
+        assert!(read.apply_packet(&ClientboundPlayPacket::from_bytes(bytes.clone())));
+        assert_eq!(read.to_packet().to_bytes(), bytes);
+        assert_eq!((read.len(), read.get_root()), (13, 0));
+
+        assert_eq!(read.get_redirect(4), Some(0));
+        match &read.get_node(3).unwrap().kind {
+            CommandNodeKind::Argument {
+                parser: CommandParser::Entity { flags },
+                suggestions: Some(suggestions),
+                ..
+            } => {
+                assert_eq!(flags.get_u8(), 0x03);
+                assert_eq!(suggestions.to_string(), "minecraft:ask_server");
+            }
+            kind => panic!("{:?}", kind),
+        }
+        match read.get_parser(7) {
+            Some(CommandParser::Double(range)) => {
+                assert_eq!(range.min.map(|min| min.get_value()), Some(-1.5));
+                assert_eq!(range.max.map(|max| max.get_value()), Some(2.5));
+            }
+            parser => panic!("{:?}", parser),
+        }
+        match read.get_parser(8) {
+            Some(CommandParser::Float(range)) => {
+                assert!(range.min.is_none());
+                assert_eq!(range.max.map(|max| max.get_value()), Some(100.0));
+            }
+            parser => panic!("{:?}", parser),
+        }
+        match read.get_parser(6) {
+            Some(CommandParser::ScoreHolder { flags }) => assert_eq!(flags.get_u8(), 0x01),
+            parser => panic!("{:?}", parser),
+        }
+        match read.get_parser(10) {
+            Some(CommandParser::Time { min }) => assert_eq!(min.get_value(), 20),
+            parser => panic!("{:?}", parser),
+        }
+
+        // the decoded graph parses like the original one
+        assert!(
+            read.parse("execute as @a[limit=1] run wait 2s 64 -5")
+                .is_valid()
+        );
+        assert!(read.parse("scoreboard * 2.5 100").is_valid());
+        assert_error(
+            &read,
+            "wait 10t",
+            "Tick count must not be less than 20, found 10",
+            5,
+        );
+        assert_error(
+            &read,
+            "wait 1s 65",
+            "Integer must not be more than 64, found 65",
+            8,
+        );
+        assert_error(
+            &read,
+            "wait 1s 1 -6",
+            "Long must not be less than -5, found -6",
+            10,
+        );
+    }
+}
@@ -0,0 +1,638 @@
+// breaks blocks the same way as the vanilla client: the break time comes from the hardness of
+// the block and the held tool, and the world is changed before the server acknowledges it
+use crate::minecraft::chunk::AIR_BLOCK_STATE;
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::{GameEventType, GameMode};
+use crate::minecraft::registry::blocks::{BlockState, get_block_registry};
+use crate::minecraft::registry::builtin;
+use crate::minecraft::registry::dynamic::{self, RegistryStore};
+use crate::minecraft::serverbound::ServerboundPlayPacket;
+use crate::minecraft::serverbound::play::{BlockFace, HandEnum, PlayerActionStatus};
+use crate::minecraft::types;
+
+use super::inventory::{ARMOR_START, Inventory};
+use super::physics::PlayerPhysics;
+use super::world::World;
+
+const DESTROY_DELAY: u32 = 5; // ticks between breaking a block and starting the next one
+const CORRECT_TOOL_DIVISOR: f32 = 30.0;
+const WRONG_TOOL_DIVISOR: f32 = 100.0;
+const SUBMERGED_SPEED_MODIFIER: f32 = 0.2; // without aqua affinity
+const AIRBORNE_SPEED_MODIFIER: f32 = 0.2;
+const HELMET_SLOT: usize = ARMOR_START;
+
+const EFFICIENCY: &str = "minecraft:efficiency";
+const AQUA_AFFINITY: &str = "minecraft:aqua_affinity";
+
+// (item name prefix, tag of blocks it can not harvest, mining speed) of each tool material
+const TOOL_MATERIALS: [(&str, &str, f32); 6] = [
+    (
+        "minecraft:wooden_",
+        "minecraft:incorrect_for_wooden_tool",
+        2.0,
+    ),
+    (
+        "minecraft:stone_",
+        "minecraft:incorrect_for_stone_tool",
+        4.0,
+    ),
+    ("minecraft:iron_", "minecraft:incorrect_for_iron_tool", 6.0),
+    (
+        "minecraft:diamond_",
+        "minecraft:incorrect_for_diamond_tool",
+        8.0,
+    ),
+    (
+        "minecraft:golden_",
+        "minecraft:incorrect_for_gold_tool",
+        12.0,
+    ),
+    (
+        "minecraft:netherite_",
+        "minecraft:incorrect_for_netherite_tool",
+        9.0,
+    ),
+];
+const DIGGER_KINDS: [&str; 4] = ["pickaxe", "axe", "shovel", "hoe"];
+
+// hardness of the block, -1 for blocks that can not be broken, None if the hardness of the
+// block is not known, requires the block data to be loaded (see `BlockRegistry::load_hardness`)
+pub fn get_block_hardness(name: &str) -> Option<f32> {
+    get_block_registry()?.get_hardness(name)
+}
+
+// whether the block drops nothing unless it is broken with the correct tool, requires the
+// block data to be loaded
+pub fn requires_correct_tool(name: &str) -> bool {
+    get_block_registry().is_some_and(|registry| registry.requires_correct_tool(name))
+}
+
+#[derive(Debug, Clone)]
+struct ToolRule {
+    blocks: types::IdSet,
+    speed: Option<f32>,
+    correct_for_drops: Option<bool>,
+}
+
+// the mining rules of the held item
+#[derive(Debug, Clone)]
+struct Tool {
+    rules: Vec<ToolRule>,
+    default_speed: f32,
+}
+
+impl Tool {
+    // the tool component of the item, or the default one of tools, which is not sent
+    fn from_slot(slot: &types::Slot) -> Self {
+        if let Some((rules, default_speed)) = slot.get_tool() {
+            return Self {
+                rules: rules
+                    .iter()
+                    .map(|rule| ToolRule {
+                        blocks: rule.get_blocks().clone(),
+                        speed: rule.get_speed(),
+                        correct_for_drops: rule.is_correct_for_drops(),
+                    })
+                    .collect(),
+                default_speed,
+            };
+        }
+        let name = match slot.item_name() {
+            Some(name) if !slot.is_empty() => name,
+            _ => return Self::hand(),
+        };
+        for (prefix, incorrect_tag, speed) in TOOL_MATERIALS {
+            if let Some(kind) = name.strip_prefix(prefix)
+                && DIGGER_KINDS.contains(&kind)
+            {
+                return Self::digger(kind, incorrect_tag, speed);
+            }
+            if name.strip_prefix(prefix) == Some("sword") {
+                return Self::sword();
+            }
+        }
+        match name {
+            "minecraft:shears" => Self::shears(),
+            _ => Self::hand(),
+        }
+    }
+
+    fn hand() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_speed: 1.0,
+        }
+    }
+
+    fn tag(tag: &str) -> types::IdSet {
+        types::IdSet::Tag(tag.to_string())
+    }
+
+    fn blocks(names: &[&str]) -> types::IdSet {
+        types::IdSet::Ids(
+            names
+                .iter()
+                .filter_map(|name| builtin::get_id(builtin::BLOCK, name))
+                .collect(),
+        )
+    }
+
+    fn digger(kind: &str, incorrect_tag: &str, speed: f32) -> Self {
+        Self {
+            rules: vec![
+                ToolRule {
+                    blocks: Self::tag(incorrect_tag),
+                    speed: None,
+                    correct_for_drops: Some(false),
+                },
+                ToolRule {
+                    blocks: Self::tag(&format!("minecraft:mineable/{}", kind)),
+                    speed: Some(speed),
+                    correct_for_drops: Some(true),
+                },
+            ],
+            default_speed: 1.0,
+        }
+    }
+
+    fn sword() -> Self {
+        Self {
+            rules: vec![
+                ToolRule {
+                    blocks: Self::blocks(&["minecraft:cobweb"]),
+                    speed: Some(15.0),
+                    correct_for_drops: Some(true),
+                },
+                ToolRule {
+                    blocks: Self::tag("minecraft:sword_efficient"),
+                    speed: Some(1.5),
+                    correct_for_drops: None,
+                },
+            ],
+            default_speed: 1.0,
+        }
+    }
+
+    fn shears() -> Self {
+        let speed = |blocks, speed| ToolRule {
+            blocks,
+            speed: Some(speed),
+            correct_for_drops: None,
+        };
+        Self {
+            rules: vec![
+                ToolRule {
+                    blocks: Self::blocks(&["minecraft:cobweb"]),
+                    speed: Some(15.0),
+                    correct_for_drops: Some(true),
+                },
+                speed(Self::tag("minecraft:leaves"), 15.0),
+                speed(Self::tag("minecraft:wool"), 5.0),
+                speed(
+                    Self::blocks(&["minecraft:vine", "minecraft:glow_lichen"]),
+                    2.0,
+                ),
+            ],
+            default_speed: 1.0,
+        }
+    }
+
+    // the first rule with a speed that matches the block
+    fn get_speed(&self, registries: &RegistryStore, block_id: Option<i32>) -> f32 {
+        let tags = registries.get_tags();
+        block_id
+            .and_then(|id| {
+                self.rules.iter().find_map(|rule| match rule.speed {
+                    Some(speed) if tags.contains(builtin::BLOCK, &rule.blocks, id) => Some(speed),
+                    _ => None,
+                })
+            })
+            .unwrap_or(self.default_speed)
+    }
+
+    // the first rule that decides about the drops of the block
+    fn is_correct_for_drops(&self, registries: &RegistryStore, block_id: Option<i32>) -> bool {
+        let tags = registries.get_tags();
+        block_id
+            .and_then(|id| {
+                self.rules
+                    .iter()
+                    .find_map(|rule| match rule.correct_for_drops {
+                        Some(correct) if tags.contains(builtin::BLOCK, &rule.blocks, id) => {
+                            Some(correct)
+                        }
+                        _ => None,
+                    })
+            })
+            .unwrap_or(false)
+    }
+}
+
+fn get_enchantment_level(registries: &RegistryStore, slot: &types::Slot, enchantment: &str) -> i32 {
+    slot.get_enchantments()
+        .iter()
+        .find(|(id, _)| registries.get_name(dynamic::ENCHANTMENT, *id) == Some(enchantment))
+        .map_or(0, |(_, level)| *level)
+}
+
+// the part of the block that breaks every tick, the block breaks when it adds up to 1
+// (mining effects and attribute modifiers from the server are not taken into account)
+pub fn get_destroy_progress(
+    state: &BlockState,
+    inventory: &Inventory,
+    physics: &PlayerPhysics,
+    registries: &RegistryStore,
+) -> f32 {
+    let name = state.get_name();
+    // unknown blocks are not broken
+    let hardness = match get_block_hardness(name) {
+        Some(hardness) if hardness >= 0.0 => hardness,
+        _ => return 0.0,
+    };
+    if hardness == 0.0 {
+        return 1.0;
+    }
+    let held_item = inventory.get_held_item();
+    let tool = Tool::from_slot(held_item);
+    let block_id = builtin::get_id(builtin::BLOCK, name);
+
+    let mut speed = tool.get_speed(registries, block_id);
+    if speed > 1.0 {
+        let efficiency = get_enchantment_level(registries, held_item, EFFICIENCY);
+        if efficiency > 0 {
+            speed += (efficiency * efficiency + 1) as f32;
+        }
+    }
+    let helmet = inventory
+        .get_player_inventory()
+        .get_slot(HELMET_SLOT)
+        .cloned()
+        .unwrap_or_else(types::Slot::new_empty);
+    if physics.is_in_water() && get_enchantment_level(registries, &helmet, AQUA_AFFINITY) == 0 {
+        speed *= SUBMERGED_SPEED_MODIFIER;
+    }
+    if !physics.is_on_ground() {
+        speed *= AIRBORNE_SPEED_MODIFIER;
+    }
+
+    let is_correct_tool =
+        !requires_correct_tool(name) || tool.is_correct_for_drops(registries, block_id);
+    let divisor = if is_correct_tool {
+        CORRECT_TOOL_DIVISOR
+    } else {
+        WRONG_TOOL_DIVISOR
+    };
+    speed / hardness / divisor
+}
+
+// the state a block turns into when it is broken, waterlogged blocks leave water behind
+fn get_broken_state(state: &BlockState) -> i32 {
+    let registry = match get_block_registry() {
+        Some(registry) => registry,
+        None => return AIR_BLOCK_STATE,
+    };
+    match state.get_property("waterlogged") {
+        Some("true") => registry
+            .get_default_state_id("minecraft:water")
+            .unwrap_or(AIR_BLOCK_STATE),
+        _ => AIR_BLOCK_STATE,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiggingRequest {
+    Start {
+        position: types::Position,
+        face: BlockFace,
+    },
+    Abort,
+}
+
+#[derive(Debug, Clone)]
+struct DiggingTarget {
+    position: types::Position,
+    face: BlockFace,
+    state: i32,
+    held_item: i32, // digging starts over when the held item changes
+    progress: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiggingController {
+    game_mode: GameMode,
+    target: Option<DiggingTarget>,
+    request: Option<DiggingRequest>, // handled on the next tick
+    delay: u32,
+    packets: Vec<ServerboundPlayPacket>,
+}
+
+impl DiggingController {
+    pub fn new(game_mode: GameMode) -> Self {
+        Self {
+            game_mode,
+            target: None,
+            request: None,
+            delay: 0,
+            packets: Vec::new(),
+        }
+    }
+
+    pub fn get_game_mode(&self) -> GameMode {
+        self.game_mode
+    }
+
+    // the block being broken
+    pub fn get_target(&self) -> Option<&types::Position> {
+        self.target.as_ref().map(|target| &target.position)
+    }
+
+    // from 0 to 1, how much of the target has been broken
+    pub fn get_progress(&self) -> f32 {
+        self.target.as_ref().map_or(0.0, |target| target.progress)
+    }
+
+    pub fn is_digging(&self) -> bool {
+        self.target.is_some() || matches!(self.request, Some(DiggingRequest::Start { .. }))
+    }
+
+    // starts breaking the block on the next tick, the block currently being broken is abandoned
+    pub fn start(&mut self, position: &types::Position, face: BlockFace) {
+        self.request = Some(DiggingRequest::Start {
+            position: *position,
+            face,
+        });
+    }
+
+    pub fn abort(&mut self) {
+        self.request = Some(DiggingRequest::Abort);
+    }
+
+    // packets to send to the server, produced by `tick`
+    pub fn take_packets(&mut self) -> Vec<ServerboundPlayPacket> {
+        std::mem::take(&mut self.packets)
+    }
+
+    fn send_action(
+        &mut self,
+        world: &mut World,
+        status: PlayerActionStatus,
+        position: &types::Position,
+        face: BlockFace,
+    ) -> i32 {
+        let sequence = world.next_sequence();
+        self.packets.push(ServerboundPlayPacket::PlayerAction {
+            status,
+            location: *position,
+            face,
+            sequence: sequence.into(),
+        });
+        sequence
+    }
+
+    fn swing_arm(&mut self) {
+        self.packets.push(ServerboundPlayPacket::SwingArm {
+            hand: HandEnum::MainHand,
+        });
+    }
+
+    // removes the block from the world until the server acknowledges the change
+    fn destroy_block(world: &mut World, position: &types::Position, sequence: i32) {
+        if let Some(state) = world.get_block_state(position) {
+            world.predict_block(position, get_broken_state(state), sequence);
+        }
+    }
+
+    fn abort_target(&mut self, world: &mut World) {
+        if let Some(target) = self.target.take() {
+            self.send_action(
+                world,
+                PlayerActionStatus::CancelledDigging,
+                &target.position,
+                target.face,
+            );
+        }
+    }
+
+    fn start_digging(
+        &mut self,
+        world: &mut World,
+        inventory: &Inventory,
+        physics: &PlayerPhysics,
+        registries: &RegistryStore,
+        position: types::Position,
+        face: BlockFace,
+    ) {
+        // the `can_break` predicates that allow breaking blocks in adventure mode are not checked
+        if matches!(self.game_mode, GameMode::Spectator | GameMode::Adventure) {
+            return;
+        }
+        if self.game_mode == GameMode::Creative {
+            self.abort_target(world);
+            let sequence =
+                self.send_action(world, PlayerActionStatus::StartedDigging, &position, face);
+            Self::destroy_block(world, &position, sequence);
+            self.delay = DESTROY_DELAY;
+            self.swing_arm();
+            return;
+        }
+        if self
+            .target
+            .as_ref()
+            .is_some_and(|target| target.position == position)
+        {
+            return;
+        }
+        self.abort_target(world);
+        let state = match world.get_block_state(&position) {
+            Some(state)
+                if get_block_hardness(state.get_name()).is_some_and(|hardness| hardness >= 0.0) =>
+            {
+                state
+            }
+            _ => return,
+        };
+        if get_block_registry().is_some_and(|registry| registry.is_air(state.get_id())) {
+            return;
+        }
+        let sequence = self.send_action(world, PlayerActionStatus::StartedDigging, &position, face);
+        if get_destroy_progress(state, inventory, physics, registries) >= 1.0 {
+            // the server breaks the block when it receives the start
+            Self::destroy_block(world, &position, sequence);
+        } else {
+            self.target = Some(DiggingTarget {
+                position,
+                face,
+                state: state.get_id(),
+                held_item: inventory.get_held_item().item_id,
+                progress: 0.0,
+            });
+        }
+        self.swing_arm();
+    }
+
+    fn continue_digging(
+        &mut self,
+        world: &mut World,
+        inventory: &Inventory,
+        physics: &PlayerPhysics,
+        registries: &RegistryStore,
+    ) {
+        let target = match self.target.as_mut() {
+            Some(target) => target,
+            None => return,
+        };
+        let state = match world.get_block_state(&target.position) {
+            Some(state) => state,
+            None => {
+                // the chunk was unloaded
+                self.target = None;
+                return;
+            }
+        };
+        if state.get_id() != target.state {
+            // the block was changed by someone else
+            self.target = None;
+            return;
+        }
+        if inventory.get_held_item().item_id != target.held_item {
+            let (position, face) = (target.position, target.face);
+            self.abort_target(world);
+            self.start_digging(world, inventory, physics, registries, position, face);
+            return;
+        }
+        target.progress += get_destroy_progress(state, inventory, physics, registries);
+        if target.progress >= 1.0 {
+            let (position, face) = (target.position, target.face);
+            self.target = None;
+            let sequence =
+                self.send_action(world, PlayerActionStatus::FinishedDigging, &position, face);
+            Self::destroy_block(world, &position, sequence);
+            self.delay = DESTROY_DELAY;
+        }
+        self.swing_arm();
+    }
+
+    pub fn tick(
+        &mut self,
+        world: &mut World,
+        inventory: &Inventory,
+        physics: &PlayerPhysics,
+        registries: &RegistryStore,
+    ) {
+        match self.request.take() {
+            Some(DiggingRequest::Start { position, face }) => {
+                self.start_digging(world, inventory, physics, registries, position, face);
+                return;
+            }
+            Some(DiggingRequest::Abort) => {
+                self.abort_target(world);
+                return;
+            }
+            None => {}
+        }
+        if self.delay > 0 {
+            self.delay -= 1;
+            return;
+        }
+        self.continue_digging(world, inventory, physics, registries);
+    }
+
+    // follows the game mode of the player
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::Login { game_mode, .. }
+            | ClientboundPlayPacket::Respawn { game_mode, .. } => {
+                self.game_mode = *game_mode;
+                self.target = None;
+                self.request = None;
+            }
+            ClientboundPlayPacket::GameEvent {
+                event: GameEventType::ChangeGameMode,
+                value,
+            } => {
+                if let Some(game_mode) = GameMode::from_id(value.get_value() as i32) {
+                    self.game_mode = game_mode;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::clientbound::configuration::{RegistryTag, RegistryTagMap};
+    use crate::minecraft::registry::blocks::{BlockRegistry, set_test_block_registry};
+    use crate::minecraft::registry::builtin::{BuiltinRegistries, set_test_builtin_registries};
+
+    #[test]
+    fn destroy_progress_of_stone() {
+        let mut blocks = BlockRegistry::from_json(
+            r#"{
+                "minecraft:air": {"states": [{"id": 0, "default": true}]},
+                "minecraft:stone": {"states": [{"id": 1, "default": true}]}
+            }"#,
+        )
+        .unwrap();
+        blocks
+            .load_hardness(
+                r#"[
+                    {"name": "stone", "hardness": 1.5, "harvestTools": {"792": true}},
+                    {"name": "air", "hardness": 0}
+                ]"#,
+            )
+            .unwrap();
+        let blocks = set_test_block_registry(blocks);
+        let builtin = set_test_builtin_registries(
+            BuiltinRegistries::from_json(
+                r#"{
+                    "minecraft:item": {"default": "minecraft:air", "entries": {
+                        "minecraft:air": {"protocol_id": 0},
+                        "minecraft:wooden_pickaxe": {"protocol_id": 1}
+                    }},
+                    "minecraft:block": {"default": "minecraft:air", "entries": {
+                        "minecraft:air": {"protocol_id": 0},
+                        "minecraft:stone": {"protocol_id": 1}
+                    }}
+                }"#,
+            )
+            .unwrap(),
+        );
+        let stone = blocks
+            .get_state(blocks.get_default_state_id("minecraft:stone").unwrap())
+            .unwrap();
+        let stone_id = builtin.get_id(builtin::BLOCK, "minecraft:stone").unwrap();
+        let pickaxe_id = builtin
+            .get_id(builtin::ITEM, "minecraft:wooden_pickaxe")
+            .unwrap();
+
+        let mut registries = RegistryStore::new();
+        registries.update_tags(&[RegistryTagMap {
+            registry: "minecraft:block".into(),
+            tagsmap: types::Array::new(vec![RegistryTag {
+                name: "minecraft:mineable/pickaxe".into(),
+                entries: types::Array::new(vec![stone_id.into()]),
+            }]),
+        }]);
+        // the player is in the air until the first tick
+        let physics = PlayerPhysics::new(0);
+        let mut inventory = Inventory::new();
+
+        // stone drops nothing without a pickaxe, the slower divisor is used by hand
+        let progress = get_destroy_progress(stone, &inventory, &physics, &registries);
+        assert!((progress - AIRBORNE_SPEED_MODIFIER / 1.5 / WRONG_TOOL_DIVISOR).abs() < 1e-6);
+
+        inventory.apply_packet(&ClientboundPlayPacket::SetContainerSlot {
+            window_id: 0.into(),
+            state_id: 0.into(),
+            slot: 36.into(),
+            slot_data: types::Slot::new(pickaxe_id, 1),
+        });
+        // a wooden pickaxe mines at a speed of 2, stone has a hardness of 1.5
+        let progress = get_destroy_progress(stone, &inventory, &physics, &registries);
+        let expected = 2.0 * AIRBORNE_SPEED_MODIFIER / 1.5 / CORRECT_TOOL_DIVISOR;
+        assert!((progress - expected).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,371 @@
+// keeps track of the entities the server has spawned around the player
+use std::collections::HashMap;
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::EntityMetadataValue;
+use crate::minecraft::registry::builtin;
+use crate::minecraft::types;
+
+use super::{Location, Velocity};
+
+// entity velocities are sent in 1/8000 of a block per tick
+const VELOCITY_UNIT: f64 = 8000.0;
+// relative moves are sent in 1/4096 of a block
+const DELTA_UNIT: f64 = 4096.0;
+
+const EXPERIENCE_ORB: &str = "minecraft:experience_orb";
+
+#[derive(Debug, Clone)]
+pub struct Entity {
+    entity_id: i32,
+    uuid: Option<u128>, // experience orbs do not have a uuid
+    entity_type: i32,   // ID in the minecraft:entity_type registry, -1 if unknown
+    location: Location, // yaw and pitch are in degrees
+    head_yaw: f32,      // in degrees
+    velocity: Velocity, // in blocks per tick
+    on_ground: bool,
+    data: i32, // meaning depends on the entity type, see Object Data
+    metadata: HashMap<u8, EntityMetadataValue>,
+    passengers: Vec<i32>,
+    vehicle: Option<i32>,
+}
+
+impl Entity {
+    pub fn get_entity_id(&self) -> i32 {
+        self.entity_id
+    }
+
+    pub fn get_uuid(&self) -> Option<u128> {
+        self.uuid
+    }
+
+    pub fn get_entity_type(&self) -> i32 {
+        self.entity_type
+    }
+
+    // e.g. "minecraft:zombie", requires the built-in registries to be loaded
+    pub fn get_entity_type_name(&self) -> Option<&'static str> {
+        builtin::get_entity_type_name(self.entity_type)
+    }
+
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn get_head_yaw(&self) -> f32 {
+        self.head_yaw
+    }
+
+    pub fn get_velocity(&self) -> &Velocity {
+        &self.velocity
+    }
+
+    pub fn is_on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    pub fn get_data(&self) -> i32 {
+        self.data
+    }
+
+    pub fn get_metadata(&self) -> &HashMap<u8, EntityMetadataValue> {
+        &self.metadata
+    }
+
+    pub fn get_metadata_value(&self, index: u8) -> Option<&EntityMetadataValue> {
+        self.metadata.get(&index)
+    }
+
+    pub fn get_passengers(&self) -> &Vec<i32> {
+        &self.passengers
+    }
+
+    pub fn get_vehicle(&self) -> Option<i32> {
+        self.vehicle
+    }
+
+    pub fn distance_squared(&self, x: f64, y: f64, z: f64) -> f64 {
+        let dx = self.location.x - x;
+        let dy = self.location.y - y;
+        let dz = self.location.z - z;
+        dx * dx + dy * dy + dz * dz
+    }
+
+    pub fn distance(&self, x: f64, y: f64, z: f64) -> f64 {
+        self.distance_squared(x, y, z).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EntityTracker {
+    entities: HashMap<i32, Entity>,
+}
+
+impl EntityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, entity_id: i32) -> Option<&Entity> {
+        self.entities.get(&entity_id)
+    }
+
+    pub fn get_by_uuid(&self, uuid: u128) -> Option<&Entity> {
+        self.entities.values().find(|e| e.uuid == Some(uuid))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    // the closest entity of the given type to a point
+    pub fn nearest(&self, entity_type: i32, x: f64, y: f64, z: f64) -> Option<&Entity> {
+        self.entities
+            .values()
+            .filter(|e| e.entity_type == entity_type)
+            .min_by(|a, b| {
+                a.distance_squared(x, y, z)
+                    .total_cmp(&b.distance_squared(x, y, z))
+            })
+    }
+
+    // same as `nearest` but takes the name of the entity type, e.g. "minecraft:zombie"
+    pub fn nearest_by_name(&self, entity_type: &str, x: f64, y: f64, z: f64) -> Option<&Entity> {
+        self.nearest(builtin::get_entity_type_id(entity_type)?, x, y, z)
+    }
+
+    // every entity within `radius` blocks of a point, closest first
+    pub fn within(&self, radius: f64, x: f64, y: f64, z: f64) -> Vec<&Entity> {
+        let mut entities: Vec<&Entity> = self
+            .entities
+            .values()
+            .filter(|e| e.distance_squared(x, y, z) <= radius * radius)
+            .collect();
+        entities.sort_by(|a, b| {
+            a.distance_squared(x, y, z)
+                .total_cmp(&b.distance_squared(x, y, z))
+        });
+        entities
+    }
+
+    fn spawn(&mut self, entity: Entity) {
+        self.entities.insert(entity.entity_id, entity);
+    }
+
+    fn remove(&mut self, entity_id: i32) {
+        if let Some(entity) = self.entities.remove(&entity_id) {
+            // detach the entity from its vehicle and passengers
+            if let Some(vehicle) = entity.vehicle.and_then(|id| self.entities.get_mut(&id)) {
+                vehicle.passengers.retain(|id| *id != entity_id);
+            }
+            for passenger in entity.passengers {
+                if let Some(passenger) = self.entities.get_mut(&passenger) {
+                    passenger.vehicle = None;
+                }
+            }
+        }
+    }
+
+    fn move_by(&mut self, entity_id: i32, delta: &types::ShortVec3, on_ground: bool) {
+        if let Some(entity) = self.entities.get_mut(&entity_id) {
+            entity.location.x += delta.x.get_value() as f64 / DELTA_UNIT;
+            entity.location.y += delta.y.get_value() as f64 / DELTA_UNIT;
+            entity.location.z += delta.z.get_value() as f64 / DELTA_UNIT;
+            entity.on_ground = on_ground;
+        }
+    }
+
+    fn rotate(&mut self, entity_id: i32, yaw: &types::Angle, pitch: &types::Angle) {
+        if let Some(entity) = self.entities.get_mut(&entity_id) {
+            entity.location.yaw = yaw.get_degrees();
+            entity.location.pitch = pitch.get_degrees();
+        }
+    }
+
+    // applies a play packet to the tracked entities, returns true if the packet was used
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::SpawnEntity {
+                entity_id,
+                entity_uuid,
+                entity_type,
+                position,
+                pitch,
+                yaw,
+                head_yaw,
+                data,
+                velocity,
+            } => {
+                self.spawn(Entity {
+                    entity_id: entity_id.get_value(),
+                    uuid: Some(entity_uuid.get_value()),
+                    entity_type: entity_type.get_value(),
+                    location: Location {
+                        x: position.x.get_value(),
+                        y: position.y.get_value(),
+                        z: position.z.get_value(),
+                        yaw: yaw.get_degrees(),
+                        pitch: pitch.get_degrees(),
+                    },
+                    head_yaw: head_yaw.get_degrees(),
+                    velocity: Velocity {
+                        x: velocity.x.get_value() as f64 / VELOCITY_UNIT,
+                        y: velocity.y.get_value() as f64 / VELOCITY_UNIT,
+                        z: velocity.z.get_value() as f64 / VELOCITY_UNIT,
+                    },
+                    on_ground: false,
+                    data: data.get_value(),
+                    metadata: HashMap::new(),
+                    passengers: Vec::new(),
+                    vehicle: None,
+                });
+            }
+            ClientboundPlayPacket::SpawnExperienceOrb {
+                entity_id,
+                position,
+                count,
+            } => {
+                self.spawn(Entity {
+                    entity_id: entity_id.get_value(),
+                    uuid: None,
+                    entity_type: builtin::get_entity_type_id(EXPERIENCE_ORB).unwrap_or(-1),
+                    location: Location {
+                        x: position.x.get_value(),
+                        y: position.y.get_value(),
+                        z: position.z.get_value(),
+                        yaw: 0.0,
+                        pitch: 0.0,
+                    },
+                    head_yaw: 0.0,
+                    velocity: Velocity {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                    on_ground: false,
+                    data: count.get_value() as i32,
+                    metadata: HashMap::new(),
+                    passengers: Vec::new(),
+                    vehicle: None,
+                });
+            }
+            ClientboundPlayPacket::UpdateEntityPosition {
+                entity_id,
+                delta,
+                on_ground,
+            } => {
+                self.move_by(entity_id.get_value(), delta, on_ground.get_value());
+            }
+            ClientboundPlayPacket::UpdateEntityPositionAndRotation {
+                entity_id,
+                delta,
+                yaw,
+                pitch,
+                on_ground,
+            } => {
+                self.move_by(entity_id.get_value(), delta, on_ground.get_value());
+                self.rotate(entity_id.get_value(), yaw, pitch);
+            }
+            ClientboundPlayPacket::UpdateEntityRotation {
+                entity_id,
+                yaw,
+                pitch,
+                on_ground,
+            } => {
+                self.rotate(entity_id.get_value(), yaw, pitch);
+                if let Some(entity) = self.entities.get_mut(&entity_id.get_value()) {
+                    entity.on_ground = on_ground.get_value();
+                }
+            }
+            ClientboundPlayPacket::TeleportEntity {
+                entity_id,
+                position,
+                yaw,
+                pitch,
+                on_ground,
+            } => {
+                if let Some(entity) = self.entities.get_mut(&entity_id.get_value()) {
+                    entity.location.x = position.x.get_value();
+                    entity.location.y = position.y.get_value();
+                    entity.location.z = position.z.get_value();
+                    entity.on_ground = on_ground.get_value();
+                }
+                self.rotate(entity_id.get_value(), yaw, pitch);
+            }
+            ClientboundPlayPacket::SetEntityVelocity {
+                entity_id,
+                velocity,
+            } => {
+                if let Some(entity) = self.entities.get_mut(&entity_id.get_value()) {
+                    entity.velocity = Velocity {
+                        x: velocity.x.get_value() as f64 / VELOCITY_UNIT,
+                        y: velocity.y.get_value() as f64 / VELOCITY_UNIT,
+                        z: velocity.z.get_value() as f64 / VELOCITY_UNIT,
+                    };
+                }
+            }
+            ClientboundPlayPacket::SetHeadRotation {
+                entity_id,
+                head_yaw,
+            } => {
+                if let Some(entity) = self.entities.get_mut(&entity_id.get_value()) {
+                    entity.head_yaw = head_yaw.get_degrees();
+                }
+            }
+            ClientboundPlayPacket::SetEntityMetadata {
+                entity_id,
+                metadata,
+            } => {
+                if let Some(entity) = self.entities.get_mut(&entity_id.get_value()) {
+                    for entry in &metadata.metadata {
+                        entity
+                            .metadata
+                            .insert(entry.index.get_value(), entry.value.clone());
+                    }
+                }
+            }
+            ClientboundPlayPacket::SetPassengers {
+                entity_id,
+                passengers,
+            } => {
+                let vehicle_id = entity_id.get_value();
+                let passengers: Vec<i32> = passengers.iter().map(|p| p.get_value()).collect();
+                // passengers that are no longer riding the vehicle
+                if let Some(vehicle) = self.entities.get(&vehicle_id) {
+                    for old in vehicle.passengers.clone() {
+                        if let Some(passenger) = self.entities.get_mut(&old) {
+                            passenger.vehicle = None;
+                        }
+                    }
+                }
+                for id in &passengers {
+                    if let Some(passenger) = self.entities.get_mut(id) {
+                        passenger.vehicle = Some(vehicle_id);
+                    }
+                }
+                if let Some(vehicle) = self.entities.get_mut(&vehicle_id) {
+                    vehicle.passengers = passengers;
+                }
+            }
+            ClientboundPlayPacket::RemoveEntities { entity_ids } => {
+                for entity_id in entity_ids.iter() {
+                    self.remove(entity_id.get_value());
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
@@ -0,0 +1,597 @@
+// finds the block under the crosshair and predicts the blocks placed by the player,
+// the way the vanilla client does before the server confirms them
+use crate::minecraft::chunk::AIR_BLOCK_STATE;
+use crate::minecraft::registry::blocks::get_block_registry;
+use crate::minecraft::registry::builtin;
+use crate::minecraft::registry::dynamic::RegistryStore;
+use crate::minecraft::serverbound::play::BlockFace;
+use crate::minecraft::types;
+
+use super::physics::{Aabb, PlayerPhysics, get_block_shape};
+use super::world::World;
+
+pub const DEFAULT_BLOCK_REACH: f64 = 4.5; // the block interaction range attribute of players
+
+const REPLACEABLE_TAG: &str = "minecraft:replaceable";
+
+// replaced by placed blocks, used when the server has not sent its block tags
+const REPLACEABLE_BLOCKS: [&str; 14] = [
+    "minecraft:air",
+    "minecraft:cave_air",
+    "minecraft:void_air",
+    "minecraft:water",
+    "minecraft:lava",
+    "minecraft:short_grass",
+    "minecraft:tall_grass",
+    "minecraft:fern",
+    "minecraft:large_fern",
+    "minecraft:dead_bush",
+    "minecraft:seagrass",
+    "minecraft:vine",
+    "minecraft:fire",
+    "minecraft:soul_fire",
+];
+
+// blocks that do something when clicked by hand, nothing is placed against them unless sneaking
+const INTERACTABLE_BLOCKS: [&str; 46] = [
+    "minecraft:chest",
+    "minecraft:trapped_chest",
+    "minecraft:ender_chest",
+    "minecraft:barrel",
+    "minecraft:furnace",
+    "minecraft:blast_furnace",
+    "minecraft:smoker",
+    "minecraft:hopper",
+    "minecraft:dispenser",
+    "minecraft:dropper",
+    "minecraft:crafter",
+    "minecraft:crafting_table",
+    "minecraft:cartography_table",
+    "minecraft:smithing_table",
+    "minecraft:enchanting_table",
+    "minecraft:anvil",
+    "minecraft:chipped_anvil",
+    "minecraft:damaged_anvil",
+    "minecraft:stonecutter",
+    "minecraft:grindstone",
+    "minecraft:loom",
+    "minecraft:brewing_stand",
+    "minecraft:beacon",
+    "minecraft:lectern",
+    "minecraft:chiseled_bookshelf",
+    "minecraft:decorated_pot",
+    "minecraft:shulker_box",
+    "minecraft:lever",
+    "minecraft:stone_button",
+    "minecraft:polished_blackstone_button",
+    "minecraft:repeater",
+    "minecraft:comparator",
+    "minecraft:daylight_detector",
+    "minecraft:note_block",
+    "minecraft:jukebox",
+    "minecraft:bell",
+    "minecraft:cake",
+    "minecraft:candle_cake",
+    "minecraft:respawn_anchor",
+    "minecraft:dragon_egg",
+    "minecraft:flower_pot",
+    "minecraft:command_block",
+    "minecraft:chain_command_block",
+    "minecraft:repeating_command_block",
+    "minecraft:structure_block",
+    "minecraft:jigsaw",
+];
+// doors, trapdoors, fence gates, buttons and signs of every wood type can be used by hand
+const WOOD_TYPES: [&str; 11] = [
+    "oak", "spruce", "birch", "jungle", "acacia", "dark_oak", "mangrove", "cherry", "bamboo",
+    "crimson", "warped",
+];
+const WOODEN_INTERACTABLES: [&str; 8] = [
+    "door",
+    "trapdoor",
+    "fence_gate",
+    "button",
+    "sign",
+    "wall_sign",
+    "hanging_sign",
+    "wall_hanging_sign",
+];
+// copper doors and trapdoors open by hand, unlike the iron ones
+const COPPER_TYPES: [&str; 8] = [
+    "copper",
+    "exposed_copper",
+    "weathered_copper",
+    "oxidized_copper",
+    "waxed_copper",
+    "waxed_exposed_copper",
+    "waxed_weathered_copper",
+    "waxed_oxidized_copper",
+];
+const COPPER_INTERACTABLES: [&str; 2] = ["door", "trapdoor"];
+const DYE_COLORS: [&str; 16] = [
+    "white",
+    "orange",
+    "magenta",
+    "light_blue",
+    "yellow",
+    "lime",
+    "pink",
+    "gray",
+    "light_gray",
+    "cyan",
+    "purple",
+    "blue",
+    "brown",
+    "green",
+    "red",
+    "black",
+];
+const COLORED_INTERACTABLES: [&str; 3] = ["bed", "shulker_box", "candle_cake"];
+
+// items that place a block with a different name
+const ITEM_BLOCKS: [(&str, &str); 6] = [
+    ("minecraft:redstone", "minecraft:redstone_wire"),
+    ("minecraft:string", "minecraft:tripwire"),
+    ("minecraft:wheat_seeds", "minecraft:wheat"),
+    ("minecraft:carrot", "minecraft:carrots"),
+    ("minecraft:potato", "minecraft:potatoes"),
+    ("minecraft:beetroot_seeds", "minecraft:beetroots"),
+];
+
+// the block a ray hit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockHit {
+    position: types::Position,
+    face: BlockFace,
+    location: (f64, f64, f64), // where the ray hit the block
+    inside_block: bool,        // the ray started inside the block
+}
+
+impl BlockHit {
+    // a click on the block at the cursor, from 0 to 1 on every axis
+    pub fn new(
+        position: types::Position,
+        face: BlockFace,
+        cursor: (f32, f32, f32),
+        inside_block: bool,
+    ) -> Self {
+        Self {
+            position,
+            face,
+            location: (
+                position.get_x() as f64 + cursor.0 as f64,
+                position.get_y() as f64 + cursor.1 as f64,
+                position.get_z() as f64 + cursor.2 as f64,
+            ),
+            inside_block,
+        }
+    }
+
+    pub fn get_position(&self) -> &types::Position {
+        &self.position
+    }
+
+    pub fn get_face(&self) -> BlockFace {
+        self.face
+    }
+
+    pub fn get_location(&self) -> (f64, f64, f64) {
+        self.location
+    }
+
+    // the hit location inside the block, from 0 to 1 on every axis
+    pub fn get_cursor(&self) -> (f32, f32, f32) {
+        (
+            (self.location.0 - self.position.get_x() as f64) as f32,
+            (self.location.1 - self.position.get_y() as f64) as f32,
+            (self.location.2 - self.position.get_z() as f64) as f32,
+        )
+    }
+
+    pub fn is_inside_block(&self) -> bool {
+        self.inside_block
+    }
+}
+
+// the direction the player looks at, rotations are in degrees like in the movement packets
+pub fn get_look_direction(yaw: f32, pitch: f32) -> (f64, f64, f64) {
+    let yaw = (yaw as f64).to_radians();
+    let pitch = (pitch as f64).to_radians();
+    (
+        -yaw.sin() * pitch.cos(),
+        -pitch.sin(),
+        yaw.cos() * pitch.cos(),
+    )
+}
+
+// the boxes a ray can hit, blocks without collision like plants and signs are hit as full cubes
+fn get_outline_shape(state: i32) -> Vec<Aabb> {
+    let full = vec![Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)];
+    let registry = match get_block_registry() {
+        Some(registry) => registry,
+        None if state == AIR_BLOCK_STATE => return Vec::new(),
+        None => return full,
+    };
+    let block = match registry.get_state(state) {
+        Some(block) => block,
+        None => return full,
+    };
+    let name = block.get_name();
+    // fluids are not targeted
+    if registry.is_air(state) || name == "minecraft:water" || name == "minecraft:lava" {
+        return Vec::new();
+    }
+    if name == "minecraft:snow" {
+        let layers: f64 = block
+            .get_property("layers")
+            .and_then(|layers| layers.parse().ok())
+            .unwrap_or(1.0);
+        return vec![Aabb::new(0.0, 0.0, 0.0, 1.0, layers * 0.125, 1.0)];
+    }
+    // fences and walls are only as high as a block
+    let shape: Vec<Aabb> = get_block_shape(state)
+        .into_iter()
+        .map(|part| Aabb {
+            max_y: part.max_y.min(1.0),
+            ..part
+        })
+        .collect();
+    if shape.is_empty() { full } else { shape }
+}
+
+// where the segment enters the box, as a fraction of the segment, and the face it enters through
+fn clip_box(
+    aabb: &Aabb,
+    from: (f64, f64, f64),
+    delta: (f64, f64, f64),
+) -> Option<(f64, BlockFace)> {
+    let axes = [
+        (
+            from.0,
+            delta.0,
+            aabb.min_x,
+            aabb.max_x,
+            BlockFace::West,
+            BlockFace::East,
+        ),
+        (
+            from.1,
+            delta.1,
+            aabb.min_y,
+            aabb.max_y,
+            BlockFace::Bottom,
+            BlockFace::Top,
+        ),
+        (
+            from.2,
+            delta.2,
+            aabb.min_z,
+            aabb.max_z,
+            BlockFace::North,
+            BlockFace::South,
+        ),
+    ];
+    let mut enter = f64::NEG_INFINITY;
+    let mut exit = f64::INFINITY;
+    let mut face = None;
+    for (start, delta, min, max, min_face, max_face) in axes {
+        if delta == 0.0 {
+            if start < min || start > max {
+                return None;
+            }
+            continue;
+        }
+        let (near, far, near_face) = if delta > 0.0 {
+            ((min - start) / delta, (max - start) / delta, min_face)
+        } else {
+            ((max - start) / delta, (min - start) / delta, max_face)
+        };
+        if near > enter {
+            enter = near;
+            face = Some(near_face);
+        }
+        exit = exit.min(far);
+    }
+    if enter > exit || exit < 0.0 || enter > 1.0 {
+        return None;
+    }
+    Some((enter.max(0.0), face?))
+}
+
+// the first block on the segment, stops at unloaded chunks
+pub fn ray_cast(world: &World, from: (f64, f64, f64), to: (f64, f64, f64)) -> Option<BlockHit> {
+    let delta = (to.0 - from.0, to.1 - from.1, to.2 - from.2);
+    let (mut x, mut y, mut z) = (
+        from.0.floor() as i32,
+        from.1.floor() as i32,
+        from.2.floor() as i32,
+    );
+    let end = (
+        to.0.floor() as i32,
+        to.1.floor() as i32,
+        to.2.floor() as i32,
+    );
+    // fraction of the segment to the next block boundary on each axis, and between boundaries
+    let step = |delta: f64| if delta > 0.0 { 1 } else { -1 };
+    let boundary = |start: f64, block: i32, delta: f64| {
+        if delta == 0.0 {
+            f64::INFINITY
+        } else if delta > 0.0 {
+            (block as f64 + 1.0 - start) / delta
+        } else {
+            (block as f64 - start) / delta
+        }
+    };
+    let spacing = |delta: f64| {
+        if delta == 0.0 {
+            f64::INFINITY
+        } else {
+            1.0 / delta.abs()
+        }
+    };
+    let mut next = (
+        boundary(from.0, x, delta.0),
+        boundary(from.1, y, delta.1),
+        boundary(from.2, z, delta.2),
+    );
+    let spacing = (spacing(delta.0), spacing(delta.1), spacing(delta.2));
+    loop {
+        let state = world.get_block_at(x, y, z)?;
+        let hit = get_outline_shape(state)
+            .iter()
+            .filter_map(|part| {
+                let part = part.offset(x as f64, y as f64, z as f64);
+                clip_box(&part, from, delta).map(|(t, face)| (t, face, part))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((t, face, part)) = hit {
+            let inside_block = part.min_x <= from.0
+                && from.0 <= part.max_x
+                && part.min_y <= from.1
+                && from.1 <= part.max_y
+                && part.min_z <= from.2
+                && from.2 <= part.max_z;
+            return Some(BlockHit {
+                position: types::Position::new(x, y as i16, z),
+                face,
+                location: (
+                    from.0 + delta.0 * t,
+                    from.1 + delta.1 * t,
+                    from.2 + delta.2 * t,
+                ),
+                inside_block,
+            });
+        }
+        if (x, y, z) == end || next.0.min(next.1).min(next.2) > 1.0 {
+            return None;
+        }
+        if next.0 <= next.1 && next.0 <= next.2 {
+            x += step(delta.0);
+            next.0 += spacing.0;
+        } else if next.1 <= next.2 {
+            y += step(delta.1);
+            next.1 += spacing.1;
+        } else {
+            z += step(delta.2);
+            next.2 += spacing.2;
+        }
+    }
+}
+
+pub fn get_eye_location(physics: &PlayerPhysics) -> (f64, f64, f64) {
+    let location = physics.get_location();
+    (
+        location.x,
+        location.y + physics.get_eye_height(),
+        location.z,
+    )
+}
+
+// the block under the crosshair of the player
+pub fn get_target_block(world: &World, physics: &PlayerPhysics, reach: f64) -> Option<BlockHit> {
+    let location = physics.get_location();
+    let eye = get_eye_location(physics);
+    let direction = get_look_direction(location.yaw, location.pitch);
+    let to = (
+        eye.0 + direction.0 * reach,
+        eye.1 + direction.1 * reach,
+        eye.2 + direction.2 * reach,
+    );
+    ray_cast(world, eye, to)
+}
+
+// the block hit when looking at the center of the block, None if another block is in the way
+pub fn aim_at_block(
+    world: &World,
+    physics: &PlayerPhysics,
+    position: &types::Position,
+    reach: f64,
+) -> Option<BlockHit> {
+    let eye = get_eye_location(physics);
+    let center = (
+        position.get_x() as f64 + 0.5,
+        position.get_y() as f64 + 0.5,
+        position.get_z() as f64 + 0.5,
+    );
+    let delta = (center.0 - eye.0, center.1 - eye.1, center.2 - eye.2);
+    let distance = (delta.0 * delta.0 + delta.1 * delta.1 + delta.2 * delta.2).sqrt();
+    if distance == 0.0 {
+        return None;
+    }
+    // past the center, for blocks whose shape does not contain it
+    let scale = reach.max(distance + 1.0) / distance;
+    let to = (
+        eye.0 + delta.0 * scale,
+        eye.1 + delta.1 * scale,
+        eye.2 + delta.2 * scale,
+    );
+    let hit = ray_cast(world, eye, to)?;
+    let location = hit.get_location();
+    let hit_distance = ((location.0 - eye.0).powi(2)
+        + (location.1 - eye.1).powi(2)
+        + (location.2 - eye.2).powi(2))
+    .sqrt();
+    (hit.position == *position && hit_distance <= reach).then_some(hit)
+}
+
+fn is_replaceable(registries: &RegistryStore, state: i32) -> bool {
+    let registry = match get_block_registry() {
+        Some(registry) => registry,
+        None => return state == AIR_BLOCK_STATE,
+    };
+    let block = match registry.get_state(state) {
+        Some(block) => block,
+        None => return false,
+    };
+    let name = block.get_name();
+    if name == "minecraft:snow" {
+        return block.get_property("layers") == Some("1");
+    }
+    match builtin::get_id(builtin::BLOCK, name) {
+        Some(id)
+            if registries
+                .get_tags()
+                .get_tag(builtin::BLOCK, REPLACEABLE_TAG)
+                .is_some() =>
+        {
+            registries
+                .get_tags()
+                .is_in_tag(builtin::BLOCK, REPLACEABLE_TAG, id)
+        }
+        _ => REPLACEABLE_BLOCKS.contains(&name),
+    }
+}
+
+// whether clicking the block opens or toggles it instead of using the held item
+pub fn is_interactable(state: i32) -> bool {
+    get_block_registry()
+        .and_then(|registry| registry.get_block_name(state))
+        .is_some_and(is_interactable_block)
+}
+
+// whether the name is "minecraft:<type>_<kind>" with one of the types and kinds
+fn is_block_variant(name: &str, types: &[&str], kinds: &[&str]) -> bool {
+    let name = match name.strip_prefix("minecraft:") {
+        Some(name) => name,
+        None => return false,
+    };
+    types.iter().any(|block_type| {
+        name.strip_prefix(block_type)
+            .and_then(|kind| kind.strip_prefix('_'))
+            .is_some_and(|kind| kinds.contains(&kind))
+    })
+}
+
+fn is_interactable_block(name: &str) -> bool {
+    INTERACTABLE_BLOCKS.contains(&name)
+        || name.starts_with("minecraft:potted_")
+        || is_block_variant(name, &WOOD_TYPES, &WOODEN_INTERACTABLES)
+        || is_block_variant(name, &COPPER_TYPES, &COPPER_INTERACTABLES)
+        || is_block_variant(name, &DYE_COLORS, &COLORED_INTERACTABLES)
+}
+
+// the block clicked on is replaced when it can be, otherwise the block is placed next to it
+pub fn get_placement_position(
+    world: &World,
+    registries: &RegistryStore,
+    hit: &BlockHit,
+) -> types::Position {
+    let position = hit.position;
+    if world
+        .get_block(&position)
+        .is_some_and(|state| is_replaceable(registries, state))
+    {
+        return position;
+    }
+    let (dx, dy, dz) = hit.face.get_offset();
+    types::Position::new(
+        position.get_x() + dx,
+        position.get_y() + dy as i16,
+        position.get_z() + dz,
+    )
+}
+
+// the block the item places and where, None if nothing would be placed. the default state is
+// predicted, the server sends the actual state (rotation, waterlogging...) before acknowledging
+pub fn predict_placement(
+    world: &World,
+    registries: &RegistryStore,
+    physics: &PlayerPhysics,
+    hit: &BlockHit,
+    item: &types::Slot,
+) -> Option<(types::Position, i32)> {
+    let registry = get_block_registry()?;
+    if item.is_empty() {
+        return None;
+    }
+    let item_name = item.item_name()?;
+    let block_name = ITEM_BLOCKS
+        .iter()
+        .find(|(item, _)| *item == item_name)
+        .map_or(item_name, |(_, block)| *block);
+    let state = registry.get_default_state_id(block_name)?;
+    let clicked = world.get_block(&hit.position)?;
+    if is_interactable(clicked) && !physics.is_sneaking() {
+        return None;
+    }
+    let position = get_placement_position(world, registries, hit);
+    if !world
+        .get_block(&position)
+        .is_some_and(|previous| is_replaceable(registries, previous))
+    {
+        return None;
+    }
+    // blocks are not placed inside the player
+    let player = physics.get_bounding_box();
+    let (x, y, z) = (
+        position.get_x() as f64,
+        position.get_y() as f64,
+        position.get_z() as f64,
+    );
+    if get_block_shape(state)
+        .iter()
+        .any(|part| part.offset(x, y, z).intersects(&player))
+    {
+        return None;
+    }
+    Some((position, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interactable_blocks() {
+        for name in [
+            "minecraft:chest",
+            "minecraft:lectern",
+            "minecraft:note_block",
+            "minecraft:jukebox",
+            "minecraft:cake",
+            "minecraft:respawn_anchor",
+            "minecraft:daylight_detector",
+            "minecraft:dark_oak_door",
+            "minecraft:bamboo_fence_gate",
+            "minecraft:cherry_wall_hanging_sign",
+            "minecraft:waxed_oxidized_copper_trapdoor",
+            "minecraft:light_blue_bed",
+            "minecraft:red_candle_cake",
+            "minecraft:potted_cactus",
+        ] {
+            assert!(is_interactable_block(name), "{}", name);
+        }
+        for name in [
+            "minecraft:stone",
+            "minecraft:iron_door",
+            "minecraft:iron_trapdoor",
+            "minecraft:fletching_table",
+            "minecraft:oak_planks",
+            "minecraft:oak_pressure_plate",
+            "minecraft:copper_block",
+            "minecraft:white_wool",
+        ] {
+            assert!(!is_interactable_block(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,704 @@
+// the player inventory and the open container, updated from container packets
+// click operations predict their result locally and build the matching Click Container packet,
+// the server corrects any wrong prediction with Set Container Slot/Content packets
+use std::collections::HashMap;
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::registry::builtin;
+use crate::minecraft::serverbound::ServerboundPlayPacket;
+use crate::minecraft::serverbound::play::{ChangedSlot, ClickContainerMode};
+use crate::minecraft::types;
+
+pub const PLAYER_WINDOW_ID: i32 = 0;
+
+// slots of the player inventory window
+pub const CRAFTING_RESULT_SLOT: usize = 0;
+pub const CRAFTING_GRID_START: usize = 1;
+pub const ARMOR_START: usize = 5;
+pub const MAIN_INVENTORY_START: usize = 9;
+pub const HOTBAR_START: usize = 36;
+pub const OFFHAND_SLOT: usize = 45;
+pub const PLAYER_INVENTORY_SIZE: usize = 46;
+
+pub const HOTBAR_SIZE: usize = 9;
+// the main inventory and the hotbar are shown below the slots of every container
+pub const INVENTORY_SIZE: usize = 36;
+
+// the slot of clicks outside the window
+pub const OUTSIDE_SLOT: i16 = -999;
+// swap button of the offhand
+pub const OFFHAND_BUTTON: u8 = 40;
+
+// special window ids of the Set Container Slot packet
+const CARRIED_ITEM_WINDOW_ID: i8 = -1;
+const ANY_PLAYER_SLOT_WINDOW_ID: i8 = -2;
+
+const DEFAULT_MAX_STACK_SIZE: i32 = 64;
+
+// window -2 uses the slot numbers of the player's own inventory (hotbar 0-8, main inventory
+// 9-35, armor 36-39 from boots to helmet, offhand 40), this maps them to player window slots
+fn get_player_window_slot(inventory_slot: usize) -> Option<usize> {
+    match inventory_slot {
+        0..HOTBAR_SIZE => Some(HOTBAR_START + inventory_slot),
+        MAIN_INVENTORY_START..HOTBAR_START => Some(inventory_slot),
+        36..=39 => Some(ARMOR_START + 39 - inventory_slot),
+        40 => Some(OFFHAND_SLOT),
+        _ => None,
+    }
+}
+
+// how many items of this kind fit in one slot, the max_stack_size component of the slot is used
+// if the server sent one, then the default components of the item, 64 if they are not loaded
+pub fn get_max_stack_size(slot: &types::Slot) -> i32 {
+    slot.get_max_stack_size()
+        .or_else(|| builtin::get_item_max_stack_size(slot.item_id))
+        .unwrap_or(DEFAULT_MAX_STACK_SIZE)
+}
+
+// the button group of a quick craft (dragging the carried item over slots)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickCraftKind {
+    Split,  // left mouse button, splits the carried items evenly
+    Single, // right mouse button, puts one item in every slot
+    Clone,  // middle mouse button, fills every slot, only in creative mode
+}
+
+impl QuickCraftKind {
+    fn get_button_offset(&self) -> i8 {
+        match self {
+            Self::Split => 0,
+            Self::Single => 4,
+            Self::Clone => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Container {
+    window_id: i32,
+    window_type: Option<i32>, // ID in the minecraft:menu registry, None for the player inventory
+    title: Option<types::NetworkNBT>,
+    state_id: i32, // the last state id sent by the server, it has to be sent back with every click
+    slots: Vec<types::Slot>,
+    properties: HashMap<i16, i16>, // e.g. the progress of a furnace
+}
+
+impl Container {
+    fn new(window_id: i32, window_type: Option<i32>, title: Option<types::NetworkNBT>) -> Self {
+        Self {
+            window_id,
+            window_type,
+            title,
+            state_id: 0,
+            slots: Vec::new(),
+            properties: HashMap::new(),
+        }
+    }
+
+    fn new_player_inventory() -> Self {
+        let mut container = Self::new(PLAYER_WINDOW_ID, None, None);
+        container.slots = vec![types::Slot::new_empty(); PLAYER_INVENTORY_SIZE];
+        container
+    }
+
+    pub fn get_window_id(&self) -> i32 {
+        self.window_id
+    }
+
+    pub fn get_window_type(&self) -> Option<i32> {
+        self.window_type
+    }
+
+    // e.g. "minecraft:generic_9x3", requires the built-in registries to be loaded
+    pub fn get_window_type_name(&self) -> Option<&'static str> {
+        builtin::get_name(builtin::MENU, self.window_type?)
+    }
+
+    pub fn get_title(&self) -> Option<&types::NetworkNBT> {
+        self.title.as_ref()
+    }
+
+    pub fn get_state_id(&self) -> i32 {
+        self.state_id
+    }
+
+    pub fn get_slots(&self) -> &Vec<types::Slot> {
+        &self.slots
+    }
+
+    pub fn get_slot(&self, slot: usize) -> Option<&types::Slot> {
+        self.slots.get(slot)
+    }
+
+    pub fn get_property(&self, property: i16) -> Option<i16> {
+        self.properties.get(&property).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub fn is_player_inventory(&self) -> bool {
+        self.window_type.is_none()
+    }
+
+    // number of slots that belong to the container and not to the player inventory shown below it
+    pub fn get_container_size(&self) -> usize {
+        if self.is_player_inventory() {
+            self.slots.len()
+        } else if self.slots.len() > INVENTORY_SIZE {
+            self.slots.len() - INVENTORY_SIZE
+        } else {
+            // e.g. lecterns do not show the player inventory
+            self.slots.len()
+        }
+    }
+
+    // the window slot of a hotbar slot (0-8)
+    pub fn get_hotbar_slot(&self, hotbar_slot: usize) -> usize {
+        if self.is_player_inventory() {
+            HOTBAR_START + hotbar_slot
+        } else {
+            self.get_container_size() + INVENTORY_SIZE - HOTBAR_SIZE + hotbar_slot
+        }
+    }
+
+    // the first slot holding the given item
+    pub fn find_item(&self, item_id: i32) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|s| !s.is_empty() && s.item_id == item_id)
+    }
+
+    // the total count of the given item in the container
+    pub fn count_item(&self, item_id: i32) -> i32 {
+        self.slots
+            .iter()
+            .filter(|s| !s.is_empty() && s.item_id == item_id)
+            .map(|s| s.item_count)
+            .sum()
+    }
+
+    fn set_slot(&mut self, slot: usize, item: types::Slot) {
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, types::Slot::new_empty());
+        }
+        self.slots[slot] = item;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    player: Container,
+    open: Option<Container>,
+    carried: types::Slot, // the item on the cursor
+    selected_slot: usize, // the selected hotbar slot (0-8)
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self {
+            player: Container::new_player_inventory(),
+            open: None,
+            carried: types::Slot::new_empty(),
+            selected_slot: 0,
+        }
+    }
+
+    pub fn get_player_inventory(&self) -> &Container {
+        &self.player
+    }
+
+    pub fn get_open_container(&self) -> Option<&Container> {
+        self.open.as_ref()
+    }
+
+    // the window clicks are sent to, the open container or the player inventory
+    pub fn get_window(&self) -> &Container {
+        self.open.as_ref().unwrap_or(&self.player)
+    }
+
+    fn get_window_mut(&mut self) -> &mut Container {
+        self.open.as_mut().unwrap_or(&mut self.player)
+    }
+
+    pub fn get_carried_item(&self) -> &types::Slot {
+        &self.carried
+    }
+
+    pub fn get_selected_slot(&self) -> usize {
+        self.selected_slot
+    }
+
+    pub fn get_held_item(&self) -> &types::Slot {
+        &self.player.slots[HOTBAR_START + self.selected_slot]
+    }
+
+    pub fn get_offhand_item(&self) -> &types::Slot {
+        &self.player.slots[OFFHAND_SLOT]
+    }
+
+    // selects a hotbar slot (0-8) and returns the packet that tells the server about it,
+    // None if the slot is not in the hotbar
+    pub fn select_slot(&mut self, hotbar_slot: usize) -> Option<ServerboundPlayPacket> {
+        if hotbar_slot >= HOTBAR_SIZE {
+            return None;
+        }
+        self.selected_slot = hotbar_slot;
+        Some(ServerboundPlayPacket::SetHeldItem {
+            slot: (hotbar_slot as i16).into(),
+        })
+    }
+
+    // the player inventory slot that a slot of the open container shows, if any
+    fn get_player_slot(&self, slot: usize) -> Option<usize> {
+        let open = self.open.as_ref()?;
+        let size = open.get_container_size();
+        if open.len() > size && slot >= size && slot < size + INVENTORY_SIZE {
+            Some(MAIN_INVENTORY_START + slot - size)
+        } else {
+            None
+        }
+    }
+
+    // the slot of the open container that shows a player inventory slot, if any
+    fn get_open_container_slot(&self, player_slot: usize) -> Option<usize> {
+        let open = self.open.as_ref()?;
+        let size = open.get_container_size();
+        if open.len() > size && (MAIN_INVENTORY_START..OFFHAND_SLOT).contains(&player_slot) {
+            Some(size + player_slot - MAIN_INVENTORY_START)
+        } else {
+            None
+        }
+    }
+
+    // the open container and the player inventory share the main inventory and hotbar slots,
+    // both setters keep them in sync
+    fn set_window_slot(&mut self, slot: usize, item: types::Slot) {
+        if let Some(player_slot) = self.get_player_slot(slot) {
+            self.player.set_slot(player_slot, item.clone());
+        }
+        self.get_window_mut().set_slot(slot, item);
+    }
+
+    fn set_player_slot(&mut self, slot: usize, item: types::Slot) {
+        if let Some(open_slot) = self.get_open_container_slot(slot)
+            && let Some(open) = self.open.as_mut()
+        {
+            open.set_slot(open_slot, item.clone());
+        }
+        self.player.set_slot(slot, item);
+    }
+
+    fn get_window_slot(&self, slot: usize) -> types::Slot {
+        self.get_window()
+            .get_slot(slot)
+            .cloned()
+            .unwrap_or_else(types::Slot::new_empty)
+    }
+
+    fn is_current_window(&self, window_id: i32) -> bool {
+        self.get_window().window_id == window_id
+    }
+
+    // applies a play packet to the inventory, returns true if the packet was used
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::OpenScreen {
+                window_id,
+                window_type,
+                window_title,
+            } => {
+                self.open = Some(Container::new(
+                    window_id.get_value(),
+                    Some(window_type.get_value()),
+                    Some(window_title.clone()),
+                ));
+            }
+            ClientboundPlayPacket::CloseContainer { window_id } => {
+                if self.open.as_ref().map(|c| c.window_id) == Some(window_id.get_value() as i32) {
+                    self.open = None;
+                }
+            }
+            ClientboundPlayPacket::SetContainerContent {
+                window_id,
+                state_id,
+                slots,
+                carriedi_item,
+            } => {
+                let window_id = window_id.get_value() as i32;
+                if !self.is_current_window(window_id) && window_id != PLAYER_WINDOW_ID {
+                    return true;
+                }
+                self.carried = carriedi_item.clone();
+                if window_id == PLAYER_WINDOW_ID {
+                    self.player.state_id = state_id.get_value();
+                    for (i, item) in slots.iter().enumerate() {
+                        self.set_player_slot(i, item.clone());
+                    }
+                } else {
+                    let window = self.get_window_mut();
+                    window.state_id = state_id.get_value();
+                    // replaced at once, the container size depends on the number of slots
+                    window.slots = slots.to_vec();
+                    for (i, item) in slots.iter().enumerate() {
+                        if let Some(player_slot) = self.get_player_slot(i) {
+                            self.player.set_slot(player_slot, item.clone());
+                        }
+                    }
+                }
+            }
+            ClientboundPlayPacket::SetContainerSlot {
+                window_id,
+                state_id,
+                slot,
+                slot_data,
+            } => {
+                let slot_index = slot.get_value();
+                match window_id.get_value() {
+                    CARRIED_ITEM_WINDOW_ID => self.carried = slot_data.clone(),
+                    ANY_PLAYER_SLOT_WINDOW_ID if slot_index >= 0 => {
+                        if let Some(slot) = get_player_window_slot(slot_index as usize) {
+                            self.set_player_slot(slot, slot_data.clone());
+                        }
+                    }
+                    id if slot_index >= 0 && id as i32 == PLAYER_WINDOW_ID => {
+                        self.player.state_id = state_id.get_value();
+                        self.set_player_slot(slot_index as usize, slot_data.clone());
+                    }
+                    id if slot_index >= 0 && self.is_current_window(id as i32) => {
+                        self.get_window_mut().state_id = state_id.get_value();
+                        self.set_window_slot(slot_index as usize, slot_data.clone());
+                    }
+                    _ => {}
+                }
+            }
+            ClientboundPlayPacket::SetContainerProperty {
+                window_id,
+                property,
+                value,
+            } => {
+                if let Some(open) = self.open.as_mut()
+                    && open.window_id == window_id.get_value() as i32
+                {
+                    open.properties
+                        .insert(property.get_value(), value.get_value());
+                }
+            }
+            ClientboundPlayPacket::SetHeldItem { slot } => {
+                let slot = slot.get_value();
+                if (0..HOTBAR_SIZE as i8).contains(&slot) {
+                    self.selected_slot = slot as usize;
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // builds a click packet, the changed slots are sent with their predicted contents
+    fn click(
+        &self,
+        slot: i16,
+        button: i8,
+        mode: ClickContainerMode,
+        changed: &[usize],
+    ) -> ServerboundPlayPacket {
+        let window = self.get_window();
+        let mut changed = changed.to_vec();
+        changed.sort_unstable();
+        changed.dedup();
+        ServerboundPlayPacket::ClickContainer {
+            window_id: (window.window_id as i8).into(),
+            state_id: window.state_id.into(),
+            slot: slot.into(),
+            button: button.into(),
+            mode,
+            changed_slots: types::Array::new(
+                changed
+                    .iter()
+                    .map(|i| ChangedSlot {
+                        slot_number: (*i as i16).into(),
+                        slot: self.get_window_slot(*i),
+                    })
+                    .collect(),
+            ),
+            carried_item: self.carried.clone(),
+        }
+    }
+
+    // left (or right) click on a slot, picks up, places or swaps items with the cursor
+    // clicking OUTSIDE_SLOT drops the carried stack (or one item of it)
+    pub fn pickup(&mut self, slot: i16, right_click: bool) -> ServerboundPlayPacket {
+        let button = if right_click { 1 } else { 0 };
+        if slot == OUTSIDE_SLOT {
+            let count = if right_click {
+                self.carried.item_count - 1
+            } else {
+                0
+            };
+            self.carried = self.carried.with_count(count);
+            return self.click(slot, button, ClickContainerMode::Pickup, &[]);
+        }
+        if slot < 0 {
+            return self.click(slot, button, ClickContainerMode::Pickup, &[]);
+        }
+
+        let index = slot as usize;
+        let item = self.get_window_slot(index);
+        let carried = self.carried.clone();
+        if carried.is_empty() {
+            if !item.is_empty() {
+                let taken = if right_click {
+                    (item.item_count + 1) / 2
+                } else {
+                    item.item_count
+                };
+                self.carried = item.with_count(taken);
+                self.set_window_slot(index, item.with_count(item.item_count - taken));
+            }
+        } else if item.is_empty() || item.is_same_item(&carried) {
+            let room = get_max_stack_size(&carried) - item.item_count.max(0);
+            let wanted = if right_click { 1 } else { carried.item_count };
+            let moved = wanted.min(room).max(0);
+            self.set_window_slot(index, carried.with_count(item.item_count.max(0) + moved));
+            self.carried = carried.with_count(carried.item_count - moved);
+        } else {
+            self.set_window_slot(index, carried);
+            self.carried = item;
+        }
+        self.click(slot, button, ClickContainerMode::Pickup, &[index])
+    }
+
+    // swaps a slot with a hotbar slot (0-8) or the offhand (OFFHAND_BUTTON)
+    pub fn swap(&mut self, slot: usize, button: u8) -> ServerboundPlayPacket {
+        let item = self.get_window_slot(slot);
+        let mut changed = vec![slot];
+        if button == OFFHAND_BUTTON {
+            let offhand = self.player.slots[OFFHAND_SLOT].clone();
+            self.set_window_slot(slot, offhand);
+            self.set_player_slot(OFFHAND_SLOT, item);
+            if self.open.is_none() {
+                changed.push(OFFHAND_SLOT);
+            }
+        } else if (button as usize) < HOTBAR_SIZE {
+            let hotbar_slot = self.get_window().get_hotbar_slot(button as usize);
+            let hotbar_item = self.get_window_slot(hotbar_slot);
+            self.set_window_slot(slot, hotbar_item);
+            self.set_window_slot(hotbar_slot, item);
+            changed.push(hotbar_slot);
+        }
+        self.click(
+            slot as i16,
+            button as i8,
+            ClickContainerMode::Swap,
+            &changed,
+        )
+    }
+
+    // moves a stack between the container and the player inventory (or the hotbar and the main
+    // inventory if no container is open), existing stacks are filled before empty slots
+    pub fn shift_click(&mut self, slot: usize) -> ServerboundPlayPacket {
+        let item = self.get_window_slot(slot);
+        if item.is_empty() {
+            return self.click(slot as i16, 0, ClickContainerMode::QuickMove, &[]);
+        }
+
+        let window = self.get_window();
+        let size = window.get_container_size();
+        let (targets, reverse): (Vec<usize>, bool) = if window.is_player_inventory() {
+            match slot {
+                // the result has to be crafted by the server
+                CRAFTING_RESULT_SLOT => (Vec::new(), false),
+                MAIN_INVENTORY_START..HOTBAR_START => {
+                    ((HOTBAR_START..OFFHAND_SLOT).collect(), false)
+                }
+                HOTBAR_START..OFFHAND_SLOT => {
+                    ((MAIN_INVENTORY_START..HOTBAR_START).collect(), false)
+                }
+                _ => ((MAIN_INVENTORY_START..OFFHAND_SLOT).collect(), false),
+            }
+        } else if slot < size {
+            ((size..window.len()).collect(), true)
+        } else {
+            ((0..size).collect(), false)
+        };
+        let targets: Vec<usize> = if reverse {
+            targets.into_iter().rev().collect()
+        } else {
+            targets
+        };
+
+        let max = get_max_stack_size(&item);
+        let mut remaining = item.item_count;
+        let mut changed = Vec::new();
+        // fill existing stacks first, then empty slots
+        for fill_empty in [false, true] {
+            for target in &targets {
+                if remaining <= 0 {
+                    break;
+                }
+                let existing = self.get_window_slot(*target);
+                let count = if existing.is_empty() {
+                    if !fill_empty {
+                        continue;
+                    }
+                    0
+                } else if existing.is_same_item(&item) {
+                    existing.item_count
+                } else {
+                    continue;
+                };
+                let moved = remaining.min(max - count);
+                if moved > 0 {
+                    self.set_window_slot(*target, item.with_count(count + moved));
+                    remaining -= moved;
+                    changed.push(*target);
+                }
+            }
+        }
+        if !changed.is_empty() {
+            self.set_window_slot(slot, item.with_count(remaining));
+            changed.push(slot);
+        }
+        self.click(slot as i16, 0, ClickContainerMode::QuickMove, &changed)
+    }
+
+    // drops one item (or the whole stack) of a slot, the cursor has to be empty
+    pub fn drop(&mut self, slot: usize, whole_stack: bool) -> ServerboundPlayPacket {
+        let button = if whole_stack { 1 } else { 0 };
+        let item = self.get_window_slot(slot);
+        if !self.carried.is_empty() || item.is_empty() {
+            return self.click(slot as i16, button, ClickContainerMode::Throw, &[]);
+        }
+        let count = if whole_stack { 0 } else { item.item_count - 1 };
+        self.set_window_slot(slot, item.with_count(count));
+        self.click(slot as i16, button, ClickContainerMode::Throw, &[slot])
+    }
+
+    // drags the carried item over the given slots, returns the start, add slot and end packets
+    // in the order they have to be sent
+    pub fn quick_craft(
+        &mut self,
+        slots: &[usize],
+        kind: QuickCraftKind,
+    ) -> Vec<ServerboundPlayPacket> {
+        let offset = kind.get_button_offset();
+        let mut packets =
+            vec![self.click(OUTSIDE_SLOT, offset, ClickContainerMode::QuickCraft, &[])];
+
+        let carried = self.carried.clone();
+        let max = get_max_stack_size(&carried);
+        // only empty slots and stacks of the same item with some room left take part
+        let mut targets: Vec<usize> = Vec::new();
+        for slot in slots {
+            let existing = self.get_window_slot(*slot);
+            if !targets.contains(slot)
+                && (existing.is_empty()
+                    || (existing.is_same_item(&carried) && existing.item_count < max))
+            {
+                targets.push(*slot);
+            }
+        }
+        for slot in &targets {
+            packets.push(self.click(
+                *slot as i16,
+                offset + 1,
+                ClickContainerMode::QuickCraft,
+                &[],
+            ));
+        }
+
+        let mut changed = Vec::new();
+        if !carried.is_empty() && !targets.is_empty() {
+            let mut remaining = carried.item_count;
+            let per_slot = match kind {
+                QuickCraftKind::Split => carried.item_count / targets.len() as i32,
+                QuickCraftKind::Single => 1,
+                QuickCraftKind::Clone => max,
+            };
+            for slot in &targets {
+                if kind != QuickCraftKind::Clone && remaining <= 0 {
+                    break;
+                }
+                let existing = self.get_window_slot(*slot);
+                let count = existing.item_count.max(0);
+                let added = match kind {
+                    QuickCraftKind::Clone => max - count,
+                    _ => per_slot.min(remaining).min(max - count),
+                };
+                if added > 0 {
+                    self.set_window_slot(*slot, carried.with_count(count + added));
+                    changed.push(*slot);
+                    if kind != QuickCraftKind::Clone {
+                        remaining -= added;
+                    }
+                }
+            }
+            self.carried = carried.with_count(remaining);
+        }
+        packets.push(self.click(
+            OUTSIDE_SLOT,
+            offset + 2,
+            ClickContainerMode::QuickCraft,
+            &changed,
+        ));
+        packets
+    }
+
+    // closes the open container (or the player inventory) and returns the packet to send
+    pub fn close(&mut self) -> ServerboundPlayPacket {
+        let window_id = self.get_window().window_id;
+        self.open = None;
+        ServerboundPlayPacket::CloseContainer {
+            window_id: window_id.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_any_player_slot(slot: i16, item: types::Slot) -> ClientboundPlayPacket {
+        ClientboundPlayPacket::SetContainerSlot {
+            window_id: ANY_PLAYER_SLOT_WINDOW_ID.into(),
+            state_id: 0.into(),
+            slot: slot.into(),
+            slot_data: item,
+        }
+    }
+
+    #[test]
+    fn any_player_slot_updates_use_inventory_slots() {
+        let mut inventory = Inventory::new();
+        // item ids: 1 stone, 2 helmet, 3 boots, 4 shield
+        assert!(inventory.apply_packet(&set_any_player_slot(2, types::Slot::new(1, 32))));
+        assert!(inventory.apply_packet(&set_any_player_slot(39, types::Slot::new(2, 1))));
+        assert!(inventory.apply_packet(&set_any_player_slot(36, types::Slot::new(3, 1))));
+        assert!(inventory.apply_packet(&set_any_player_slot(40, types::Slot::new(4, 1))));
+
+        let player = inventory.get_player_inventory();
+        let item_id = |slot: usize| player.get_slot(slot).map(|item| item.item_id);
+        assert_eq!(item_id(HOTBAR_START + 2), Some(1));
+        assert_eq!(item_id(ARMOR_START), Some(2));
+        assert_eq!(item_id(ARMOR_START + 3), Some(3));
+        assert_eq!(item_id(OFFHAND_SLOT), Some(4));
+        assert!(player.get_slot(2).is_some_and(|slot| slot.is_empty()));
+
+        assert!(inventory.select_slot(2).is_some());
+        assert!(inventory.select_slot(HOTBAR_SIZE).is_none());
+        assert_eq!(inventory.get_held_item().item_count, 32);
+        assert_eq!(inventory.get_offhand_item().item_id, 4);
+    }
+}
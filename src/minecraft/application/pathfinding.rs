@@ -0,0 +1,1019 @@
+// A* search for walkable routes over the client world cache, and a navigator that
+// steers the movement simulator along the found path
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::types;
+
+use super::Location;
+use super::physics::{
+    self, Aabb, MovementInput, PLAYER_HEIGHT, PLAYER_WIDTH, PlayerPhysics, STEP_HEIGHT,
+};
+use super::world::World;
+
+pub const DEFAULT_MAX_FALL_HEIGHT: i32 = 3;
+pub const DEFAULT_MAX_NODES: usize = 10000;
+
+const JUMP_HEIGHT: f64 = 1.25; // how high the player reaches when jumping
+
+const WALK_COST: f64 = 1.0;
+const JUMP_COST: f64 = 2.0;
+const FALL_COST: f64 = 1.0; // for each block fallen
+const CLIMB_COST: f64 = 1.5;
+const SWIM_COST: f64 = 2.5;
+
+// how close the player has to get to the center of a waypoint
+const WAYPOINT_RADIUS: f64 = 0.5;
+const GOAL_RADIUS: f64 = 0.3;
+// the path is planned again when the player stops getting closer to the next waypoint
+const STUCK_TICKS: u32 = 40;
+const MAX_REPLANS: u32 = 10; // in a row without reaching a waypoint
+
+const HORIZONTAL_DIRECTIONS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl BlockPos {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    // the block the feet of the player are in
+    pub fn from_location(location: &Location) -> Self {
+        Self::new(
+            location.x.floor() as i32,
+            location.y.floor() as i32,
+            location.z.floor() as i32,
+        )
+    }
+
+    pub fn offset(&self, dx: i32, dy: i32, dz: i32) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.z + dz)
+    }
+
+    pub fn distance(&self, other: &BlockPos) -> f64 {
+        let dx = (self.x - other.x) as f64;
+        let dy = (self.y - other.y) as f64;
+        let dz = (self.z - other.z) as f64;
+        (dx * dx + dy * dy + dz * dz).sqrt()
+    }
+}
+
+impl From<&types::Position> for BlockPos {
+    fn from(position: &types::Position) -> Self {
+        Self::new(position.get_x(), position.get_y() as i32, position.get_z())
+    }
+}
+
+// how the player gets to a waypoint from the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    Walk,
+    Jump,
+    Fall,
+    Climb,
+    Swim,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Waypoint {
+    position: BlockPos,
+    kind: MoveKind,
+}
+
+impl Waypoint {
+    pub fn get_position(&self) -> &BlockPos {
+        &self.position
+    }
+
+    pub fn get_kind(&self) -> MoveKind {
+        self.kind
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Path {
+    start: BlockPos,
+    goal: BlockPos,
+    waypoints: Vec<Waypoint>, // the start is not included
+    is_partial: bool,         // ends at the closest reachable position to the goal
+    visited_nodes: usize,
+}
+
+impl Path {
+    pub fn get_start(&self) -> &BlockPos {
+        &self.start
+    }
+
+    pub fn get_goal(&self) -> &BlockPos {
+        &self.goal
+    }
+
+    pub fn get_waypoints(&self) -> &Vec<Waypoint> {
+        &self.waypoints
+    }
+
+    pub fn get_end(&self) -> &BlockPos {
+        self.waypoints
+            .last()
+            .map_or(&self.start, |waypoint| &waypoint.position)
+    }
+
+    pub fn is_partial(&self) -> bool {
+        self.is_partial
+    }
+
+    pub fn get_visited_nodes(&self) -> usize {
+        self.visited_nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.waypoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+
+    // whether a change of the block may make the path unwalkable, checks the blocks around
+    // every step from one waypoint to the next
+    pub fn is_affected_by(&self, position: &BlockPos) -> bool {
+        let mut previous = self.start;
+        for waypoint in &self.waypoints {
+            let current = waypoint.position;
+            if position.x >= previous.x.min(current.x) - 1
+                && position.x <= previous.x.max(current.x) + 1
+                && position.y >= previous.y.min(current.y) - 1
+                && position.y <= previous.y.max(current.y) + 2
+                && position.z >= previous.z.min(current.z) - 1
+                && position.z <= previous.z.max(current.z) + 1
+            {
+                return true;
+            }
+            previous = current;
+        }
+        false
+    }
+}
+
+// what the player can do with its feet in a block
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Stand(f64), // supported, with the height the feet are at
+    Air,        // the player fits but falls
+    Water,
+    Climb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathSettings {
+    pub max_fall_height: i32, // not counting falls into water
+    pub max_nodes: usize,     // positions searched before giving up with a partial path
+    pub allow_water: bool,
+    pub allow_climbing: bool,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            max_fall_height: DEFAULT_MAX_FALL_HEIGHT,
+            max_nodes: DEFAULT_MAX_NODES,
+            allow_water: true,
+            allow_climbing: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    cost: f64,
+    parent: Option<BlockPos>,
+    kind: MoveKind,
+}
+
+// an entry of the open set, the lowest estimated cost comes first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OpenNode {
+    estimate: f64,
+    cost: f64,
+    position: BlockPos,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| self.cost.total_cmp(&other.cost))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Pathfinder {
+    settings: PathSettings,
+}
+
+impl Pathfinder {
+    pub fn new(settings: PathSettings) -> Self {
+        Self { settings }
+    }
+
+    pub fn get_settings(&self) -> &PathSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: PathSettings) {
+        self.settings = settings;
+    }
+
+    // the box of the player with its feet at the given height in the middle of the block
+    fn get_player_box(position: &BlockPos, feet_y: f64) -> Aabb {
+        Aabb::from_feet(
+            position.x as f64 + 0.5,
+            feet_y,
+            position.z as f64 + 0.5,
+            PLAYER_WIDTH,
+            PLAYER_HEIGHT,
+        )
+    }
+
+    // whether the player fits with its feet at the given height in the middle of the block
+    fn fits(&self, world: &World, position: &BlockPos, feet_y: f64) -> bool {
+        physics::get_collision_boxes(world, &Self::get_player_box(position, feet_y)).is_empty()
+    }
+
+    // like `fits`, the climbable blocks the player holds on to do not get in the way
+    fn fits_climbing(&self, world: &World, position: &BlockPos, feet_y: f64) -> bool {
+        let aabb = Self::get_player_box(position, feet_y);
+        physics::get_collision_boxes_where(world, &aabb, |state| !physics::is_climbable(state))
+            .is_empty()
+    }
+
+    // the highest top of the collision boxes in a part of the block, (min x, min z, max x, max z)
+    // from 0 to 1, the bottom of the block if it is empty there
+    fn get_top(&self, world: &World, position: &BlockPos, part: (f64, f64, f64, f64)) -> f64 {
+        let (min_x, min_z, max_x, max_z) = part;
+        let (x, y, z) = (position.x as f64, position.y as f64, position.z as f64);
+        // slightly smaller, the neighbors of the block are not included
+        let area = Aabb::new(
+            x + min_x + 0.01,
+            y + 0.01,
+            z + min_z + 0.01,
+            x + max_x - 0.01,
+            y + 0.99,
+            z + max_z - 0.01,
+        );
+        physics::get_collision_boxes(world, &area)
+            .iter()
+            .map(|aabb| aabb.max_y)
+            .fold(y, f64::max)
+    }
+
+    // whether the blocked block in front can be walked up without jumping, like stairs:
+    // the half the player enters first and then the other half rise by a step at most
+    fn is_staircase(
+        &self,
+        world: &World,
+        position: &BlockPos,
+        next: &BlockPos,
+        feet_y: f64,
+    ) -> bool {
+        let (near, far) = match (next.x - position.x, next.z - position.z) {
+            (1, _) => ((0.0, 0.0, 0.5, 1.0), (0.5, 0.0, 1.0, 1.0)),
+            (-1, _) => ((0.5, 0.0, 1.0, 1.0), (0.0, 0.0, 0.5, 1.0)),
+            (_, 1) => ((0.0, 0.0, 1.0, 0.5), (0.0, 0.5, 1.0, 1.0)),
+            _ => ((0.0, 0.5, 1.0, 1.0), (0.0, 0.0, 1.0, 0.5)),
+        };
+        let near_top = self.get_top(world, next, near);
+        let far_top = self.get_top(world, next, far);
+        near_top - feet_y <= STEP_HEIGHT && far_top - near_top <= STEP_HEIGHT
+    }
+
+    // the top of the blocks under the feet, from the block below up to half of the block
+    fn get_floor(&self, world: &World, position: &BlockPos) -> Option<f64> {
+        let half_width = PLAYER_WIDTH / 2.0;
+        let (x, y, z) = (
+            position.x as f64 + 0.5,
+            position.y as f64,
+            position.z as f64 + 0.5,
+        );
+        let area = Aabb::new(
+            x - half_width,
+            y - 0.01,
+            z - half_width,
+            x + half_width,
+            y + 0.51,
+            z + half_width,
+        );
+        physics::get_collision_boxes(world, &area)
+            .iter()
+            .map(|aabb| aabb.max_y)
+            .filter(|top| *top >= y && *top <= y + 0.5)
+            .max_by(|a, b| a.total_cmp(b))
+    }
+
+    fn get_cell(&self, world: &World, position: &BlockPos) -> Option<Cell> {
+        let state = world.get_block_at(position.x, position.y, position.z)?;
+        if physics::is_water(state) {
+            return match self.settings.allow_water && self.fits(world, position, position.y as f64)
+            {
+                true => Some(Cell::Water),
+                false => None,
+            };
+        }
+        if physics::is_climbable(state) {
+            return match self.settings.allow_climbing
+                && self.fits_climbing(world, position, position.y as f64)
+            {
+                true => Some(Cell::Climb),
+                false => None,
+            };
+        }
+        match self.get_floor(world, position) {
+            Some(floor) if self.fits(world, position, floor) => Some(Cell::Stand(floor)),
+            Some(_) => None,
+            None if self.fits(world, position, position.y as f64) => Some(Cell::Air),
+            None => None,
+        }
+    }
+
+    // the height the feet are at in the cell, used to check steps and jumps
+    fn get_feet_y(position: &BlockPos, cell: Cell) -> f64 {
+        match cell {
+            Cell::Stand(floor) => floor,
+            _ => position.y as f64,
+        }
+    }
+
+    // where the player lands when falling down from the position
+    fn find_landing(&self, world: &World, position: &BlockPos) -> Option<(BlockPos, Cell)> {
+        let min_y = world.get_min_y();
+        let mut below = position.offset(0, -1, 0);
+        while below.y >= min_y {
+            let height = position.y - below.y;
+            match self.get_cell(world, &below)? {
+                Cell::Air => {}
+                Cell::Water => return Some((below, Cell::Water)),
+                cell if height <= self.settings.max_fall_height => return Some((below, cell)),
+                _ => return None,
+            }
+            below = below.offset(0, -1, 0);
+        }
+        None
+    }
+
+    fn get_neighbors(
+        &self,
+        world: &World,
+        position: &BlockPos,
+        cell: Cell,
+    ) -> Vec<(BlockPos, MoveKind, f64)> {
+        let mut neighbors = Vec::new();
+        if cell == Cell::Air {
+            // only falling is possible
+            if let Some((landing, _)) = self.find_landing(world, position) {
+                let height = (position.y - landing.y) as f64;
+                neighbors.push((landing, MoveKind::Fall, FALL_COST * height));
+            }
+            return neighbors;
+        }
+
+        let feet_y = Self::get_feet_y(position, cell);
+        for (dx, dz) in HORIZONTAL_DIRECTIONS {
+            let next = position.offset(dx, 0, dz);
+            let is_diagonal = dx != 0 && dz != 0;
+            let distance = if is_diagonal {
+                std::f64::consts::SQRT_2
+            } else {
+                1.0
+            };
+            // diagonal moves can not cut corners
+            if is_diagonal
+                && (self.get_cell(world, &position.offset(dx, 0, 0)).is_none()
+                    || self.get_cell(world, &position.offset(0, 0, dz)).is_none())
+            {
+                continue;
+            }
+            match self.get_cell(world, &next) {
+                Some(Cell::Stand(floor)) if floor - feet_y <= STEP_HEIGHT => {
+                    neighbors.push((next, MoveKind::Walk, WALK_COST * distance));
+                }
+                Some(Cell::Stand(_)) => {}
+                Some(Cell::Climb) => neighbors.push((next, MoveKind::Walk, WALK_COST * distance)),
+                Some(Cell::Water) => neighbors.push((next, MoveKind::Swim, SWIM_COST * distance)),
+                Some(Cell::Air) => {
+                    if let Some((landing, _)) = self.find_landing(world, &next) {
+                        let height = (next.y - landing.y) as f64;
+                        neighbors.push((
+                            landing,
+                            MoveKind::Fall,
+                            WALK_COST * distance + FALL_COST * height,
+                        ));
+                    }
+                }
+                None if !is_diagonal => {
+                    // walk up stairs or jump onto the block in front, if there is room above
+                    // the player
+                    let above = next.offset(0, 1, 0);
+                    if let Some(Cell::Stand(floor)) = self.get_cell(world, &above)
+                        && self.fits(world, position, floor)
+                    {
+                        if self.is_staircase(world, position, &next, feet_y) {
+                            neighbors.push((above, MoveKind::Walk, WALK_COST));
+                        } else if floor - feet_y <= JUMP_HEIGHT {
+                            neighbors.push((above, MoveKind::Jump, JUMP_COST));
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+
+        // moving up and down ladders and water
+        let up = position.offset(0, 1, 0);
+        let down = position.offset(0, -1, 0);
+        match cell {
+            Cell::Climb => {
+                if let Some(Cell::Climb) = self.get_cell(world, &up) {
+                    neighbors.push((up, MoveKind::Climb, CLIMB_COST));
+                }
+                if let Some(Cell::Climb | Cell::Stand(_)) = self.get_cell(world, &down) {
+                    neighbors.push((down, MoveKind::Climb, CLIMB_COST));
+                }
+            }
+            Cell::Water => {
+                if let Some(Cell::Water) = self.get_cell(world, &up) {
+                    neighbors.push((up, MoveKind::Swim, SWIM_COST));
+                }
+                if let Some(Cell::Water | Cell::Stand(_)) = self.get_cell(world, &down) {
+                    neighbors.push((down, MoveKind::Swim, SWIM_COST));
+                }
+            }
+            Cell::Stand(_) => {
+                if let Some(Cell::Climb) = self.get_cell(world, &down) {
+                    neighbors.push((down, MoveKind::Climb, CLIMB_COST));
+                }
+            }
+            Cell::Air => {}
+        }
+        neighbors
+    }
+
+    // plans a route from the start to the goal, both are the blocks the feet are in.
+    // when the goal can not be reached within the node budget the path ends at the closest
+    // position found, returns None if the player can not be at the start
+    pub fn find_path(&self, world: &World, start: BlockPos, goal: BlockPos) -> Option<Path> {
+        let start_cell = self.get_cell(world, &start)?;
+        let mut nodes: HashMap<BlockPos, Node> = HashMap::new();
+        let mut cells: HashMap<BlockPos, Cell> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        nodes.insert(
+            start,
+            Node {
+                cost: 0.0,
+                parent: None,
+                kind: MoveKind::Walk,
+            },
+        );
+        cells.insert(start, start_cell);
+        open.push(OpenNode {
+            estimate: start.distance(&goal),
+            cost: 0.0,
+            position: start,
+        });
+
+        let mut closest = start;
+        let mut visited = 0;
+        let mut reached = false;
+        while let Some(current) = open.pop() {
+            // skip entries that were replaced by a cheaper one
+            if current.cost > nodes[&current.position].cost {
+                continue;
+            }
+            if current.position == goal {
+                closest = goal;
+                reached = true;
+                break;
+            }
+            if current.position.distance(&goal) < closest.distance(&goal) {
+                closest = current.position;
+            }
+            visited += 1;
+            if visited > self.settings.max_nodes {
+                break;
+            }
+
+            let cell = cells[&current.position];
+            for (next, kind, cost) in self.get_neighbors(world, &current.position, cell) {
+                let cost = current.cost + cost;
+                if nodes.get(&next).is_some_and(|node| node.cost <= cost) {
+                    continue;
+                }
+                if let Some(cell) = self.get_cell(world, &next) {
+                    cells.insert(next, cell);
+                }
+                nodes.insert(
+                    next,
+                    Node {
+                        cost,
+                        parent: Some(current.position),
+                        kind,
+                    },
+                );
+                open.push(OpenNode {
+                    estimate: cost + next.distance(&goal),
+                    cost,
+                    position: next,
+                });
+            }
+        }
+
+        let mut waypoints = Vec::new();
+        let mut position = closest;
+        while let Some(node) = nodes.get(&position) {
+            let parent = match node.parent {
+                Some(parent) => parent,
+                None => break,
+            };
+            waypoints.push(Waypoint {
+                position,
+                kind: node.kind,
+            });
+            position = parent;
+        }
+        waypoints.reverse();
+        Some(Path {
+            start,
+            goal,
+            waypoints,
+            is_partial: !reached,
+            visited_nodes: visited,
+        })
+    }
+
+    // whether every waypoint of the path can still be used
+    pub fn is_walkable(&self, world: &World, waypoints: &[Waypoint]) -> bool {
+        // waypoints are never in the air, the block the player stands on may be gone
+        waypoints.iter().all(|waypoint| {
+            self.get_cell(world, &waypoint.position)
+                .is_some_and(|cell| cell != Cell::Air)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationState {
+    Idle,
+    Navigating,
+    Arrived,
+    Failed, // no route to the goal was found
+}
+
+// follows paths by setting the movement input of the player every tick
+#[derive(Debug, Clone)]
+pub struct Navigator {
+    pathfinder: Pathfinder,
+    state: NavigationState,
+    goal: Option<BlockPos>,
+    path: Option<Path>,
+    index: usize, // the next waypoint
+    needs_replan: bool,
+    needs_check: bool, // blocks changed, the path has to be checked
+    replans: u32,
+    best_distance: f64, // to the next waypoint, used to notice when the player is stuck
+    stuck_ticks: u32,
+    release_input: bool,
+}
+
+impl Navigator {
+    pub fn new(settings: PathSettings) -> Self {
+        Self {
+            pathfinder: Pathfinder::new(settings),
+            state: NavigationState::Idle,
+            goal: None,
+            path: None,
+            index: 0,
+            needs_replan: false,
+            needs_check: false,
+            replans: 0,
+            best_distance: f64::MAX,
+            stuck_ticks: 0,
+            release_input: false,
+        }
+    }
+
+    pub fn get_pathfinder(&self) -> &Pathfinder {
+        &self.pathfinder
+    }
+
+    pub fn get_pathfinder_mut(&mut self) -> &mut Pathfinder {
+        &mut self.pathfinder
+    }
+
+    pub fn get_state(&self) -> NavigationState {
+        self.state
+    }
+
+    pub fn get_goal(&self) -> Option<&BlockPos> {
+        self.goal.as_ref()
+    }
+
+    pub fn get_path(&self) -> Option<&Path> {
+        self.path.as_ref()
+    }
+
+    pub fn get_next_waypoint(&self) -> Option<&Waypoint> {
+        self.path.as_ref()?.waypoints.get(self.index)
+    }
+
+    pub fn is_navigating(&self) -> bool {
+        self.state == NavigationState::Navigating
+    }
+
+    // the path is planned on the next tick
+    pub fn walk_to(&mut self, goal: BlockPos) {
+        self.goal = Some(goal);
+        self.path = None;
+        self.state = NavigationState::Navigating;
+        self.needs_replan = true;
+        self.replans = 0;
+    }
+
+    pub fn stop(&mut self) {
+        self.finish(NavigationState::Idle);
+    }
+
+    fn finish(&mut self, state: NavigationState) {
+        if self.state == NavigationState::Navigating {
+            self.release_input = true;
+        }
+        self.state = state;
+        self.path = None;
+        self.index = 0;
+    }
+
+    fn replan(&mut self, world: &World, physics: &PlayerPhysics) {
+        self.needs_replan = false;
+        self.replans += 1;
+        let goal = match self.goal {
+            Some(goal) => goal,
+            None => return self.finish(NavigationState::Failed),
+        };
+        let start = BlockPos::from_location(physics.get_location());
+        match self.pathfinder.find_path(world, start, goal) {
+            // a partial path that goes nowhere means the goal is unreachable
+            Some(path)
+                if !(path.is_partial() && path.is_empty()) && self.replans <= MAX_REPLANS =>
+            {
+                self.path = Some(path);
+                self.index = 0;
+                self.best_distance = f64::MAX;
+                self.stuck_ticks = 0;
+            }
+            _ => self.finish(NavigationState::Failed),
+        }
+    }
+
+    pub fn tick(&mut self, world: &World, physics: &mut PlayerPhysics) {
+        if self.state != NavigationState::Navigating {
+            if self.release_input {
+                self.release_input = false;
+                physics.set_input(MovementInput::default());
+            }
+            return;
+        }
+        if !physics.is_spawned() {
+            return;
+        }
+        if self.needs_check {
+            self.needs_check = false;
+            if let Some(path) = &self.path
+                && !self
+                    .pathfinder
+                    .is_walkable(world, &path.waypoints[self.index..])
+            {
+                self.needs_replan = true;
+            }
+        }
+        if self.needs_replan || self.path.is_none() {
+            self.replan(world, physics);
+            if self.state != NavigationState::Navigating {
+                return self.tick(world, physics);
+            }
+        }
+
+        let location = *physics.get_location();
+        let current = BlockPos::from_location(&location);
+        let path = self.path.as_ref().expect("planned above");
+
+        // skip the waypoints that were reached
+        let is_settled =
+            physics.is_on_ground() || physics.is_in_water() || physics.is_on_climbable();
+        while let Some(waypoint) = path.waypoints.get(self.index) {
+            let radius = if self.index + 1 == path.len() {
+                GOAL_RADIUS
+            } else {
+                WAYPOINT_RADIUS
+            };
+            let distance = Self::get_horizontal_distance(&location, &waypoint.position);
+            if distance < radius && current.y == waypoint.position.y && is_settled {
+                self.index += 1;
+                self.replans = 0;
+                self.best_distance = f64::MAX;
+                self.stuck_ticks = 0;
+            } else {
+                break;
+            }
+        }
+        let waypoint = match path.waypoints.get(self.index) {
+            Some(waypoint) => *waypoint,
+            None if path.is_partial() => {
+                // get closer to the goal from the end of the partial path
+                self.needs_replan = true;
+                return;
+            }
+            None => {
+                physics.set_input(MovementInput::default());
+                return self.finish(NavigationState::Arrived);
+            }
+        };
+
+        let distance = Self::get_horizontal_distance(&location, &waypoint.position);
+        let target = waypoint.position;
+        let vertical = (target.y - current.y) as f64;
+        let total = (distance * distance + vertical * vertical).sqrt();
+        if total < self.best_distance - 0.01 {
+            self.best_distance = total;
+            self.stuck_ticks = 0;
+        } else {
+            self.stuck_ticks += 1;
+            if self.stuck_ticks > STUCK_TICKS {
+                self.needs_replan = true;
+            }
+        }
+
+        // face the center of the waypoint, yaw 0 looks towards +z
+        let dx = target.x as f64 + 0.5 - location.x;
+        let dz = target.z as f64 + 0.5 - location.z;
+        if distance > 0.05 {
+            let yaw = (-dx).atan2(dz).to_degrees() as f32;
+            physics.set_rotation(yaw, location.pitch);
+        }
+        let is_last = self.index + 1 == path.len();
+        let forward = if distance < 0.1 {
+            0.0
+        } else if is_last && distance < 0.6 {
+            0.3 // slow down to stop on the goal
+        } else {
+            1.0
+        };
+        let going_up = target.y > current.y;
+        let jump = match waypoint.kind {
+            MoveKind::Jump => distance < 1.5,
+            MoveKind::Climb | MoveKind::Swim => going_up,
+            MoveKind::Walk | MoveKind::Fall => false,
+        } || (physics.is_in_water() && target.y >= current.y)
+            || (physics.is_on_climbable() && going_up);
+        physics.set_input(MovementInput {
+            forward,
+            strafe: 0.0,
+            jump,
+            sprint: false,
+            sneak: false,
+        });
+    }
+
+    fn get_horizontal_distance(location: &Location, position: &BlockPos) -> f64 {
+        let dx = position.x as f64 + 0.5 - location.x;
+        let dz = position.z as f64 + 0.5 - location.z;
+        (dx * dx + dz * dz).sqrt()
+    }
+
+    // block changes make the navigator check its path on the next tick
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::BlockUpdate { location, .. } => {
+                if let Some(path) = &self.path
+                    && path.is_affected_by(&BlockPos::from(location))
+                {
+                    self.needs_check = true;
+                }
+            }
+            ClientboundPlayPacket::UpdateSectionBlocks { .. }
+            | ClientboundPlayPacket::ChunkDataAndUpdateLight { .. }
+            | ClientboundPlayPacket::UnloadChunk { .. }
+            | ClientboundPlayPacket::Explosion { .. } => {
+                if self.path.is_some() {
+                    self.needs_check = true;
+                }
+            }
+            ClientboundPlayPacket::Respawn { .. } => {
+                if self.state == NavigationState::Navigating {
+                    self.finish(NavigationState::Failed);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Default for Navigator {
+    fn default() -> Self {
+        Self::new(PathSettings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::chunk::Chunk;
+    use crate::minecraft::registry::blocks::{
+        BlockRegistry, get_block_registry, set_test_block_registry,
+    };
+
+    // full blocks, a ladder against the east side of its block, bottom stairs facing east and
+    // blocks without a collision box
+    fn test_blocks() -> BlockRegistry {
+        let mut registry = BlockRegistry::from_json(
+            r#"{
+                "minecraft:air": {"states": [{"id": 0, "default": true}]},
+                "minecraft:stone": {"states": [{"id": 1, "default": true}]},
+                "minecraft:ladder": {"states": [
+                    {"id": 2, "default": true, "properties": {"facing": "west"}}
+                ]},
+                "minecraft:water": {"states": [{"id": 3, "default": true}]},
+                "minecraft:oak_stairs": {"states": [
+                    {"id": 4, "default": true, "properties": {"facing": "east", "half": "bottom"}}
+                ]},
+                "minecraft:vine": {"states": [{"id": 5, "default": true}]}
+            }"#,
+        )
+        .unwrap();
+        registry
+            .load_collision_shapes(
+                r#"{
+                    "blocks": {"air": 0, "stone": 1, "ladder": 2, "water": 0, "oak_stairs": 3, "vine": 0},
+                    "shapes": {
+                        "0": [],
+                        "1": [[0, 0, 0, 1, 1, 1]],
+                        "2": [[0.8125, 0, 0, 1, 1, 1]],
+                        "3": [[0, 0, 0, 1, 0.5, 1], [0.5, 0.5, 0, 1, 1, 1]]
+                    }
+                }"#,
+            )
+            .unwrap();
+        registry
+    }
+
+    fn state(name: &str) -> i32 {
+        get_block_registry()
+            .and_then(|registry| registry.get_default_state_id(name))
+            .unwrap()
+    }
+
+    // one chunk with a stone floor at y 0, the player walks at y 1
+    fn flat_world() -> World {
+        set_test_block_registry(test_blocks());
+        let mut world = World::new("minecraft:overworld", 0, 16);
+        world.insert_chunk(0, 0, Chunk::new_empty(0, 0, 0, 16, 0));
+        for x in 0..16 {
+            for z in 0..16 {
+                world.set_block_at(x, 0, z, state("minecraft:stone"));
+            }
+        }
+        world
+    }
+
+    // a wall across the chunk at x 4, from y 1 up to the height
+    fn add_wall(world: &mut World, height: i32, block: &str) {
+        for z in 0..16 {
+            for y in 1..=height {
+                world.set_block_at(4, y, z, state(block));
+            }
+        }
+    }
+
+    fn kinds(path: &Path) -> Vec<MoveKind> {
+        path.get_waypoints()
+            .iter()
+            .map(|waypoint| waypoint.get_kind())
+            .collect()
+    }
+
+    #[test]
+    fn walks_on_flat_ground() {
+        let world = flat_world();
+        let path = Pathfinder::default()
+            .find_path(&world, BlockPos::new(1, 1, 8), BlockPos::new(6, 1, 8))
+            .unwrap();
+        assert!(!path.is_partial());
+        assert_eq!(kinds(&path), vec![MoveKind::Walk; 5]);
+        assert_eq!(path.get_end(), &BlockPos::new(6, 1, 8));
+    }
+
+    #[test]
+    fn jumps_one_block_and_walks_up_stairs() {
+        let mut world = flat_world();
+        add_wall(&mut world, 1, "minecraft:stone");
+        let path = Pathfinder::default()
+            .find_path(&world, BlockPos::new(1, 1, 8), BlockPos::new(6, 1, 8))
+            .unwrap();
+        assert!(!path.is_partial());
+        assert!(kinds(&path).contains(&MoveKind::Jump));
+        assert!(path.get_waypoints().contains(&Waypoint {
+            position: BlockPos::new(4, 2, 8),
+            kind: MoveKind::Jump,
+        }));
+
+        add_wall(&mut world, 1, "minecraft:oak_stairs");
+        let path = Pathfinder::default()
+            .find_path(&world, BlockPos::new(1, 1, 8), BlockPos::new(6, 1, 8))
+            .unwrap();
+        assert!(!path.is_partial());
+        assert!(!kinds(&path).contains(&MoveKind::Jump));
+
+        // two blocks can not be jumped
+        add_wall(&mut world, 2, "minecraft:stone");
+        let path = Pathfinder::default()
+            .find_path(&world, BlockPos::new(1, 1, 8), BlockPos::new(6, 1, 8))
+            .unwrap();
+        assert!(path.is_partial());
+    }
+
+    #[test]
+    fn falls_down_to_the_max_fall_height() {
+        // a tower at x 1 and 2, the player stands on it at y 5
+        let mut world = flat_world();
+        for x in 1..=2 {
+            for z in 0..16 {
+                for y in 1..=4 {
+                    world.set_block_at(x, y, z, state("minecraft:stone"));
+                }
+            }
+        }
+        let (start, goal) = (BlockPos::new(1, 5, 8), BlockPos::new(6, 1, 8));
+        let path = Pathfinder::default()
+            .find_path(&world, start, goal)
+            .unwrap();
+        assert!(path.is_partial());
+
+        let settings = PathSettings {
+            max_fall_height: 4,
+            ..Default::default()
+        };
+        let path = Pathfinder::new(settings)
+            .find_path(&world, start, goal)
+            .unwrap();
+        assert!(!path.is_partial());
+        assert!(kinds(&path).contains(&MoveKind::Fall));
+    }
+
+    #[test]
+    fn climbs_ladders_and_vines() {
+        for climbable in ["minecraft:ladder", "minecraft:vine"] {
+            let mut world = flat_world();
+            add_wall(&mut world, 3, "minecraft:stone");
+            for y in 1..=3 {
+                world.set_block_at(3, y, 8, state(climbable));
+            }
+            let path = Pathfinder::default()
+                .find_path(&world, BlockPos::new(1, 1, 8), BlockPos::new(4, 4, 8))
+                .unwrap();
+            assert!(!path.is_partial(), "{}", climbable);
+            assert!(kinds(&path).contains(&MoveKind::Climb), "{}", climbable);
+
+            let settings = PathSettings {
+                allow_climbing: false,
+                ..Default::default()
+            };
+            let path = Pathfinder::new(settings)
+                .find_path(&world, BlockPos::new(1, 1, 8), BlockPos::new(4, 4, 8))
+                .unwrap();
+            assert!(path.is_partial(), "{}", climbable);
+        }
+    }
+}
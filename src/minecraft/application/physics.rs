@@ -0,0 +1,1310 @@
+// movement of the player, simulated every client tick the same way as the vanilla client
+use crate::minecraft::chunk::{AIR_BLOCK_STATE, SECTION_WIDTH};
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::TeleportFlags;
+use crate::minecraft::registry::blocks::{BlockState, get_block_registry};
+use crate::minecraft::serverbound::ServerboundPlayPacket;
+use crate::minecraft::serverbound::play::PlayerCommandAction;
+use crate::minecraft::types;
+
+use super::world::World;
+use super::{Location, Velocity};
+
+pub const PLAYER_WIDTH: f64 = 0.6;
+pub const PLAYER_HEIGHT: f64 = 1.8;
+pub const STEP_HEIGHT: f64 = 0.6;
+pub const EYE_HEIGHT: f64 = 1.62;
+pub const SNEAKING_EYE_HEIGHT: f64 = 1.27;
+
+const GRAVITY: f64 = 0.08;
+const VERTICAL_DRAG: f64 = 0.98;
+const HORIZONTAL_DRAG: f64 = 0.91;
+const DEFAULT_SLIPPERINESS: f64 = 0.6;
+const MOVEMENT_SPEED: f64 = 0.1; // base value of the movement speed attribute of players
+const SPRINT_SPEED_MODIFIER: f64 = 1.3;
+const SNEAK_SPEED_MODIFIER: f64 = 0.3; // base value of the sneaking speed attribute
+const INPUT_MODIFIER: f64 = 0.98;
+const AIR_ACCELERATION: f64 = 0.02;
+const SPRINT_AIR_ACCELERATION: f64 = 0.026;
+const JUMP_VELOCITY: f64 = 0.42;
+const SPRINT_JUMP_BOOST: f64 = 0.2;
+const JUMP_DELAY: u32 = 10; // ticks between two jumps while the jump key is held
+const MIN_VELOCITY: f64 = 0.003; // smaller velocities are set to zero
+const SPRINT_MIN_FORWARD: f64 = 0.8;
+const EDGE_STEP: f64 = 0.05; // how much the movement is reduced each try when sneaking near an edge
+const COLLISION_EPSILON: f64 = 1.0E-7;
+
+const WATER_DRAG: f64 = 0.8;
+const WATER_ACCELERATION: f64 = 0.02;
+const WATER_GRAVITY: f64 = GRAVITY / 16.0;
+const WATER_SWIM_UP: f64 = 0.04;
+const WATER_JUMP_OUT: f64 = 0.3; // pushed onto the edge when swimming against it
+const CLIMB_SPEED: f64 = 0.15; // the largest speed along and down climbable blocks
+const CLIMB_UP_VELOCITY: f64 = 0.2;
+
+const CLIMBABLE_BLOCKS: [&str; 9] = [
+    "minecraft:ladder",
+    "minecraft:vine",
+    "minecraft:scaffolding",
+    "minecraft:weeping_vines",
+    "minecraft:weeping_vines_plant",
+    "minecraft:twisting_vines",
+    "minecraft:twisting_vines_plant",
+    "minecraft:cave_vines",
+    "minecraft:cave_vines_plant",
+];
+
+// the position is sent when it moved further than this or every 20 ticks
+const POSITION_THRESHOLD: f64 = 2.0E-4;
+const POSITION_REMINDER_TICKS: u32 = 20;
+
+// velocities of the set entity velocity packet are in 1/8000 of a block per tick
+const VELOCITY_UNIT: f64 = 8000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub min_z: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub max_z: f64,
+}
+
+impl Aabb {
+    pub fn new(min_x: f64, min_y: f64, min_z: f64, max_x: f64, max_y: f64, max_z: f64) -> Self {
+        Self {
+            min_x: min_x.min(max_x),
+            min_y: min_y.min(max_y),
+            min_z: min_z.min(max_z),
+            max_x: min_x.max(max_x),
+            max_y: min_y.max(max_y),
+            max_z: min_z.max(max_z),
+        }
+    }
+
+    // the box of an entity standing at the given feet position
+    pub fn from_feet(x: f64, y: f64, z: f64, width: f64, height: f64) -> Self {
+        let half_width = width / 2.0;
+        Self::new(
+            x - half_width,
+            y,
+            z - half_width,
+            x + half_width,
+            y + height,
+            z + half_width,
+        )
+    }
+
+    pub fn offset(&self, dx: f64, dy: f64, dz: f64) -> Self {
+        Self {
+            min_x: self.min_x + dx,
+            min_y: self.min_y + dy,
+            min_z: self.min_z + dz,
+            max_x: self.max_x + dx,
+            max_y: self.max_y + dy,
+            max_z: self.max_z + dz,
+        }
+    }
+
+    // stretches the box in the direction of the movement
+    pub fn expand_towards(&self, dx: f64, dy: f64, dz: f64) -> Self {
+        Self {
+            min_x: self.min_x + dx.min(0.0),
+            min_y: self.min_y + dy.min(0.0),
+            min_z: self.min_z + dz.min(0.0),
+            max_x: self.max_x + dx.max(0.0),
+            max_y: self.max_y + dy.max(0.0),
+            max_z: self.max_z + dz.max(0.0),
+        }
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min_x < other.max_x
+            && self.max_x > other.min_x
+            && self.min_y < other.max_y
+            && self.max_y > other.min_y
+            && self.min_z < other.max_z
+            && self.max_z > other.min_z
+    }
+
+    fn overlaps_x(&self, other: &Aabb) -> bool {
+        self.min_x < other.max_x - COLLISION_EPSILON && self.max_x > other.min_x + COLLISION_EPSILON
+    }
+
+    fn overlaps_y(&self, other: &Aabb) -> bool {
+        self.min_y < other.max_y - COLLISION_EPSILON && self.max_y > other.min_y + COLLISION_EPSILON
+    }
+
+    fn overlaps_z(&self, other: &Aabb) -> bool {
+        self.min_z < other.max_z - COLLISION_EPSILON && self.max_z > other.min_z + COLLISION_EPSILON
+    }
+
+    // how far this box can move along an axis before touching the other box
+    fn clip_x(&self, other: &Aabb, dx: f64) -> f64 {
+        if !self.overlaps_y(other) || !self.overlaps_z(other) {
+            return dx;
+        }
+        if dx > 0.0 && self.max_x <= other.min_x + COLLISION_EPSILON {
+            dx.min(other.min_x - self.max_x)
+        } else if dx < 0.0 && self.min_x >= other.max_x - COLLISION_EPSILON {
+            dx.max(other.max_x - self.min_x)
+        } else {
+            dx
+        }
+    }
+
+    fn clip_y(&self, other: &Aabb, dy: f64) -> f64 {
+        if !self.overlaps_x(other) || !self.overlaps_z(other) {
+            return dy;
+        }
+        if dy > 0.0 && self.max_y <= other.min_y + COLLISION_EPSILON {
+            dy.min(other.min_y - self.max_y)
+        } else if dy < 0.0 && self.min_y >= other.max_y - COLLISION_EPSILON {
+            dy.max(other.max_y - self.min_y)
+        } else {
+            dy
+        }
+    }
+
+    fn clip_z(&self, other: &Aabb, dz: f64) -> f64 {
+        if !self.overlaps_x(other) || !self.overlaps_y(other) {
+            return dz;
+        }
+        if dz > 0.0 && self.max_z <= other.min_z + COLLISION_EPSILON {
+            dz.min(other.min_z - self.max_z)
+        } else if dz < 0.0 && self.min_z >= other.max_z - COLLISION_EPSILON {
+            dz.max(other.max_z - self.min_z)
+        } else {
+            dz
+        }
+    }
+}
+
+// collision boxes of a block state inside its block, from the collision shapes of the block
+// registry when they are loaded, approximated from the name of the block otherwise.
+// only air is empty when the block registry is not loaded
+pub fn get_block_shape(state: i32) -> Vec<Aabb> {
+    let full = vec![Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)];
+    let registry = match get_block_registry() {
+        Some(registry) => registry,
+        None if state == AIR_BLOCK_STATE => return Vec::new(),
+        None => return full,
+    };
+    if let Some(shape) = registry.get_collision_shape(state) {
+        return shape
+            .iter()
+            .map(|[min_x, min_y, min_z, max_x, max_y, max_z]| {
+                Aabb::new(*min_x, *min_y, *min_z, *max_x, *max_y, *max_z)
+            })
+            .collect();
+    }
+    if !registry.is_solid(state) {
+        return Vec::new();
+    }
+    match registry.get_state(state) {
+        Some(block) => approximate_block_shape(block),
+        None => full,
+    }
+}
+
+// a 3/16 thick panel against the side of the block opposite to the direction, the shape of
+// ladders, closed doors and open trapdoors
+fn get_panel_shape(direction: Option<&str>) -> Aabb {
+    let thickness = 0.1875;
+    match direction {
+        Some("north") => Aabb::new(0.0, 0.0, 1.0 - thickness, 1.0, 1.0, 1.0),
+        Some("south") => Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, thickness),
+        Some("west") => Aabb::new(1.0 - thickness, 0.0, 0.0, 1.0, 1.0, 1.0),
+        _ => Aabb::new(0.0, 0.0, 0.0, thickness, 1.0, 1.0),
+    }
+}
+
+// the shapes of common blocks, other blocks are full cubes
+fn approximate_block_shape(block: &BlockState) -> Vec<Aabb> {
+    let full = vec![Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)];
+    let height = |height: f64| vec![Aabb::new(0.0, 0.0, 0.0, 1.0, height, 1.0)];
+    let name = block.get_name();
+    let is_open = block.get_property("open") == Some("true");
+    let facing = block.get_property("facing");
+    if name.ends_with("_sign")
+        || name.ends_with("_banner")
+        || name.ends_with("_button")
+        || name.ends_with("_pressure_plate")
+    {
+        Vec::new()
+    } else if name == "minecraft:ladder" {
+        // against the block behind it
+        vec![get_panel_shape(facing)]
+    } else if name.ends_with("_slab") {
+        match block.get_property("type") {
+            Some("bottom") => height(0.5),
+            Some("top") => vec![Aabb::new(0.0, 0.5, 0.0, 1.0, 1.0, 1.0)],
+            _ => full,
+        }
+    } else if name.ends_with("_stairs") {
+        // a slab and a step on the half of the facing direction, corners are not approximated
+        let (slab, step) = match block.get_property("half") {
+            Some("top") => ((0.5, 1.0), (0.0, 0.5)),
+            _ => ((0.0, 0.5), (0.5, 1.0)),
+        };
+        let (min_x, min_z, max_x, max_z) = match facing {
+            Some("north") => (0.0, 0.0, 1.0, 0.5),
+            Some("south") => (0.0, 0.5, 1.0, 1.0),
+            Some("west") => (0.0, 0.0, 0.5, 1.0),
+            _ => (0.5, 0.0, 1.0, 1.0),
+        };
+        vec![
+            Aabb::new(0.0, slab.0, 0.0, 1.0, slab.1, 1.0),
+            Aabb::new(min_x, step.0, min_z, max_x, step.1, max_z),
+        ]
+    } else if name.ends_with("_trapdoor") {
+        match (is_open, block.get_property("half")) {
+            (true, _) => vec![get_panel_shape(facing)],
+            (false, Some("top")) => vec![Aabb::new(0.0, 0.8125, 0.0, 1.0, 1.0, 1.0)],
+            (false, _) => height(0.1875),
+        }
+    } else if name.ends_with("_door") {
+        // open doors turn around their hinge
+        let is_right_hinge = block.get_property("hinge") == Some("right");
+        let direction = match (is_open, is_right_hinge, facing) {
+            (false, _, facing) => facing,
+            (true, true, Some("south")) | (true, false, Some("north")) => Some("east"),
+            (true, false, Some("south")) | (true, true, Some("north")) => Some("west"),
+            (true, true, Some("west")) | (true, false, Some("east")) => Some("south"),
+            (true, _, _) => Some("north"),
+        };
+        vec![get_panel_shape(direction)]
+    } else if name.ends_with("_fence_gate") {
+        if is_open { Vec::new() } else { height(1.5) }
+    } else if name.ends_with("_fence") || name.ends_with("_wall") {
+        height(1.5)
+    } else if name.ends_with("_carpet") {
+        height(0.0625)
+    } else if name.ends_with("_bed") {
+        height(0.5625)
+    } else if name == "minecraft:snow" {
+        let layers: f64 = block
+            .get_property("layers")
+            .and_then(|layers| layers.parse().ok())
+            .unwrap_or(1.0);
+        if layers > 1.0 {
+            height((layers - 1.0) * 0.125)
+        } else {
+            Vec::new()
+        }
+    } else if name == "minecraft:farmland" || name == "minecraft:dirt_path" {
+        height(0.9375)
+    } else if name == "minecraft:soul_sand" {
+        height(0.875)
+    } else {
+        full
+    }
+}
+
+// collision boxes of every block touching the area, blocks of unloaded chunks are solid
+pub fn get_collision_boxes(world: &World, area: &Aabb) -> Vec<Aabb> {
+    get_collision_boxes_where(world, area, |_| true)
+}
+
+// like `get_collision_boxes`, only blocks whose state passes the filter are included
+pub fn get_collision_boxes_where(
+    world: &World,
+    area: &Aabb,
+    filter: impl Fn(i32) -> bool,
+) -> Vec<Aabb> {
+    let mut boxes = Vec::new();
+    // fences and walls reach into the block above them
+    let min_y = (area.min_y - 0.5).floor() as i32;
+    for x in area.min_x.floor() as i32..=area.max_x.floor() as i32 {
+        for z in area.min_z.floor() as i32..=area.max_z.floor() as i32 {
+            for y in min_y..=area.max_y.floor() as i32 {
+                let shape = match world.get_block_at(x, y, z) {
+                    Some(state) if !filter(state) => continue,
+                    Some(state) => get_block_shape(state),
+                    // blocks above and below the world have no collision
+                    None if y < world.get_min_y()
+                        || y >= world.get_min_y() + world.get_height() =>
+                    {
+                        continue;
+                    }
+                    None => vec![Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)],
+                };
+                for part in shape {
+                    let part = part.offset(x as f64, y as f64, z as f64);
+                    if part.intersects(area) {
+                        boxes.push(part);
+                    }
+                }
+            }
+        }
+    }
+    boxes
+}
+
+// moves the box along y first, then along the longer horizontal axis, like vanilla
+fn collide_with_boxes(aabb: &Aabb, movement: (f64, f64, f64), boxes: &[Aabb]) -> (f64, f64, f64) {
+    let (mut dx, mut dy, mut dz) = movement;
+    let mut aabb = *aabb;
+    if dy != 0.0 {
+        dy = boxes.iter().fold(dy, |dy, other| aabb.clip_y(other, dy));
+        aabb = aabb.offset(0.0, dy, 0.0);
+    }
+    let z_first = dx.abs() < dz.abs();
+    if z_first && dz != 0.0 {
+        dz = boxes.iter().fold(dz, |dz, other| aabb.clip_z(other, dz));
+        aabb = aabb.offset(0.0, 0.0, dz);
+    }
+    if dx != 0.0 {
+        dx = boxes.iter().fold(dx, |dx, other| aabb.clip_x(other, dx));
+        aabb = aabb.offset(dx, 0.0, 0.0);
+    }
+    if !z_first && dz != 0.0 {
+        dz = boxes.iter().fold(dz, |dz, other| aabb.clip_z(other, dz));
+    }
+    (dx, dy, dz)
+}
+
+fn horizontal_distance_squared(movement: (f64, f64, f64)) -> f64 {
+    movement.0 * movement.0 + movement.2 * movement.2
+}
+
+// water and waterlogged blocks, only known when the block registry is loaded
+pub fn is_water(state: i32) -> bool {
+    let block = match get_block_registry().and_then(|registry| registry.get_state(state)) {
+        Some(block) => block,
+        None => return false,
+    };
+    block.get_name() == "minecraft:water"
+        || block.get_name() == "minecraft:bubble_column"
+        || block.get_property("waterlogged") == Some("true")
+}
+
+// blocks the player can climb, like ladders and vines
+pub fn is_climbable(state: i32) -> bool {
+    get_block_registry()
+        .and_then(|registry| registry.get_block_name(state))
+        .is_some_and(|name| CLIMBABLE_BLOCKS.contains(&name))
+}
+
+// how slippery the block under the player is, higher is more slippery
+fn get_slipperiness(state: i32) -> f64 {
+    let name = get_block_registry().and_then(|registry| registry.get_block_name(state));
+    match name {
+        Some("minecraft:ice") | Some("minecraft:packed_ice") | Some("minecraft:frosted_ice") => {
+            0.98
+        }
+        Some("minecraft:blue_ice") => 0.989,
+        Some("minecraft:slime_block") => 0.8,
+        _ => DEFAULT_SLIPPERINESS,
+    }
+}
+
+fn get_speed_factor(state: i32) -> f64 {
+    let name = get_block_registry().and_then(|registry| registry.get_block_name(state));
+    match name {
+        Some("minecraft:soul_sand") | Some("minecraft:honey_block") => 0.4,
+        _ => 1.0,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MovementInput {
+    pub forward: f64, // -1.0 to 1.0, positive forward
+    pub strafe: f64,  // -1.0 to 1.0, positive to the left
+    pub jump: bool,
+    pub sprint: bool,
+    pub sneak: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerPhysics {
+    entity_id: i32,
+    location: Location,
+    velocity: Velocity,
+    input: MovementInput,
+    on_ground: bool,
+    horizontal_collision: bool,
+    vertical_collision: bool,
+    in_water: bool,
+    on_climbable: bool,
+    sprinting: bool,
+    sneaking: bool,
+    jump_delay: u32,
+    spawned: bool, // the server has sent the position of the player
+
+    // last state sent to the server
+    last_location: Location,
+    last_on_ground: bool,
+    last_sprinting: bool,
+    last_sneaking: bool,
+    position_reminder: u32,
+
+    packets: Vec<ServerboundPlayPacket>,
+}
+
+impl PlayerPhysics {
+    pub fn new(entity_id: i32) -> Self {
+        let location = Location {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            yaw: 180.0,
+            pitch: 0.0,
+        };
+        Self {
+            entity_id,
+            location,
+            velocity: Velocity {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            input: MovementInput::default(),
+            on_ground: false,
+            horizontal_collision: false,
+            vertical_collision: false,
+            in_water: false,
+            on_climbable: false,
+            sprinting: false,
+            sneaking: false,
+            jump_delay: 0,
+            spawned: false,
+            last_location: location,
+            last_on_ground: false,
+            last_sprinting: false,
+            last_sneaking: false,
+            position_reminder: 0,
+            packets: Vec::new(),
+        }
+    }
+
+    pub fn get_location(&self) -> &Location {
+        &self.location
+    }
+
+    pub fn get_velocity(&self) -> &Velocity {
+        &self.velocity
+    }
+
+    pub fn get_bounding_box(&self) -> Aabb {
+        Aabb::from_feet(
+            self.location.x,
+            self.location.y,
+            self.location.z,
+            PLAYER_WIDTH,
+            PLAYER_HEIGHT,
+        )
+    }
+
+    // the height of the eyes above the feet, lower while sneaking
+    pub fn get_eye_height(&self) -> f64 {
+        if self.sneaking {
+            SNEAKING_EYE_HEIGHT
+        } else {
+            EYE_HEIGHT
+        }
+    }
+
+    pub fn get_input(&self) -> &MovementInput {
+        &self.input
+    }
+
+    // the input is kept until it is changed, like a held key
+    pub fn set_input(&mut self, input: MovementInput) {
+        self.input = input;
+    }
+
+    pub fn set_rotation(&mut self, yaw: f32, pitch: f32) {
+        self.location.yaw = yaw;
+        self.location.pitch = pitch.clamp(-90.0, 90.0);
+    }
+
+    pub fn is_on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    pub fn has_horizontal_collision(&self) -> bool {
+        self.horizontal_collision
+    }
+
+    pub fn has_vertical_collision(&self) -> bool {
+        self.vertical_collision
+    }
+
+    pub fn is_in_water(&self) -> bool {
+        self.in_water
+    }
+
+    pub fn is_on_climbable(&self) -> bool {
+        self.on_climbable
+    }
+
+    pub fn is_sprinting(&self) -> bool {
+        self.sprinting
+    }
+
+    pub fn is_sneaking(&self) -> bool {
+        self.sneaking
+    }
+
+    // the player is not simulated before the server sends its position
+    pub fn is_spawned(&self) -> bool {
+        self.spawned
+    }
+
+    // packets to send to the server, produced by `tick` and `apply_packet`
+    pub fn take_packets(&mut self) -> Vec<ServerboundPlayPacket> {
+        std::mem::take(&mut self.packets)
+    }
+
+    pub fn tick(&mut self, world: &World) {
+        if !self.spawned {
+            return;
+        }
+        // the vanilla client does not move the player while its chunk is not loaded
+        let chunk_x = (self.location.x.floor() as i32).div_euclid(SECTION_WIDTH);
+        let chunk_z = (self.location.z.floor() as i32).div_euclid(SECTION_WIDTH);
+        if world.is_chunk_loaded(chunk_x, chunk_z) {
+            self.update_movement(world);
+        }
+        self.send_position();
+    }
+
+    fn update_movement(&mut self, world: &World) {
+        if self.jump_delay > 0 {
+            self.jump_delay -= 1;
+        }
+        if self.velocity.x.abs() < MIN_VELOCITY {
+            self.velocity.x = 0.0;
+        }
+        if self.velocity.y.abs() < MIN_VELOCITY {
+            self.velocity.y = 0.0;
+        }
+        if self.velocity.z.abs() < MIN_VELOCITY {
+            self.velocity.z = 0.0;
+        }
+
+        let feet = world
+            .get_block_at(
+                self.location.x.floor() as i32,
+                self.location.y.floor() as i32,
+                self.location.z.floor() as i32,
+            )
+            .unwrap_or(AIR_BLOCK_STATE);
+        self.in_water = is_water(feet);
+        self.on_climbable = is_climbable(feet);
+
+        self.sneaking = self.input.sneak;
+        self.sprinting = self.input.sprint
+            && !self.sneaking
+            && !self.in_water
+            && self.input.forward >= SPRINT_MIN_FORWARD
+            && !self.horizontal_collision;
+
+        if self.input.jump {
+            if self.in_water {
+                self.velocity.y += WATER_SWIM_UP;
+            } else if self.on_ground && self.jump_delay == 0 {
+                self.jump();
+                self.jump_delay = JUMP_DELAY;
+            }
+        } else {
+            self.jump_delay = 0;
+        }
+
+        let mut forward = self.input.forward.clamp(-1.0, 1.0);
+        let mut strafe = self.input.strafe.clamp(-1.0, 1.0);
+        if self.sneaking {
+            forward *= SNEAK_SPEED_MODIFIER;
+            strafe *= SNEAK_SPEED_MODIFIER;
+        }
+        forward *= INPUT_MODIFIER;
+        strafe *= INPUT_MODIFIER;
+
+        if self.in_water {
+            self.update_swimming(world, strafe, forward);
+            return;
+        }
+
+        // the block that decides the friction is half a block under the feet
+        let below = world
+            .get_block_at(
+                self.location.x.floor() as i32,
+                (self.location.y - 0.500001).floor() as i32,
+                self.location.z.floor() as i32,
+            )
+            .unwrap_or(AIR_BLOCK_STATE);
+        let slipperiness = if self.on_ground {
+            get_slipperiness(below)
+        } else {
+            1.0
+        };
+        let friction = slipperiness * HORIZONTAL_DRAG;
+        let acceleration = if self.on_ground {
+            let speed = if self.sprinting {
+                MOVEMENT_SPEED * SPRINT_SPEED_MODIFIER
+            } else {
+                MOVEMENT_SPEED
+            };
+            speed * (0.21600002 / (slipperiness * slipperiness * slipperiness))
+        } else if self.sprinting {
+            SPRINT_AIR_ACCELERATION
+        } else {
+            AIR_ACCELERATION
+        };
+        self.move_relative(acceleration, strafe, forward);
+        if self.on_climbable {
+            self.velocity.x = self.velocity.x.clamp(-CLIMB_SPEED, CLIMB_SPEED);
+            self.velocity.z = self.velocity.z.clamp(-CLIMB_SPEED, CLIMB_SPEED);
+            self.velocity.y = self.velocity.y.max(-CLIMB_SPEED);
+            // sneaking players hold on to the ladder
+            if self.sneaking && self.velocity.y < 0.0 {
+                self.velocity.y = 0.0;
+            }
+        }
+
+        let movement = (self.velocity.x, self.velocity.y, self.velocity.z);
+        self.move_with_collisions(world, movement, below);
+        if self.on_climbable && (self.horizontal_collision || self.input.jump) {
+            self.velocity.y = CLIMB_UP_VELOCITY;
+        }
+
+        self.velocity.y = (self.velocity.y - GRAVITY) * VERTICAL_DRAG;
+        self.velocity.x *= friction;
+        self.velocity.z *= friction;
+    }
+
+    fn update_swimming(&mut self, world: &World, strafe: f64, forward: f64) {
+        self.move_relative(WATER_ACCELERATION, strafe, forward);
+        let movement = (self.velocity.x, self.velocity.y, self.velocity.z);
+        self.move_with_collisions(world, movement, AIR_BLOCK_STATE);
+
+        self.velocity.x *= WATER_DRAG;
+        self.velocity.y = self.velocity.y * WATER_DRAG - WATER_GRAVITY;
+        self.velocity.z *= WATER_DRAG;
+
+        // climb out of the water onto the block in front
+        if self.horizontal_collision {
+            let area = self.get_bounding_box().offset(
+                self.velocity.x,
+                self.velocity.y + STEP_HEIGHT - movement.1,
+                self.velocity.z,
+            );
+            if get_collision_boxes(world, &area).is_empty() {
+                self.velocity.y = WATER_JUMP_OUT;
+            }
+        }
+    }
+
+    fn jump(&mut self) {
+        self.velocity.y = JUMP_VELOCITY;
+        if self.sprinting {
+            let yaw = (self.location.yaw as f64).to_radians();
+            self.velocity.x -= yaw.sin() * SPRINT_JUMP_BOOST;
+            self.velocity.z += yaw.cos() * SPRINT_JUMP_BOOST;
+        }
+    }
+
+    // accelerates in the direction the player is facing
+    fn move_relative(&mut self, speed: f64, strafe: f64, forward: f64) {
+        let length = strafe * strafe + forward * forward;
+        if length < 1.0E-7 {
+            return;
+        }
+        let scale = if length > 1.0 {
+            speed / length.sqrt()
+        } else {
+            speed
+        };
+        let (strafe, forward) = (strafe * scale, forward * scale);
+        let yaw = (self.location.yaw as f64).to_radians();
+        let (sin, cos) = (yaw.sin(), yaw.cos());
+        self.velocity.x += strafe * cos - forward * sin;
+        self.velocity.z += forward * cos + strafe * sin;
+    }
+
+    fn move_with_collisions(&mut self, world: &World, movement: (f64, f64, f64), below: i32) {
+        let movement = self.back_off_from_edge(world, movement);
+        let aabb = self.get_bounding_box();
+        let area = aabb
+            .expand_towards(movement.0, movement.1, movement.2)
+            .expand_towards(0.0, STEP_HEIGHT, 0.0);
+        let boxes = get_collision_boxes(world, &area);
+
+        let mut moved = collide_with_boxes(&aabb, movement, &boxes);
+        let collided_x = moved.0 != movement.0;
+        let collided_y = moved.1 != movement.1;
+        let collided_z = moved.2 != movement.2;
+
+        // walk up blocks lower than the step height, e.g. slabs
+        let was_on_ground = self.on_ground || (collided_y && movement.1 < 0.0);
+        if was_on_ground && (collided_x || collided_z) {
+            let mut stepped =
+                collide_with_boxes(&aabb, (movement.0, STEP_HEIGHT, movement.2), &boxes);
+            let up = collide_with_boxes(
+                &aabb.expand_towards(movement.0, 0.0, movement.2),
+                (0.0, STEP_HEIGHT, 0.0),
+                &boxes,
+            )
+            .1;
+            if up < STEP_HEIGHT {
+                let horizontal = collide_with_boxes(
+                    &aabb.offset(0.0, up, 0.0),
+                    (movement.0, 0.0, movement.2),
+                    &boxes,
+                );
+                if horizontal_distance_squared(horizontal) > horizontal_distance_squared(stepped) {
+                    stepped = (horizontal.0, up, horizontal.2);
+                }
+            }
+            // back down onto whatever was stepped on
+            let down = collide_with_boxes(
+                &aabb.offset(stepped.0, stepped.1, stepped.2),
+                (0.0, -stepped.1 + movement.1, 0.0),
+                &boxes,
+            )
+            .1;
+            stepped.1 += down;
+            if horizontal_distance_squared(stepped) > horizontal_distance_squared(moved) {
+                moved = stepped;
+            }
+        }
+
+        self.location.x += moved.0;
+        self.location.y += moved.1;
+        self.location.z += moved.2;
+
+        let collided_x = moved.0 != movement.0;
+        let collided_z = moved.2 != movement.2;
+        self.horizontal_collision = collided_x || collided_z;
+        self.vertical_collision = moved.1 != movement.1;
+        self.on_ground = self.vertical_collision && movement.1 < 0.0;
+
+        if collided_x {
+            self.velocity.x = 0.0;
+        }
+        if collided_z {
+            self.velocity.z = 0.0;
+        }
+        if self.vertical_collision {
+            self.velocity.y = 0.0;
+        }
+
+        // soul sand and honey slow down the player standing in or on them
+        let inside = world
+            .get_block_at(
+                self.location.x.floor() as i32,
+                self.location.y.floor() as i32,
+                self.location.z.floor() as i32,
+            )
+            .unwrap_or(AIR_BLOCK_STATE);
+        let speed_factor = match get_speed_factor(inside) {
+            factor if factor != 1.0 => factor,
+            _ => get_speed_factor(below),
+        };
+        self.velocity.x *= speed_factor;
+        self.velocity.z *= speed_factor;
+    }
+
+    // sneaking players do not walk off the edge of the block they stand on
+    fn back_off_from_edge(&self, world: &World, movement: (f64, f64, f64)) -> (f64, f64, f64) {
+        let (mut dx, dy, mut dz) = movement;
+        if !self.sneaking || !self.on_ground || dy > 0.0 {
+            return movement;
+        }
+        let aabb = self.get_bounding_box();
+        let has_ground = |dx: f64, dz: f64| {
+            let area = aabb.offset(dx, -STEP_HEIGHT, dz);
+            get_collision_boxes(world, &area)
+                .iter()
+                .any(|other| other.intersects(&area))
+        };
+        let approach = |value: f64| {
+            if value.abs() < EDGE_STEP {
+                0.0
+            } else {
+                value - EDGE_STEP * value.signum()
+            }
+        };
+        while dx != 0.0 && !has_ground(dx, 0.0) {
+            dx = approach(dx);
+        }
+        while dz != 0.0 && !has_ground(0.0, dz) {
+            dz = approach(dz);
+        }
+        while dx != 0.0 && dz != 0.0 && !has_ground(dx, dz) {
+            dx = approach(dx);
+            dz = approach(dz);
+        }
+        (dx, dy, dz)
+    }
+
+    fn send_position(&mut self) {
+        if self.sprinting != self.last_sprinting {
+            let action = if self.sprinting {
+                PlayerCommandAction::StartSprinting
+            } else {
+                PlayerCommandAction::StopSprinting
+            };
+            self.send_command(action);
+            self.last_sprinting = self.sprinting;
+        }
+        if self.sneaking != self.last_sneaking {
+            let action = if self.sneaking {
+                PlayerCommandAction::StartSneaking
+            } else {
+                PlayerCommandAction::StopSneaking
+            };
+            self.send_command(action);
+            self.last_sneaking = self.sneaking;
+        }
+
+        let dx = self.location.x - self.last_location.x;
+        let dy = self.location.y - self.last_location.y;
+        let dz = self.location.z - self.last_location.z;
+        self.position_reminder += 1;
+        let moved = dx * dx + dy * dy + dz * dz > POSITION_THRESHOLD * POSITION_THRESHOLD
+            || self.position_reminder >= POSITION_REMINDER_TICKS;
+        let rotated = self.location.yaw != self.last_location.yaw
+            || self.location.pitch != self.last_location.pitch;
+
+        let location = self.location;
+        let position = types::DoubleVec3 {
+            x: location.x.into(),
+            y: location.y.into(),
+            z: location.z.into(),
+        };
+        let on_ground = self.on_ground.into();
+        let packet = if moved && rotated {
+            Some(ServerboundPlayPacket::SetPlayerPositionAndRotation {
+                position,
+                yaw: location.yaw.into(),
+                ptch: location.pitch.into(),
+                on_ground,
+            })
+        } else if moved {
+            Some(ServerboundPlayPacket::SetPlayerPosition {
+                position,
+                on_ground,
+            })
+        } else if rotated {
+            Some(ServerboundPlayPacket::SetPlayerRotation {
+                yaw: location.yaw.into(),
+                ptch: location.pitch.into(),
+                on_ground,
+            })
+        } else if self.on_ground != self.last_on_ground {
+            Some(ServerboundPlayPacket::SetPlayerOnGround { on_ground })
+        } else {
+            None
+        };
+        if let Some(packet) = packet {
+            self.packets.push(packet);
+        }
+
+        if moved {
+            self.last_location.x = location.x;
+            self.last_location.y = location.y;
+            self.last_location.z = location.z;
+            self.position_reminder = 0;
+        }
+        if rotated {
+            self.last_location.yaw = location.yaw;
+            self.last_location.pitch = location.pitch;
+        }
+        self.last_on_ground = self.on_ground;
+    }
+
+    fn send_command(&mut self, action: PlayerCommandAction) {
+        self.packets.push(ServerboundPlayPacket::PlayerCommand {
+            entity_id: self.entity_id.into(),
+            action,
+            jump_boost: 0.into(),
+        });
+    }
+
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::SynchronizePlayerPosition {
+                location,
+                flags,
+                teleport_id,
+            } => {
+                // relative axes keep their velocity, absolute ones are reset
+                let relative = |flag: TeleportFlags, current: f64, value: f64| {
+                    if flags.contains(flag) {
+                        current + value
+                    } else {
+                        value
+                    }
+                };
+                self.location.x = relative(
+                    TeleportFlags::RELATIVE_X,
+                    self.location.x,
+                    location.x.get_value(),
+                );
+                self.location.y = relative(
+                    TeleportFlags::RELATIVE_Y,
+                    self.location.y,
+                    location.y.get_value(),
+                );
+                self.location.z = relative(
+                    TeleportFlags::RELATIVE_Z,
+                    self.location.z,
+                    location.z.get_value(),
+                );
+                if !flags.contains(TeleportFlags::RELATIVE_X) {
+                    self.velocity.x = 0.0;
+                }
+                if !flags.contains(TeleportFlags::RELATIVE_Y) {
+                    self.velocity.y = 0.0;
+                }
+                if !flags.contains(TeleportFlags::RELATIVE_Z) {
+                    self.velocity.z = 0.0;
+                }
+                self.location.yaw = if flags.contains(TeleportFlags::RELATIVE_YAW) {
+                    self.location.yaw + location.yaw.get_value()
+                } else {
+                    location.yaw.get_value()
+                };
+                self.location.pitch = if flags.contains(TeleportFlags::RELATIVE_PITCH) {
+                    self.location.pitch + location.pitch.get_value()
+                } else {
+                    location.pitch.get_value()
+                };
+
+                // the teleport is confirmed, followed by the new position like the vanilla client
+                self.packets
+                    .push(ServerboundPlayPacket::ConfirmTeleportation {
+                        teleport_id: *teleport_id,
+                    });
+                self.packets
+                    .push(ServerboundPlayPacket::SetPlayerPositionAndRotation {
+                        position: types::DoubleVec3 {
+                            x: self.location.x.into(),
+                            y: self.location.y.into(),
+                            z: self.location.z.into(),
+                        },
+                        yaw: self.location.yaw.into(),
+                        ptch: self.location.pitch.into(),
+                        on_ground: false.into(),
+                    });
+                self.last_location = self.location;
+                self.last_on_ground = false;
+                self.on_ground = false;
+                self.position_reminder = 0;
+                self.spawned = true;
+            }
+            ClientboundPlayPacket::SetEntityVelocity {
+                entity_id,
+                velocity,
+            } => {
+                if entity_id.get_value() != self.entity_id {
+                    return false;
+                }
+                // knockback
+                self.velocity = Velocity {
+                    x: velocity.x.get_value() as f64 / VELOCITY_UNIT,
+                    y: velocity.y.get_value() as f64 / VELOCITY_UNIT,
+                    z: velocity.z.get_value() as f64 / VELOCITY_UNIT,
+                };
+            }
+            ClientboundPlayPacket::Explosion { player_motion, .. } => {
+                self.velocity.x += player_motion.x.get_value() as f64;
+                self.velocity.y += player_motion.y.get_value() as f64;
+                self.velocity.z += player_motion.z.get_value() as f64;
+            }
+            ClientboundPlayPacket::Respawn { .. } => {
+                // the server teleports the player after respawning
+                self.velocity = Velocity {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                };
+                self.on_ground = false;
+                self.spawned = false;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minecraft::chunk::Chunk;
+    use crate::minecraft::registry::blocks::{BlockRegistry, set_test_block_registry};
+
+    const STONE: i32 = 1;
+    const SLAB: i32 = 2;
+
+    // one chunk with a stone floor at y 0 from x 0 to `floor_x`, the player stands at y 1
+    fn floor_world(floor_x: i32) -> World {
+        let mut registry = BlockRegistry::from_json(
+            r#"{
+                "minecraft:air": {"states": [{"id": 0, "default": true}]},
+                "minecraft:stone": {"states": [{"id": 1, "default": true}]},
+                "minecraft:oak_slab": {"states": [
+                    {"id": 2, "default": true, "properties": {"type": "bottom"}}
+                ]}
+            }"#,
+        )
+        .unwrap();
+        registry
+            .load_collision_shapes(
+                r#"{
+                    "blocks": {"air": 0, "stone": 1, "oak_slab": 2},
+                    "shapes": {"0": [], "1": [[0, 0, 0, 1, 1, 1]], "2": [[0, 0, 0, 1, 0.5, 1]]}
+                }"#,
+            )
+            .unwrap();
+        set_test_block_registry(registry);
+
+        let mut world = World::new("minecraft:overworld", 0, 16);
+        world.insert_chunk(0, 0, Chunk::new_empty(0, 0, 0, 16, 0));
+        for x in 0..floor_x {
+            for z in 0..16 {
+                world.set_block_at(x, 0, z, STONE);
+            }
+        }
+        world
+    }
+
+    // a step on the floor from x 10 to the end of the chunk
+    fn add_step(world: &mut World, block: i32) {
+        for x in 10..16 {
+            for z in 0..16 {
+                world.set_block_at(x, 1, z, block);
+            }
+        }
+    }
+
+    // a yaw of -90 faces towards +x
+    fn spawn(x: f64, y: f64, z: f64, yaw: f32) -> PlayerPhysics {
+        let mut physics = PlayerPhysics::new(1);
+        physics.apply_packet(&ClientboundPlayPacket::SynchronizePlayerPosition {
+            location: types::Location {
+                x: x.into(),
+                y: y.into(),
+                z: z.into(),
+                yaw: yaw.into(),
+                pitch: 0.0.into(),
+            },
+            flags: TeleportFlags::empty(),
+            teleport_id: 1.into(),
+        });
+        physics.take_packets();
+        physics
+    }
+
+    fn tick(physics: &mut PlayerPhysics, world: &World, ticks: u32) {
+        for _ in 0..ticks {
+            physics.tick(world);
+        }
+    }
+
+    fn walk(sneak: bool) -> MovementInput {
+        MovementInput {
+            forward: 1.0,
+            sneak,
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn gravity_and_drag() {
+        let world = floor_world(16);
+        let mut physics = spawn(8.5, 10.0, 8.5, 0.0);
+        // the velocity is applied before gravity, the player starts falling on the second tick
+        let expected = [
+            (10.0, -0.0784),
+            (9.9216, -0.155232),
+            (9.766368, -0.23052736),
+        ];
+        for (y, velocity) in expected {
+            tick(&mut physics, &world, 1);
+            assert_close(physics.get_location().y, y);
+            assert_close(physics.get_velocity().y, velocity);
+            assert!(!physics.is_on_ground());
+        }
+
+        // knockback of one block per tick, slowed down by the air
+        assert!(
+            physics.apply_packet(&ClientboundPlayPacket::SetEntityVelocity {
+                entity_id: 1.into(),
+                velocity: types::ShortVec3 {
+                    x: 8000.into(),
+                    y: 0.into(),
+                    z: 0.into(),
+                },
+            })
+        );
+        tick(&mut physics, &world, 1);
+        assert_close(physics.get_location().x, 9.5);
+        assert_close(physics.get_velocity().x, 0.91);
+        tick(&mut physics, &world, 1);
+        assert_close(physics.get_location().x, 10.41);
+        assert_close(physics.get_velocity().x, 0.8281);
+    }
+
+    #[test]
+    fn knockback_is_in_eight_thousandths() {
+        let mut physics = spawn(8.5, 10.0, 8.5, 0.0);
+        let velocity = |entity_id: i32| ClientboundPlayPacket::SetEntityVelocity {
+            entity_id: entity_id.into(),
+            velocity: types::ShortVec3 {
+                x: 8000.into(),
+                y: 4000.into(),
+                z: (-16000).into(),
+            },
+        };
+        assert!(!physics.apply_packet(&velocity(2)));
+        assert_eq!(physics.get_velocity().y, 0.0);
+        assert!(physics.apply_packet(&velocity(1)));
+        let velocity = physics.get_velocity();
+        assert_eq!((velocity.x, velocity.y, velocity.z), (1.0, 0.5, -2.0));
+    }
+
+    #[test]
+    fn lands_on_the_ground() {
+        let world = floor_world(16);
+        let mut physics = spawn(8.5, 1.5, 8.5, 0.0);
+        let mut ticks = 0;
+        while !physics.is_on_ground() {
+            assert!(ticks < 10, "the player did not land");
+            tick(&mut physics, &world, 1);
+            ticks += 1;
+        }
+        assert_close(physics.get_location().y, 1.0);
+        assert!(physics.has_vertical_collision());
+        tick(&mut physics, &world, 5);
+        assert!(physics.is_on_ground());
+        assert_close(physics.get_location().y, 1.0);
+    }
+
+    #[test]
+    fn steps_up_onto_slabs() {
+        let mut world = floor_world(16);
+        add_step(&mut world, SLAB);
+        let mut physics = spawn(8.5, 1.0, 8.5, -90.0);
+        physics.set_input(walk(false));
+        tick(&mut physics, &world, 20);
+        assert!(physics.get_location().x > 10.3);
+        assert_close(physics.get_location().y, 1.5);
+        assert!(physics.is_on_ground());
+
+        // full blocks are too high
+        add_step(&mut world, STONE);
+        let mut physics = spawn(8.5, 1.0, 8.5, -90.0);
+        physics.set_input(walk(false));
+        tick(&mut physics, &world, 20);
+        assert_close(physics.get_location().x, 10.0 - PLAYER_WIDTH / 2.0);
+        assert_close(physics.get_location().y, 1.0);
+        assert!(physics.has_horizontal_collision());
+    }
+
+    #[test]
+    fn sneaking_stops_at_the_edge() {
+        let world = floor_world(9);
+        let mut physics = spawn(8.5, 1.0, 8.5, -90.0);
+        physics.set_input(walk(true));
+        tick(&mut physics, &world, 40);
+        // the player stays on the floor with part of its box
+        let x = physics.get_location().x;
+        assert!(x > 9.0 && x < 9.0 + PLAYER_WIDTH / 2.0, "{}", x);
+        assert_close(physics.get_location().y, 1.0);
+        assert!(physics.is_on_ground());
+
+        physics.set_input(walk(false));
+        tick(&mut physics, &world, 10);
+        assert!(physics.get_location().y < 1.0);
+    }
+
+    #[test]
+    fn position_packets() {
+        let world = floor_world(16);
+        let mut physics = spawn(8.5, 1.0, 8.5, 0.0);
+        // the player is not on the ground until it tries to fall
+        tick(&mut physics, &world, 1);
+        assert!(physics.take_packets().is_empty());
+        tick(&mut physics, &world, 1);
+        assert!(matches!(
+            physics.take_packets().as_slice(),
+            [ServerboundPlayPacket::SetPlayerOnGround { .. }]
+        ));
+
+        // the position is sent every 20 ticks without moving
+        tick(&mut physics, &world, 17);
+        assert!(physics.take_packets().is_empty());
+        tick(&mut physics, &world, 1);
+        assert!(matches!(
+            physics.take_packets().as_slice(),
+            [ServerboundPlayPacket::SetPlayerPosition { .. }]
+        ));
+
+        physics.set_rotation(90.0, 10.0);
+        tick(&mut physics, &world, 1);
+        assert!(matches!(
+            physics.take_packets().as_slice(),
+            [ServerboundPlayPacket::SetPlayerRotation { .. }]
+        ));
+
+        physics.set_input(walk(false));
+        tick(&mut physics, &world, 1);
+        assert!(matches!(
+            physics.take_packets().as_slice(),
+            [ServerboundPlayPacket::SetPlayerPosition { .. }]
+        ));
+
+        physics.set_rotation(0.0, 0.0);
+        tick(&mut physics, &world, 1);
+        assert!(matches!(
+            physics.take_packets().as_slice(),
+            [ServerboundPlayPacket::SetPlayerPositionAndRotation { .. }]
+        ));
+    }
+
+    #[test]
+    fn approximated_stairs_and_doors() {
+        let registry = BlockRegistry::from_json(
+            r#"{
+                "minecraft:oak_stairs": {"states": [
+                    {"id": 1, "properties": {"facing": "north", "half": "bottom"}},
+                    {"id": 2, "properties": {"facing": "east", "half": "top"}}
+                ]},
+                "minecraft:oak_door": {"states": [
+                    {"id": 3, "properties": {"facing": "north", "hinge": "left", "open": "false"}},
+                    {"id": 4, "properties": {"facing": "north", "hinge": "left", "open": "true"}}
+                ]},
+                "minecraft:oak_trapdoor": {"states": [
+                    {"id": 5, "properties": {"facing": "south", "half": "bottom", "open": "true"}}
+                ]}
+            }"#,
+        )
+        .unwrap();
+        let shape = |id| approximate_block_shape(registry.get_state(id).unwrap());
+
+        // the step of a bottom stair can be climbed in two steps
+        let stairs = shape(1);
+        assert_eq!(stairs[0], Aabb::new(0.0, 0.0, 0.0, 1.0, 0.5, 1.0));
+        assert_eq!(stairs[1], Aabb::new(0.0, 0.5, 0.0, 1.0, 1.0, 0.5));
+        assert!(
+            stairs
+                .iter()
+                .all(|part| part.max_y - part.min_y <= STEP_HEIGHT)
+        );
+        let stairs = shape(2);
+        assert_eq!(stairs[0], Aabb::new(0.0, 0.5, 0.0, 1.0, 1.0, 1.0));
+        assert_eq!(stairs[1], Aabb::new(0.5, 0.0, 0.0, 1.0, 0.5, 1.0));
+
+        assert_eq!(shape(3), vec![Aabb::new(0.0, 0.0, 0.8125, 1.0, 1.0, 1.0)]);
+        assert_eq!(shape(4), vec![Aabb::new(0.0, 0.0, 0.0, 0.1875, 1.0, 1.0)]);
+        assert_eq!(shape(5), vec![Aabb::new(0.0, 0.0, 0.0, 1.0, 1.0, 0.1875)]);
+    }
+}
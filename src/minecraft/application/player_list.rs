@@ -0,0 +1,283 @@
+// the tab list, built from player info and tab list header/footer packets
+use std::collections::HashMap;
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::clientbound::play::{PlayerInfoUpdateAction, SignatureData};
+use crate::minecraft::types;
+use crate::utils::language::get_language;
+use crate::utils::render_text_component_plain;
+
+#[derive(Debug, Clone)]
+pub struct PlayerInfo {
+    uuid: u128,
+    name: String,
+    properties: Vec<types::SingedProperty>, // e.g. the "textures" property with the player's skin
+    game_mode: i32,                         // 0: survival, 1: creative, 2: adventure, 3: spectator
+    listed: bool,                           // whether the player is shown in the tab list
+    ping: i32,                              // in milliseconds
+    display_name: Option<types::NetworkNBT>, // replaces the name in the tab list if present
+    chat_session: Option<SignatureData>,
+}
+
+impl PlayerInfo {
+    fn new(uuid: u128) -> Self {
+        Self {
+            uuid,
+            name: String::new(),
+            properties: Vec::new(),
+            game_mode: 0,
+            listed: false,
+            ping: 0,
+            display_name: None,
+            chat_session: None,
+        }
+    }
+
+    pub fn get_uuid(&self) -> u128 {
+        self.uuid
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_properties(&self) -> &Vec<types::SingedProperty> {
+        &self.properties
+    }
+
+    pub fn get_game_mode(&self) -> i32 {
+        self.game_mode
+    }
+
+    pub fn is_listed(&self) -> bool {
+        self.listed
+    }
+
+    pub fn get_ping(&self) -> i32 {
+        self.ping
+    }
+
+    pub fn get_display_name(&self) -> Option<&types::NetworkNBT> {
+        self.display_name.as_ref()
+    }
+
+    // the name shown in the tab list as plain text
+    pub fn get_display_name_text(&self) -> String {
+        match &self.display_name {
+            Some(display_name) => {
+                render_text_component_plain(&display_name.to_json(), get_language())
+            }
+            None => self.name.clone(),
+        }
+    }
+
+    pub fn get_chat_session(&self) -> Option<&SignatureData> {
+        self.chat_session.as_ref()
+    }
+
+    fn apply_action(&mut self, action: &PlayerInfoUpdateAction) {
+        match action {
+            PlayerInfoUpdateAction::AddPlayer { name, properties } => {
+                self.name = name.get_value();
+                self.properties = properties.to_vec();
+            }
+            PlayerInfoUpdateAction::InitializeChat { signature_data } => {
+                self.chat_session = match signature_data {
+                    types::Optional::Some(data) => Some(data.clone()),
+                    types::Optional::None => None,
+                };
+            }
+            PlayerInfoUpdateAction::GameMode(game_mode) => {
+                self.game_mode = game_mode.get_value();
+            }
+            PlayerInfoUpdateAction::Listed(listed) => {
+                self.listed = listed.get_value();
+            }
+            PlayerInfoUpdateAction::Ping(ping) => {
+                self.ping = ping.get_value();
+            }
+            PlayerInfoUpdateAction::DisplayName(display_name) => {
+                self.display_name = match display_name {
+                    types::Optional::Some(nbt) => Some(nbt.clone()),
+                    types::Optional::None => None,
+                };
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerList {
+    players: HashMap<u128, PlayerInfo>,
+    header: Option<types::NetworkNBT>,
+    footer: Option<types::NetworkNBT>,
+}
+
+impl PlayerList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, uuid: u128) -> Option<&PlayerInfo> {
+        self.players.get(&uuid)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&PlayerInfo> {
+        self.players.values().find(|p| p.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PlayerInfo> {
+        self.players.values()
+    }
+
+    // the players shown in the tab list, sorted by name
+    pub fn get_listed_players(&self) -> Vec<&PlayerInfo> {
+        let mut players: Vec<&PlayerInfo> = self.players.values().filter(|p| p.listed).collect();
+        players.sort_by(|a, b| a.name.cmp(&b.name));
+        players
+    }
+
+    pub fn get_ping(&self, name: &str) -> Option<i32> {
+        self.get_by_name(name).map(|p| p.ping)
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    pub fn get_header(&self) -> Option<&types::NetworkNBT> {
+        self.header.as_ref()
+    }
+
+    pub fn get_footer(&self) -> Option<&types::NetworkNBT> {
+        self.footer.as_ref()
+    }
+
+    pub fn get_header_text(&self) -> String {
+        match &self.header {
+            Some(header) => render_text_component_plain(&header.to_json(), get_language()),
+            None => String::new(),
+        }
+    }
+
+    pub fn get_footer_text(&self) -> String {
+        match &self.footer {
+            Some(footer) => render_text_component_plain(&footer.to_json(), get_language()),
+            None => String::new(),
+        }
+    }
+
+    // applies a play packet to the player list, returns true if the packet was used
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::PlayerInfoUpdate { updates } => {
+                for (uuid, actions) in &updates.player_actions {
+                    let uuid = uuid.get_value();
+                    let is_added = actions
+                        .iter()
+                        .any(|action| matches!(action, PlayerInfoUpdateAction::AddPlayer { .. }));
+                    // players only join the list with the add player action
+                    let player = if is_added {
+                        self.players
+                            .entry(uuid)
+                            .or_insert_with(|| PlayerInfo::new(uuid))
+                    } else {
+                        match self.players.get_mut(&uuid) {
+                            Some(player) => player,
+                            None => continue,
+                        }
+                    };
+                    for action in actions {
+                        player.apply_action(action);
+                    }
+                }
+            }
+            ClientboundPlayPacket::PlayerInfoRemove { players } => {
+                for uuid in players.iter() {
+                    self.players.remove(&uuid.get_value());
+                }
+            }
+            ClientboundPlayPacket::SetTabListHeaderAndFooter { header, footer } => {
+                self.header = Some(header.clone());
+                self.footer = Some(footer.clone());
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::minecraft::clientbound::play::PlayerInfoUpdates;
+    use crate::minecraft::packet::PacketReadable;
+
+    // a player info update with the add player (0x01), listed (0x08) and ping (0x10) actions
+    fn add_players(players: &[(u128, &str, i32)]) -> Vec<u8> {
+        let mut bytes = vec![0x19, players.len() as u8];
+        for (uuid, name, ping) in players {
+            // the uuid is sent once per player, not once per action
+            bytes.extend_from_slice(&uuid.to_be_bytes());
+            bytes.push(name.len() as u8);
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.push(0); // no properties
+            bytes.push(1); // listed
+            bytes.push(*ping as u8);
+        }
+        bytes
+    }
+
+    fn apply(player_list: &mut PlayerList, bytes: Vec<u8>) {
+        let mut stream = Cursor::new(bytes);
+        let updates = PlayerInfoUpdates::read(&mut stream);
+        assert_eq!(stream.position() as usize, stream.get_ref().len());
+        assert!(player_list.apply_packet(&ClientboundPlayPacket::PlayerInfoUpdate { updates }));
+    }
+
+    #[test]
+    fn reads_every_action_of_every_player() {
+        let bytes = add_players(&[(1, "Alex", 20), (2, "Steve", 100)]);
+        let updates = PlayerInfoUpdates::read(&mut Cursor::new(bytes.clone()));
+        assert_eq!(updates.player_actions.len(), 2);
+        for (_, actions) in &updates.player_actions {
+            let bits: Vec<u8> = actions.iter().map(|a| a.get_mask_bit()).collect();
+            assert_eq!(bits, vec![0x01, 0x08, 0x10]);
+        }
+
+        let mut player_list = PlayerList::new();
+        apply(&mut player_list, bytes);
+        let alex = player_list.get(1).unwrap();
+        assert_eq!(alex.get_name(), "Alex");
+        assert!(alex.is_listed());
+        assert_eq!(alex.get_ping(), 20);
+        let steve = player_list.get(2).unwrap();
+        assert_eq!(steve.get_name(), "Steve");
+        assert_eq!(steve.get_ping(), 100);
+        assert_eq!(player_list.get_listed_players().len(), 2);
+    }
+
+    #[test]
+    fn updates_of_unknown_players_are_ignored() {
+        let mut player_list = PlayerList::new();
+        apply(&mut player_list, add_players(&[(1, "Alex", 20)]));
+
+        // ping (0x10) updates for a known and an unknown player
+        let mut bytes = vec![0x10, 2];
+        bytes.extend_from_slice(&1u128.to_be_bytes());
+        bytes.push(50);
+        bytes.extend_from_slice(&3u128.to_be_bytes());
+        bytes.push(60);
+        apply(&mut player_list, bytes);
+
+        assert_eq!(player_list.get(1).unwrap().get_ping(), 50);
+        assert!(player_list.get(3).is_none());
+        assert_eq!(player_list.len(), 1);
+    }
+}
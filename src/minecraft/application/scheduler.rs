@@ -0,0 +1,384 @@
+// runs the client at the tick rate of the server (20 TPS by default) between packets,
+// with callbacks that run every tick and tasks that run once after a delay
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+
+pub const DEFAULT_TICK_RATE: f32 = 20.0;
+const MIN_TICK_RATE: f32 = 1.0;
+const MAX_TICK_RATE: f32 = 10000.0;
+
+// when the client falls behind by more than this, the missed ticks are skipped
+const MAX_MISSED_TICKS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+struct TickCallback<T> {
+    id: TaskId,
+    callback: Box<dyn FnMut(&mut T)>,
+}
+
+struct DelayedTask<T> {
+    id: TaskId,
+    due_tick: u64,
+    task: Box<dyn FnOnce(&mut T)>,
+}
+
+pub struct TickScheduler<T> {
+    tick_rate: f32,
+    is_frozen: bool,    // set by the server, game ticks only run when stepping
+    pending_steps: u32, // game ticks the server allowed while frozen
+    current_tick: u64,  // number of game ticks that have run
+    next_tick: Option<Instant>,
+
+    next_id: u64,
+    callbacks: Vec<TickCallback<T>>,
+    tasks: Vec<DelayedTask<T>>,
+    running: HashSet<TaskId>, // callbacks taken out of the scheduler while they run
+    cancelled: HashSet<TaskId>, // running callbacks that were cancelled
+}
+
+impl<T> fmt::Debug for TickScheduler<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TickScheduler")
+            .field("tick_rate", &self.tick_rate)
+            .field("is_frozen", &self.is_frozen)
+            .field("pending_steps", &self.pending_steps)
+            .field("current_tick", &self.current_tick)
+            .field("callbacks", &self.callbacks.len())
+            .field("tasks", &self.tasks.len())
+            .finish()
+    }
+}
+
+impl<T> Default for TickScheduler<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TickScheduler<T> {
+    pub fn new() -> Self {
+        Self {
+            tick_rate: DEFAULT_TICK_RATE,
+            is_frozen: false,
+            pending_steps: 0,
+            current_tick: 0,
+            next_tick: None,
+            next_id: 0,
+            callbacks: Vec::new(),
+            tasks: Vec::new(),
+            running: HashSet::new(),
+            cancelled: HashSet::new(),
+        }
+    }
+
+    pub fn get_tick_rate(&self) -> f32 {
+        self.tick_rate
+    }
+
+    pub fn get_tick_duration(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.tick_rate as f64)
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.is_frozen
+    }
+
+    pub fn get_pending_steps(&self) -> u32 {
+        self.pending_steps
+    }
+
+    // game ticks since the scheduler was created, frozen ticks are not counted
+    pub fn get_current_tick(&self) -> u64 {
+        self.current_tick
+    }
+
+    fn next_task_id(&mut self) -> TaskId {
+        self.next_id += 1;
+        TaskId(self.next_id)
+    }
+
+    // runs the callback every game tick until it is cancelled
+    pub fn add_tick_callback(&mut self, callback: impl FnMut(&mut T) + 'static) -> TaskId {
+        let id = self.next_task_id();
+        self.callbacks.push(TickCallback {
+            id,
+            callback: Box::new(callback),
+        });
+        id
+    }
+
+    // runs the task once, the given number of game ticks from now (at least on the next tick)
+    pub fn schedule(&mut self, delay: u64, task: impl FnOnce(&mut T) + 'static) -> TaskId {
+        let id = self.next_task_id();
+        self.tasks.push(DelayedTask {
+            id,
+            due_tick: self.current_tick + delay.max(1),
+            task: Box::new(task),
+        });
+        id
+    }
+
+    // returns false if there is no callback or pending task with this id
+    pub fn cancel(&mut self, id: TaskId) -> bool {
+        if self.running.contains(&id) {
+            return self.cancelled.insert(id);
+        }
+        let count = self.callbacks.len() + self.tasks.len();
+        self.callbacks.retain(|callback| callback.id != id);
+        self.tasks.retain(|task| task.id != id);
+        count != self.callbacks.len() + self.tasks.len()
+    }
+
+    pub fn is_scheduled(&self, id: TaskId) -> bool {
+        (self.running.contains(&id) && !self.cancelled.contains(&id))
+            || self.callbacks.iter().any(|callback| callback.id == id)
+            || self.tasks.iter().any(|task| task.id == id)
+    }
+
+    // how long to wait for packets before the next tick is due
+    pub fn get_time_until_tick(&self, now: Instant) -> Duration {
+        match self.next_tick {
+            Some(next_tick) => next_tick.saturating_duration_since(now),
+            None => Duration::ZERO,
+        }
+    }
+
+    // starts a client tick if one is due, returns None if it is not time yet, otherwise
+    // whether game ticks run (false while the server has frozen the game)
+    pub fn start_tick(&mut self, now: Instant) -> Option<bool> {
+        let duration = self.get_tick_duration();
+        let next_tick = self.next_tick.unwrap_or(now);
+        if now < next_tick {
+            return None;
+        }
+        self.next_tick = if now > next_tick + duration * MAX_MISSED_TICKS {
+            Some(now + duration)
+        } else {
+            Some(next_tick + duration)
+        };
+
+        let run_game_tick = if !self.is_frozen {
+            true
+        } else if self.pending_steps > 0 {
+            self.pending_steps -= 1;
+            true
+        } else {
+            false
+        };
+        if run_game_tick {
+            self.current_tick += 1;
+        }
+        Some(run_game_tick)
+    }
+
+    // the callbacks are taken out while they run, so they can add or cancel tasks
+    fn take_callbacks(&mut self) -> Vec<TickCallback<T>> {
+        let callbacks = std::mem::take(&mut self.callbacks);
+        self.running = callbacks.iter().map(|callback| callback.id).collect();
+        callbacks
+    }
+
+    fn restore_callbacks(&mut self, mut callbacks: Vec<TickCallback<T>>) {
+        callbacks.retain(|callback| !self.cancelled.contains(&callback.id));
+        // callbacks added while running go after the existing ones
+        callbacks.append(&mut self.callbacks);
+        self.callbacks = callbacks;
+        self.running.clear();
+        self.cancelled.clear();
+    }
+
+    // removes the tasks that are due, in the order they were scheduled
+    fn take_due_tasks(&mut self) -> Vec<DelayedTask<T>> {
+        let (due, pending) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|task| task.due_tick <= self.current_tick);
+        self.tasks = pending;
+        due
+    }
+
+    // runs one game tick of callbacks and tasks on the target
+    pub fn run(target: &mut T, scheduler: impl Fn(&mut T) -> &mut Self) {
+        let mut callbacks = scheduler(target).take_callbacks();
+        for callback in callbacks.iter_mut() {
+            // cancelled by a callback that ran before it
+            if scheduler(target).cancelled.contains(&callback.id) {
+                continue;
+            }
+            (callback.callback)(target);
+        }
+        scheduler(target).restore_callbacks(callbacks);
+        for task in scheduler(target).take_due_tasks() {
+            (task.task)(target);
+        }
+    }
+
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::SetTickingState {
+                tick_rate,
+                is_frozen,
+            } => {
+                self.tick_rate = tick_rate.get_value().clamp(MIN_TICK_RATE, MAX_TICK_RATE);
+                self.is_frozen = is_frozen.get_value();
+                if !self.is_frozen {
+                    self.pending_steps = 0;
+                }
+            }
+            ClientboundPlayPacket::StepTick { tick_steps } => {
+                self.pending_steps = tick_steps.get_value().max(0) as u32;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Target {
+        scheduler: TickScheduler<Target>,
+        log: Vec<(u64, &'static str)>, // the tick each callback or task ran in
+    }
+
+    impl Target {
+        fn record(&mut self, name: &'static str) {
+            let tick = self.scheduler.get_current_tick();
+            self.log.push((tick, name));
+        }
+
+        // runs the scheduler like the play loop, `count` client ticks from `start`
+        fn run_ticks(&mut self, start: Instant, count: u32) {
+            let duration = self.scheduler.get_tick_duration();
+            for i in 0..count {
+                if self.scheduler.start_tick(start + duration * i) == Some(true) {
+                    TickScheduler::run(self, |target| &mut target.scheduler);
+                }
+            }
+        }
+    }
+
+    fn ticking_state(is_frozen: bool) -> ClientboundPlayPacket {
+        ClientboundPlayPacket::SetTickingState {
+            tick_rate: 20.0.into(),
+            is_frozen: is_frozen.into(),
+        }
+    }
+
+    #[test]
+    fn frozen_game_only_runs_stepped_ticks() {
+        let mut scheduler = TickScheduler::<()>::new();
+        let duration = scheduler.get_tick_duration();
+        let start = Instant::now();
+        assert!(scheduler.apply_packet(&ticking_state(true)));
+        assert_eq!(scheduler.start_tick(start), Some(false));
+        // the next client tick is not due yet
+        assert_eq!(scheduler.start_tick(start + duration / 2), None);
+        assert_eq!(scheduler.get_current_tick(), 0);
+
+        assert!(scheduler.apply_packet(&ClientboundPlayPacket::StepTick {
+            tick_steps: 3.into(),
+        }));
+        let game_ticks: Vec<Option<bool>> = (1..=5)
+            .map(|i| scheduler.start_tick(start + duration * i))
+            .collect();
+        assert_eq!(
+            game_ticks,
+            vec![Some(true), Some(true), Some(true), Some(false), Some(false)]
+        );
+        assert_eq!(scheduler.get_current_tick(), 3);
+
+        // unfreezing drops the steps that are left
+        scheduler.apply_packet(&ClientboundPlayPacket::StepTick {
+            tick_steps: 2.into(),
+        });
+        scheduler.apply_packet(&ticking_state(false));
+        assert_eq!(scheduler.get_pending_steps(), 0);
+        assert_eq!(scheduler.start_tick(start + duration * 6), Some(true));
+    }
+
+    #[test]
+    fn tasks_run_after_their_delay() {
+        let mut target = Target::default();
+        target
+            .scheduler
+            .schedule(3, |target| target.record("three"));
+        target.scheduler.schedule(0, |target| target.record("zero"));
+        target.scheduler.schedule(1, |target| target.record("one"));
+        let cancelled = target.scheduler.schedule(2, |target| target.record("two"));
+        assert!(target.scheduler.cancel(cancelled));
+        assert!(!target.scheduler.is_scheduled(cancelled));
+
+        target.run_ticks(Instant::now(), 5);
+        assert_eq!(target.log, vec![(1, "zero"), (1, "one"), (3, "three")]);
+    }
+
+    #[test]
+    fn callbacks_cancel_callbacks_while_running() {
+        let mut target = Target::default();
+        let second = target
+            .scheduler
+            .add_tick_callback(|target| target.record("second"));
+        let third = target
+            .scheduler
+            .add_tick_callback(|target| target.record("third"));
+        target.scheduler.add_tick_callback(move |target| {
+            target.record("first");
+            // cancels a callback that already ran this tick and one that did not
+            if target.scheduler.get_current_tick() == 1 {
+                assert!(target.scheduler.cancel(second));
+                assert!(!target.scheduler.is_scheduled(second));
+            }
+        });
+        // the callbacks run in the order they were added, move the first one to the front
+        target.scheduler.callbacks.rotate_right(1);
+        target.scheduler.add_tick_callback(move |target| {
+            if target.scheduler.get_current_tick() == 2 {
+                assert!(target.scheduler.cancel(third));
+            }
+        });
+
+        target.run_ticks(Instant::now(), 3);
+        assert_eq!(
+            target.log,
+            vec![
+                (1, "first"),
+                (1, "third"),
+                (2, "first"),
+                (2, "third"),
+                (3, "first"),
+            ]
+        );
+        assert!(!target.scheduler.is_scheduled(third));
+    }
+
+    #[test]
+    fn missed_ticks_are_skipped() {
+        let mut scheduler = TickScheduler::<()>::new();
+        let duration = scheduler.get_tick_duration();
+        let start = Instant::now();
+        assert_eq!(scheduler.start_tick(start), Some(true));
+
+        // a short delay is caught up with
+        let now = start + duration * 3;
+        let mut ticks = 0;
+        while scheduler.start_tick(now).is_some() {
+            ticks += 1;
+        }
+        assert_eq!(ticks, 3);
+
+        // a long one is not, the next tick is one tick after the late one
+        let now = now + duration * (MAX_MISSED_TICKS + 5);
+        assert_eq!(scheduler.start_tick(now), Some(true));
+        assert_eq!(scheduler.start_tick(now), None);
+        assert_eq!(scheduler.get_time_until_tick(now), duration);
+        assert_eq!(scheduler.get_current_tick(), 5);
+    }
+}
@@ -0,0 +1,301 @@
+// client side copy of the blocks around the player, updated from play packets
+use std::collections::{HashMap, HashSet};
+
+use crate::minecraft::chunk::{AIR_BLOCK_STATE, Chunk, SECTION_HEIGHT, SECTION_WIDTH};
+use crate::minecraft::clientbound::ClientboundPlayPacket;
+use crate::minecraft::light::LightStorage;
+use crate::minecraft::registry::blocks::{BlockState, get_block_registry};
+use crate::minecraft::types;
+
+// block interactions of the explosion packet that remove the affected blocks
+const EXPLOSION_DESTROY: i32 = 1;
+const EXPLOSION_DESTROY_WITH_DECAY: i32 = 2;
+
+// a block changed by the player before the server confirmed it
+#[derive(Debug, Clone, Copy)]
+struct BlockPrediction {
+    sequence: i32,     // the latest change of the block
+    server_state: i32, // restored when the change is acknowledged, unless the server agrees
+}
+
+#[derive(Debug, Clone)]
+pub struct World {
+    dimension_name: String,
+    min_y: i32,
+    height: i32,
+    chunks: HashMap<(i32, i32), Chunk>,
+    light: LightStorage,
+    sequence: i32, // the last block change sequence number
+    predictions: HashMap<(i32, i32, i32), BlockPrediction>,
+}
+
+impl World {
+    pub fn new(dimension_name: &str, min_y: i32, height: i32) -> Self {
+        Self {
+            dimension_name: dimension_name.to_string(),
+            min_y,
+            height,
+            chunks: HashMap::new(),
+            light: LightStorage::new(min_y, height),
+            sequence: 0,
+            predictions: HashMap::new(),
+        }
+    }
+
+    pub fn get_dimension_name(&self) -> &str {
+        &self.dimension_name
+    }
+
+    pub fn get_min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn get_light(&self) -> &LightStorage {
+        &self.light
+    }
+
+    // removes every chunk, e.g. when respawning into another dimension
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.light.clear();
+        self.predictions.clear();
+    }
+
+    // switches to another dimension, the cached chunks are dropped if the dimension changed
+    pub fn set_dimension(&mut self, dimension_name: &str, min_y: i32, height: i32) {
+        if self.dimension_name == dimension_name && self.min_y == min_y && self.height == height {
+            return;
+        }
+        self.dimension_name = dimension_name.to_string();
+        self.min_y = min_y;
+        self.height = height;
+        self.chunks.clear();
+        self.light = LightStorage::new(min_y, height);
+        self.predictions.clear();
+    }
+
+    pub fn get_chunk(&self, chunk_x: i32, chunk_z: i32) -> Option<&Chunk> {
+        self.chunks.get(&(chunk_x, chunk_z))
+    }
+
+    // replaces the chunk at the chunk coordinates, the chunk should have the height of the world
+    pub fn insert_chunk(&mut self, chunk_x: i32, chunk_z: i32, chunk: Chunk) {
+        self.chunks.insert((chunk_x, chunk_z), chunk);
+    }
+
+    pub fn is_chunk_loaded(&self, chunk_x: i32, chunk_z: i32) -> bool {
+        self.chunks.contains_key(&(chunk_x, chunk_z))
+    }
+
+    // chunk coordinates of every loaded chunk
+    pub fn get_loaded_chunks(&self) -> HashSet<(i32, i32)> {
+        self.chunks.keys().copied().collect()
+    }
+
+    pub fn get_loaded_chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    // block state id at the given block coordinates, None if the chunk is not loaded
+    pub fn get_block_at(&self, x: i32, y: i32, z: i32) -> Option<i32> {
+        self.get_chunk(x.div_euclid(SECTION_WIDTH), z.div_euclid(SECTION_WIDTH))?
+            .block_state_at(x, y, z)
+    }
+
+    pub fn get_block(&self, position: &types::Position) -> Option<i32> {
+        self.get_block_at(position.get_x(), position.get_y() as i32, position.get_z())
+    }
+
+    // requires the block registry to be loaded, see `set_block_registry`
+    pub fn get_block_state(&self, position: &types::Position) -> Option<&'static BlockState> {
+        get_block_registry()?.get_state(self.get_block(position)?)
+    }
+
+    // returns false if the chunk is not loaded or y is outside the world
+    pub fn set_block_at(&mut self, x: i32, y: i32, z: i32, state: i32) -> bool {
+        match self
+            .chunks
+            .get_mut(&(x.div_euclid(SECTION_WIDTH), z.div_euclid(SECTION_WIDTH)))
+        {
+            Some(chunk) => chunk.set_block_state_at(x, y, z, state),
+            None => false,
+        }
+    }
+
+    pub fn set_block(&mut self, position: &types::Position, state: i32) -> bool {
+        self.set_block_at(
+            position.get_x(),
+            position.get_y() as i32,
+            position.get_z(),
+            state,
+        )
+    }
+
+    // block changes made by the player are numbered, the server acknowledges them in order
+    pub fn next_sequence(&mut self) -> i32 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    pub fn get_sequence(&self) -> i32 {
+        self.sequence
+    }
+
+    // changes a block before the server confirms it, the block is set back to the state the
+    // server sent when the sequence is acknowledged, returns false if the chunk is not loaded
+    pub fn predict_block(&mut self, position: &types::Position, state: i32, sequence: i32) -> bool {
+        let server_state = match self.get_block(position) {
+            Some(state) => state,
+            None => return false,
+        };
+        let key = (position.get_x(), position.get_y() as i32, position.get_z());
+        self.predictions
+            .entry(key)
+            .and_modify(|prediction| prediction.sequence = sequence)
+            .or_insert(BlockPrediction {
+                sequence,
+                server_state,
+            });
+        self.set_block(position, state)
+    }
+
+    // whether the block was changed by the player and the server has not acknowledged it yet
+    pub fn is_predicted(&self, position: &types::Position) -> bool {
+        self.predictions.contains_key(&(
+            position.get_x(),
+            position.get_y() as i32,
+            position.get_z(),
+        ))
+    }
+
+    pub fn get_prediction_count(&self) -> usize {
+        self.predictions.len()
+    }
+
+    // changes from the server do not replace predicted blocks before the acknowledgement
+    fn set_server_block_at(&mut self, x: i32, y: i32, z: i32, state: i32) {
+        match self.predictions.get_mut(&(x, y, z)) {
+            Some(prediction) => prediction.server_state = state,
+            None => {
+                self.set_block_at(x, y, z, state);
+            }
+        }
+    }
+
+    // confirms or rolls back every prediction up to the sequence number
+    fn acknowledge(&mut self, sequence: i32) {
+        let acknowledged: Vec<((i32, i32, i32), i32)> = self
+            .predictions
+            .iter()
+            .filter(|(_, prediction)| prediction.sequence <= sequence)
+            .map(|(key, prediction)| (*key, prediction.server_state))
+            .collect();
+        for ((x, y, z), server_state) in acknowledged {
+            self.predictions.remove(&(x, y, z));
+            if self.get_block_at(x, y, z) != Some(server_state) {
+                self.set_block_at(x, y, z, server_state);
+            }
+        }
+    }
+
+    pub fn sky_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        self.light.sky_light(x, y, z)
+    }
+
+    pub fn block_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        self.light.block_light(x, y, z)
+    }
+
+    // applies a play packet to the world, returns true if the packet was used
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) -> bool {
+        match packet {
+            ClientboundPlayPacket::ChunkDataAndUpdateLight {
+                chunk_x,
+                chunk_z,
+                data,
+                ..
+            } => {
+                let chunk = Chunk::from_data(
+                    data,
+                    chunk_x.get_value(),
+                    chunk_z.get_value(),
+                    self.min_y,
+                    self.height,
+                );
+                self.insert_chunk(chunk_x.get_value(), chunk_z.get_value(), chunk);
+                self.light.apply_packet(packet);
+            }
+            ClientboundPlayPacket::UpdateLight { .. } => {
+                self.light.apply_packet(packet);
+            }
+            ClientboundPlayPacket::UnloadChunk { chunk_x, chunk_z } => {
+                self.chunks
+                    .remove(&(chunk_x.get_value(), chunk_z.get_value()));
+                self.light
+                    .unload_chunk(chunk_x.get_value(), chunk_z.get_value());
+            }
+            ClientboundPlayPacket::BlockUpdate { location, block_id } => {
+                self.set_server_block_at(
+                    location.get_x(),
+                    location.get_y() as i32,
+                    location.get_z(),
+                    block_id.get_value(),
+                );
+            }
+            ClientboundPlayPacket::AcknowledgeBlockChange { sequence_id } => {
+                self.acknowledge(sequence_id.get_value());
+            }
+            ClientboundPlayPacket::UpdateSectionBlocks {
+                chunk_section_position,
+                blocks_array,
+            } => {
+                // 22 bits x, 22 bits z and 20 bits y, all signed
+                let position = chunk_section_position.get_value();
+                let section_x = (position >> 42) as i32;
+                let section_y = ((position << 44) >> 44) as i32;
+                let section_z = ((position << 22) >> 42) as i32;
+                for entry in blocks_array.iter() {
+                    let entry = entry.get_value();
+                    let state = (entry >> 12) as i32;
+                    let x = ((entry >> 8) & 0xF) as i32;
+                    let z = ((entry >> 4) & 0xF) as i32;
+                    let y = (entry & 0xF) as i32;
+                    self.set_server_block_at(
+                        section_x * SECTION_WIDTH + x,
+                        section_y * SECTION_HEIGHT + y,
+                        section_z * SECTION_WIDTH + z,
+                        state,
+                    );
+                }
+            }
+            ClientboundPlayPacket::Explosion {
+                position,
+                records,
+                block_interaction,
+                ..
+            } => {
+                let interaction = block_interaction.get_value();
+                if interaction == EXPLOSION_DESTROY || interaction == EXPLOSION_DESTROY_WITH_DECAY {
+                    // records are offsets from the block the explosion happened in
+                    let x = position.x.get_value().floor() as i32;
+                    let y = position.y.get_value().floor() as i32;
+                    let z = position.z.get_value().floor() as i32;
+                    for record in records.iter() {
+                        self.set_block_at(
+                            x + record.x.get_value() as i32,
+                            y + record.y.get_value() as i32,
+                            z + record.z.get_value() as i32,
+                            AIR_BLOCK_STATE,
+                        );
+                    }
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
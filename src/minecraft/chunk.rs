@@ -0,0 +1,725 @@
+// chunk data format based on https://minecraft.wiki/w/Java_Edition_protocol/Chunk_format
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::{Cursor, Read, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use super::clientbound::ClientboundPlayPacket;
+use super::clientbound::play::BlockEntityData;
+use super::light::ChunkLight;
+use super::packet::{PacketReadable, PacketWritable};
+use super::types;
+
+pub const SECTION_WIDTH: i32 = 16;
+pub const SECTION_HEIGHT: i32 = 16;
+
+// height of the overworld, used until the dimension type is known
+pub const DEFAULT_MIN_Y: i32 = -64;
+pub const DEFAULT_HEIGHT: i32 = 384;
+
+pub const AIR_BLOCK_STATE: i32 = 0;
+
+// bits per entry of the direct palettes, ceil(log2(number of ids in the registry))
+pub const DIRECT_BLOCK_STATE_BITS: u8 = 15;
+// the biome registry is sent by the server, this is the width for the vanilla biomes
+pub const DEFAULT_DIRECT_BIOME_BITS: u8 = 6;
+
+static DIRECT_BIOME_BITS: AtomicU8 = AtomicU8::new(DEFAULT_DIRECT_BIOME_BITS);
+
+// sets the width of direct biome palettes from the number of entries of the
+// `minecraft:worldgen/biome` registry received in the configuration state
+pub fn set_biome_registry_size(size: usize) {
+    DIRECT_BIOME_BITS.store(bits_for(size.max(1) as u64 - 1), Ordering::Relaxed);
+}
+
+// number of bits needed to store values from 0 to `max_value`
+#[inline]
+fn bits_for(max_value: u64) -> u8 {
+    (64 - max_value.leading_zeros()) as u8
+}
+
+// packs values into longs, entries never span across two longs
+fn pack_values(values: impl Iterator<Item = u64>, bits: u8, count: usize) -> Vec<i64> {
+    let bits = bits as usize;
+    let entries_per_long = 64 / bits;
+    let mut data = vec![0i64; count.div_ceil(entries_per_long)];
+    for (index, value) in values.enumerate() {
+        let offset = (index % entries_per_long) * bits;
+        data[index / entries_per_long] |= (value << offset) as i64;
+    }
+    data
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PalettedContainerKind {
+    BlockStates, // 16x16x16 block states per section
+    Biomes,      // 4x4x4 biomes per section
+}
+
+impl PalettedContainerKind {
+    // number of entries along each axis
+    pub fn get_size(&self) -> usize {
+        match self {
+            Self::BlockStates => 16,
+            Self::Biomes => 4,
+        }
+    }
+
+    pub fn get_entry_count(&self) -> usize {
+        self.get_size().pow(3)
+    }
+
+    // the bits per entry of the direct palette, which holds the registry ids themselves
+    pub fn get_direct_bits(&self) -> u8 {
+        match self {
+            Self::BlockStates => DIRECT_BLOCK_STATE_BITS,
+            Self::Biomes => DIRECT_BIOME_BITS.load(Ordering::Relaxed),
+        }
+    }
+
+    // the bits per entry actually used for a given bits per entry value sent over the network,
+    // returns None for the direct palette, see `get_direct_bits`
+    pub fn get_indirect_bits(&self, bits_per_entry: u8) -> Option<u8> {
+        match self {
+            Self::BlockStates => match bits_per_entry {
+                1..=4 => Some(4),
+                5..=8 => Some(bits_per_entry),
+                _ => None,
+            },
+            Self::Biomes => match bits_per_entry {
+                1..=3 => Some(bits_per_entry),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Palette {
+    SingleValued(i32),  // every entry has the same registry id, there is no data array
+    Indirect(Vec<i32>), // the data array holds indices into this list of registry ids
+    Direct,             // the data array holds registry ids
+}
+
+#[derive(Clone)]
+pub struct PalettedContainer {
+    kind: PalettedContainerKind,
+    bits_per_entry: u8,
+    palette: Palette,
+    data: Vec<i64>,
+}
+
+impl Debug for PalettedContainer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!(
+            "PalettedContainer {{ kind: {:?}, bits_per_entry: {}, palette: {:?}, data: [{} longs] }}",
+            self.kind,
+            self.bits_per_entry,
+            self.palette,
+            self.data.len()
+        ))
+    }
+}
+
+impl PalettedContainer {
+    pub fn new_single_valued(kind: PalettedContainerKind, value: i32) -> Self {
+        Self {
+            kind,
+            bits_per_entry: 0,
+            palette: Palette::SingleValued(value),
+            data: Vec::new(),
+        }
+    }
+
+    // builds a container with the smallest palette that can hold the given values,
+    // `values` must have exactly `kind.get_entry_count()` entries
+    pub fn from_values(kind: PalettedContainerKind, values: &[i32]) -> Self {
+        if values.len() != kind.get_entry_count() {
+            panic!(
+                "PalettedContainer: expected {} values but got {}",
+                kind.get_entry_count(),
+                values.len()
+            );
+        }
+
+        // registry ids in order of first appearance
+        let mut palette: Vec<i32> = Vec::new();
+        let mut palette_indices: HashMap<i32, usize> = HashMap::new();
+        for value in values {
+            if !palette_indices.contains_key(value) {
+                palette_indices.insert(*value, palette.len());
+                palette.push(*value);
+            }
+        }
+
+        if palette.len() == 1 {
+            return Self::new_single_valued(kind, palette[0]);
+        }
+
+        let count = values.len();
+        let bits = bits_for(palette.len() as u64 - 1);
+        match kind.get_indirect_bits(bits) {
+            Some(bits) => {
+                let data = pack_values(
+                    values.iter().map(|v| palette_indices[v] as u64),
+                    bits,
+                    count,
+                );
+                Self {
+                    kind,
+                    bits_per_entry: bits,
+                    palette: Palette::Indirect(palette),
+                    data,
+                }
+            }
+            None => {
+                let max_value = values.iter().copied().max().unwrap_or(0).max(0) as u64;
+                let bits = kind.get_direct_bits().max(bits_for(max_value));
+                let data = pack_values(values.iter().map(|v| *v as u64), bits, count);
+                Self {
+                    kind,
+                    bits_per_entry: bits,
+                    palette: Palette::Direct,
+                    data,
+                }
+            }
+        }
+    }
+
+    pub fn read(stream: &mut impl Read, kind: PalettedContainerKind) -> Self {
+        let bits_per_entry = types::UnsignedByte::read(stream).get_value();
+        let (bits_per_entry, palette) = if bits_per_entry == 0 {
+            let value = types::VarInt::read(stream).get_value();
+            (0, Palette::SingleValued(value))
+        } else if let Some(bits) = kind.get_indirect_bits(bits_per_entry) {
+            let palette: types::Array<types::VarInt> = types::Array::read(stream);
+            let palette: Vec<i32> = palette.iter().map(|v| v.get_value()).collect();
+            (bits, Palette::Indirect(palette))
+        } else {
+            // like vanilla, the width comes from the registry size and not from the server
+            (kind.get_direct_bits(), Palette::Direct)
+        };
+
+        let data: types::Array<types::Long> = types::Array::read(stream);
+        let data: Vec<i64> = data.iter().map(|v| v.get_value()).collect();
+
+        Self {
+            kind,
+            bits_per_entry,
+            palette,
+            data,
+        }
+    }
+
+    pub fn get_kind(&self) -> PalettedContainerKind {
+        self.kind
+    }
+
+    pub fn get_bits_per_entry(&self) -> u8 {
+        self.bits_per_entry
+    }
+
+    pub fn get_palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    pub fn get_data(&self) -> &Vec<i64> {
+        &self.data
+    }
+
+    // index of an entry, x, y and z must be smaller than the container size
+    #[inline]
+    pub fn get_index(&self, x: usize, y: usize, z: usize) -> usize {
+        let size = self.kind.get_size();
+        (y * size + z) * size + x
+    }
+
+    // reads the raw value stored in the data array, entries never span across two longs
+    fn get_raw(&self, index: usize) -> u64 {
+        let bits = self.bits_per_entry as usize;
+        let entries_per_long = 64 / bits;
+        let long = match self.data.get(index / entries_per_long) {
+            Some(v) => *v as u64,
+            None => return 0,
+        };
+        let offset = (index % entries_per_long) * bits;
+        (long >> offset) & ((1u64 << bits) - 1)
+    }
+
+    // registry id of the entry at `index`
+    pub fn get_by_index(&self, index: usize) -> i32 {
+        match &self.palette {
+            Palette::SingleValued(value) => *value,
+            Palette::Indirect(palette) => {
+                let raw = self.get_raw(index) as usize;
+                palette.get(raw).copied().unwrap_or(0)
+            }
+            Palette::Direct => self.get_raw(index) as i32,
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> i32 {
+        self.get_by_index(self.get_index(x, y, z))
+    }
+
+    // every entry of the container in index order
+    pub fn get_values(&self) -> Vec<i32> {
+        (0..self.kind.get_entry_count())
+            .map(|i| self.get_by_index(i))
+            .collect()
+    }
+
+    fn set_raw(&mut self, index: usize, value: u64) {
+        let bits = self.bits_per_entry as usize;
+        let entries_per_long = 64 / bits;
+        let offset = (index % entries_per_long) * bits;
+        let mask = ((1u64 << bits) - 1) << offset;
+        let long = &mut self.data[index / entries_per_long];
+        *long = ((*long as u64 & !mask) | ((value << offset) & mask)) as i64;
+    }
+
+    // sets the entry at `index`, the palette is rebuilt when the value does not fit into it
+    pub fn set_by_index(&mut self, index: usize, value: i32) {
+        if self.get_by_index(index) == value {
+            return;
+        }
+        let raw = match &mut self.palette {
+            Palette::SingleValued(_) => None,
+            Palette::Indirect(palette) => match palette.iter().position(|v| *v == value) {
+                Some(i) => Some(i as u64),
+                None if palette.len() < (1 << self.bits_per_entry) => {
+                    palette.push(value);
+                    Some(palette.len() as u64 - 1)
+                }
+                None => None,
+            },
+            Palette::Direct if bits_for(value.max(0) as u64) <= self.bits_per_entry => {
+                Some(value as u64)
+            }
+            Palette::Direct => None,
+        };
+        match raw {
+            Some(raw) => self.set_raw(index, raw),
+            None => {
+                let mut values = self.get_values();
+                values[index] = value;
+                *self = Self::from_values(self.kind, &values);
+            }
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, value: i32) {
+        self.set_by_index(self.get_index(x, y, z), value);
+    }
+}
+
+impl PacketWritable for PalettedContainer {
+    fn write(&self, stream: &mut impl Write) {
+        types::UnsignedByte::new(self.bits_per_entry).write(stream);
+        match &self.palette {
+            Palette::SingleValued(value) => types::VarInt::new(*value).write(stream),
+            Palette::Indirect(palette) => {
+                let palette: Vec<types::VarInt> =
+                    palette.iter().map(|v| types::VarInt::new(*v)).collect();
+                types::Array::new(palette).write(stream);
+            }
+            Palette::Direct => {}
+        }
+        let data: Vec<types::Long> = self.data.iter().map(|v| types::Long::new(*v)).collect();
+        types::Array::new(data).write(stream);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChunkSection {
+    block_count: i16, // number of non-air blocks, used for lighting
+    block_states: PalettedContainer,
+    biomes: PalettedContainer,
+}
+
+impl ChunkSection {
+    pub fn new(
+        block_count: i16,
+        block_states: PalettedContainer,
+        biomes: PalettedContainer,
+    ) -> Self {
+        Self {
+            block_count,
+            block_states,
+            biomes,
+        }
+    }
+
+    // a section filled with air
+    pub fn new_empty(biome: i32) -> Self {
+        Self::new(
+            0,
+            PalettedContainer::new_single_valued(
+                PalettedContainerKind::BlockStates,
+                AIR_BLOCK_STATE,
+            ),
+            PalettedContainer::new_single_valued(PalettedContainerKind::Biomes, biome),
+        )
+    }
+
+    pub fn get_block_count(&self) -> i16 {
+        self.block_count
+    }
+
+    pub fn get_block_states(&self) -> &PalettedContainer {
+        &self.block_states
+    }
+
+    pub fn get_biomes(&self) -> &PalettedContainer {
+        &self.biomes
+    }
+
+    // x, y and z are relative to the section (0-15)
+    pub fn get_block_state(&self, x: usize, y: usize, z: usize) -> i32 {
+        self.block_states.get(x, y, z)
+    }
+
+    // x, y and z are in biome coordinates relative to the section (0-3)
+    pub fn get_biome(&self, x: usize, y: usize, z: usize) -> i32 {
+        self.biomes.get(x, y, z)
+    }
+
+    // keeps the block count up to date assuming only `AIR_BLOCK_STATE` is air,
+    // use `count_blocks` afterwards if other air-like states (e.g. cave air) are placed
+    pub fn set_block_state(&mut self, x: usize, y: usize, z: usize, state: i32) {
+        let previous = self.block_states.get(x, y, z);
+        if previous == AIR_BLOCK_STATE && state != AIR_BLOCK_STATE {
+            self.block_count += 1;
+        } else if previous != AIR_BLOCK_STATE && state == AIR_BLOCK_STATE {
+            self.block_count -= 1;
+        }
+        self.block_states.set(x, y, z, state);
+    }
+
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, biome: i32) {
+        self.biomes.set(x, y, z, biome);
+    }
+
+    // recalculates the number of non-air blocks
+    pub fn count_blocks(&mut self, is_air: impl Fn(i32) -> bool) {
+        self.block_count = match self.block_states.get_palette() {
+            Palette::SingleValued(value) if is_air(*value) => 0,
+            Palette::SingleValued(_) => PalettedContainerKind::BlockStates.get_entry_count() as i16,
+            _ => self
+                .block_states
+                .get_values()
+                .into_iter()
+                .filter(|v| !is_air(*v))
+                .count() as i16,
+        };
+    }
+}
+
+impl PacketReadable for ChunkSection {
+    fn read(stream: &mut impl Read) -> Self {
+        let block_count = types::Short::read(stream).get_value();
+        let block_states = PalettedContainer::read(stream, PalettedContainerKind::BlockStates);
+        let biomes = PalettedContainer::read(stream, PalettedContainerKind::Biomes);
+        Self {
+            block_count,
+            block_states,
+            biomes,
+        }
+    }
+}
+
+impl PacketWritable for ChunkSection {
+    fn write(&self, stream: &mut impl Write) {
+        types::Short::new(self.block_count).write(stream);
+        self.block_states.write(stream);
+        self.biomes.write(stream);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    chunk_x: i32,
+    chunk_z: i32,
+    min_y: i32, // lowest block y of the dimension
+    sections: Vec<ChunkSection>,
+}
+
+impl Chunk {
+    // the number of sections sent in a chunk depends on the height of the dimension
+    #[inline]
+    pub fn get_section_count(height: i32) -> usize {
+        (height / SECTION_HEIGHT).max(0) as usize
+    }
+
+    // a chunk filled with air
+    pub fn new_empty(chunk_x: i32, chunk_z: i32, min_y: i32, height: i32, biome: i32) -> Self {
+        Self {
+            chunk_x,
+            chunk_z,
+            min_y,
+            sections: vec![ChunkSection::new_empty(biome); Self::get_section_count(height)],
+        }
+    }
+
+    pub fn read(
+        stream: &mut impl Read,
+        chunk_x: i32,
+        chunk_z: i32,
+        min_y: i32,
+        height: i32,
+    ) -> Self {
+        let section_count = Self::get_section_count(height);
+        let mut sections = Vec::with_capacity(section_count);
+        for _ in 0..section_count {
+            sections.push(ChunkSection::read(stream));
+        }
+        Self {
+            chunk_x,
+            chunk_z,
+            min_y,
+            sections,
+        }
+    }
+
+    // decodes the `data` field of the Chunk Data and Update Light packet
+    pub fn from_data(
+        data: &types::ByteArray,
+        chunk_x: i32,
+        chunk_z: i32,
+        min_y: i32,
+        height: i32,
+    ) -> Self {
+        let mut stream = Cursor::new(data.as_slice());
+        Self::read(&mut stream, chunk_x, chunk_z, min_y, height)
+    }
+
+    pub fn get_chunk_x(&self) -> i32 {
+        self.chunk_x
+    }
+
+    pub fn get_chunk_z(&self) -> i32 {
+        self.chunk_z
+    }
+
+    pub fn get_min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    pub fn get_height(&self) -> i32 {
+        self.sections.len() as i32 * SECTION_HEIGHT
+    }
+
+    pub fn get_sections(&self) -> &Vec<ChunkSection> {
+        &self.sections
+    }
+
+    // index of the section containing the block y coordinate
+    fn get_section_index(&self, y: i32) -> Option<usize> {
+        let section_y = (y - self.min_y).div_euclid(SECTION_HEIGHT);
+        if section_y < 0 || section_y as usize >= self.sections.len() {
+            return None;
+        }
+        Some(section_y as usize)
+    }
+
+    pub fn get_section(&self, y: i32) -> Option<&ChunkSection> {
+        self.sections.get(self.get_section_index(y)?)
+    }
+
+    pub fn get_section_mut(&mut self, y: i32) -> Option<&mut ChunkSection> {
+        let index = self.get_section_index(y)?;
+        self.sections.get_mut(index)
+    }
+
+    // block state id at the given block coordinates, only the lowest 4 bits of x and z are used
+    // so both absolute and chunk relative coordinates work. returns None if y is outside the world
+    pub fn block_state_at(&self, x: i32, y: i32, z: i32) -> Option<i32> {
+        let section = self.get_section(y)?;
+        Some(section.get_block_state(
+            x.rem_euclid(SECTION_WIDTH) as usize,
+            (y - self.min_y).rem_euclid(SECTION_HEIGHT) as usize,
+            z.rem_euclid(SECTION_WIDTH) as usize,
+        ))
+    }
+
+    // biome id at the given block coordinates
+    pub fn biome_at(&self, x: i32, y: i32, z: i32) -> Option<i32> {
+        let section = self.get_section(y)?;
+        Some(section.get_biome(
+            (x.rem_euclid(SECTION_WIDTH) / 4) as usize,
+            ((y - self.min_y).rem_euclid(SECTION_HEIGHT) / 4) as usize,
+            (z.rem_euclid(SECTION_WIDTH) / 4) as usize,
+        ))
+    }
+
+    // sets the block state at the given block coordinates, returns false if y is outside the world
+    pub fn set_block_state_at(&mut self, x: i32, y: i32, z: i32, state: i32) -> bool {
+        let min_y = self.min_y;
+        match self.get_section_mut(y) {
+            Some(section) => {
+                section.set_block_state(
+                    x.rem_euclid(SECTION_WIDTH) as usize,
+                    (y - min_y).rem_euclid(SECTION_HEIGHT) as usize,
+                    z.rem_euclid(SECTION_WIDTH) as usize,
+                    state,
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_biome_at(&mut self, x: i32, y: i32, z: i32, biome: i32) -> bool {
+        let min_y = self.min_y;
+        match self.get_section_mut(y) {
+            Some(section) => {
+                section.set_biome(
+                    (x.rem_euclid(SECTION_WIDTH) / 4) as usize,
+                    ((y - min_y).rem_euclid(SECTION_HEIGHT) / 4) as usize,
+                    (z.rem_euclid(SECTION_WIDTH) / 4) as usize,
+                    biome,
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    // encodes the sections into the `data` field of the Chunk Data and Update Light packet
+    pub fn to_data(&self) -> types::ByteArray {
+        let mut stream = Cursor::new(Vec::new());
+        for section in &self.sections {
+            section.write(&mut stream);
+        }
+        types::ByteArray::new(stream.into_inner())
+    }
+
+    // y of the block above the highest block matching `predicate` for every column, relative
+    // to the bottom of the world (0 means the column has no matching block)
+    fn get_heightmap(&self, predicate: &impl Fn(i32) -> bool) -> Vec<u64> {
+        let mut heights = vec![0u64; 256];
+        for (i, height) in heights.iter_mut().enumerate() {
+            let x = (i % 16) as i32;
+            let z = (i / 16) as i32;
+            for y in (self.min_y..self.min_y + self.get_height()).rev() {
+                if predicate(self.block_state_at(x, y, z).unwrap_or(AIR_BLOCK_STATE)) {
+                    *height = (y - self.min_y + 1) as u64;
+                    break;
+                }
+            }
+        }
+        heights
+    }
+
+    // builds the heightmaps sent to the client, WORLD_SURFACE is the highest non-air block
+    // and MOTION_BLOCKING is the highest block that blocks motion or contains a fluid
+    pub fn get_heightmaps(
+        &self,
+        is_air: impl Fn(i32) -> bool,
+        blocks_motion: impl Fn(i32) -> bool,
+    ) -> types::NetworkNBT {
+        let bits = bits_for(self.get_height() as u64);
+        let world_surface = self.get_heightmap(&|state| !is_air(state));
+        let motion_blocking = self.get_heightmap(&blocks_motion);
+
+        let mut heightmaps = HashMap::new();
+        heightmaps.insert(
+            "MOTION_BLOCKING".to_string(),
+            types::NBTValue::LongArray(pack_values(motion_blocking.into_iter(), bits, 256)),
+        );
+        heightmaps.insert(
+            "WORLD_SURFACE".to_string(),
+            types::NBTValue::LongArray(pack_values(world_surface.into_iter(), bits, 256)),
+        );
+        types::NetworkNBT::new(types::NBTValue::Compound(String::new(), heightmaps))
+    }
+
+    pub fn to_packet(
+        &self,
+        heightmaps: types::NetworkNBT,
+        block_entities: Vec<BlockEntityData>,
+        light: &ChunkLight,
+    ) -> ClientboundPlayPacket {
+        let (sky_light_mask, empty_sky_light_mask, sky_light_arrays) = light.encode_sky_light();
+        let (block_light_mask, empty_block_light_mask, block_light_arrays) =
+            light.encode_block_light();
+        ClientboundPlayPacket::ChunkDataAndUpdateLight {
+            chunk_x: types::Int::new(self.chunk_x),
+            chunk_z: types::Int::new(self.chunk_z),
+            heightmaps,
+            data: self.to_data(),
+            block_entities: types::Array::new(block_entities),
+            sky_light_mask,
+            block_light_mask,
+            empty_sky_light_mask,
+            empty_block_light_mask,
+            sky_light_arrays,
+            block_light_arrays,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(container: &PalettedContainer) -> PalettedContainer {
+        let mut buffer = Vec::new();
+        container.write(&mut buffer);
+        let mut stream = Cursor::new(buffer);
+        let read = PalettedContainer::read(&mut stream, container.get_kind());
+        assert_eq!(stream.position() as usize, stream.get_ref().len());
+        read
+    }
+
+    fn assert_round_trip(kind: PalettedContainerKind, values: &[i32], palette: Palette) {
+        let container = PalettedContainer::from_values(kind, values);
+        assert_eq!(
+            std::mem::discriminant(container.get_palette()),
+            std::mem::discriminant(&palette)
+        );
+        let read = round_trip(&container);
+        assert_eq!(read.get_bits_per_entry(), container.get_bits_per_entry());
+        assert_eq!(read.get_palette(), container.get_palette());
+        assert_eq!(read.get_values(), values);
+    }
+
+    #[test]
+    fn paletted_container_round_trip() {
+        let kind = PalettedContainerKind::BlockStates;
+        let count = kind.get_entry_count() as i32;
+        assert_round_trip(kind, &vec![1; count as usize], Palette::SingleValued(1));
+        let indirect: Vec<i32> = (0..count).map(|i| (i % 7) * 100).collect();
+        assert_round_trip(kind, &indirect, Palette::Indirect(Vec::new()));
+        let direct: Vec<i32> = (0..count).map(|i| i * 5).collect();
+        assert_round_trip(kind, &direct, Palette::Direct);
+
+        let kind = PalettedContainerKind::Biomes;
+        let count = kind.get_entry_count() as i32;
+        let indirect: Vec<i32> = (0..count).map(|i| i % 5).collect();
+        assert_round_trip(kind, &indirect, Palette::Indirect(Vec::new()));
+        let direct: Vec<i32> = (0..count).map(|i| i % 60).collect();
+        assert_round_trip(kind, &direct, Palette::Direct);
+    }
+
+    #[test]
+    fn paletted_container_set_changes_palette() {
+        let kind = PalettedContainerKind::BlockStates;
+        let mut container = PalettedContainer::new_single_valued(kind, AIR_BLOCK_STATE);
+        for (index, value) in [(0, 1), (17, 2), (4095, 300)] {
+            container.set_by_index(index, value);
+        }
+        let read = round_trip(&container);
+        assert_eq!(read.get_by_index(0), 1);
+        assert_eq!(read.get_by_index(17), 2);
+        assert_eq!(read.get_by_index(4095), 300);
+        assert_eq!(read.get_by_index(1), AIR_BLOCK_STATE);
+    }
+
+    #[test]
+    fn registry_size_to_direct_bits() {
+        assert_eq!(bits_for(64 - 1), 6);
+        assert_eq!(bits_for(65 - 1), 7);
+        assert_eq!(bits_for(1000 - 1), 10);
+    }
+}
@@ -148,6 +148,28 @@ pub enum FilterType {
     PartiallyFiltered(types::BitSet),
 }
 
+// the values a chat type decoration passes to its translation, in order
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatTypeParameter {
+    Sender,
+    Target,
+    Content,
+}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct ChatTypeDecoration {
+    pub translation_key: types::String, // e.g. "chat.type.text"
+    pub parameters: types::Array<ChatTypeParameter>,
+    pub style: types::NetworkNBT, // applied to the whole message
+}
+
+// an inline entry of the minecraft:chat_type registry
+#[derive(PacketSerde, Debug, Clone)]
+pub struct ChatType {
+    pub chat: ChatTypeDecoration,
+    pub narration: ChatTypeDecoration,
+}
+
 #[derive(PacketSerde, Debug, Clone)]
 pub struct SignatureData {
     pub session_id: types::UUID,
@@ -174,7 +196,22 @@ pub enum PlayerInfoUpdateAction {
 #[derive(Debug, Clone)]
 pub struct PlayerInfoUpdates {
     pub actions_mask: types::Byte,
-    pub player_actions: Vec<(types::UUID, PlayerInfoUpdateAction)>,
+    // the actions of each player, in the order of the bits of the actions mask
+    pub player_actions: Vec<(types::UUID, Vec<PlayerInfoUpdateAction>)>,
+}
+
+impl PlayerInfoUpdateAction {
+    // the bit of the action in the actions mask
+    pub fn get_mask_bit(&self) -> u8 {
+        match self {
+            PlayerInfoUpdateAction::AddPlayer { .. } => 0x01,
+            PlayerInfoUpdateAction::InitializeChat { .. } => 0x02,
+            PlayerInfoUpdateAction::GameMode(_) => 0x04,
+            PlayerInfoUpdateAction::Listed(_) => 0x08,
+            PlayerInfoUpdateAction::Ping(_) => 0x10,
+            PlayerInfoUpdateAction::DisplayName(_) => 0x20,
+        }
+    }
 }
 
 impl PacketReadable for PlayerInfoUpdates {
@@ -185,12 +222,14 @@ impl PacketReadable for PlayerInfoUpdates {
         let num_players_usize = num_players.get_value() as usize;
         let mut player_actions = Vec::with_capacity(num_players_usize);
         for _ in 0..num_players_usize {
+            // the uuid is sent once, followed by every action present in the mask
+            let player_uuid = types::UUID::read(stream);
+            let mut actions = Vec::new();
             for shift in 0..6 {
-                let is_action_present = (actions_mask_u8 & (1u8 >> shift)) != 0;
+                let is_action_present = (actions_mask_u8 & (1u8 << shift)) != 0;
                 if !is_action_present {
                     continue;
                 }
-                let player_uuid = types::UUID::read(stream);
                 let player_info_update_action = match shift {
                     0 => PlayerInfoUpdateAction::AddPlayer {
                         name: types::String::read(stream),
@@ -209,8 +248,9 @@ impl PacketReadable for PlayerInfoUpdates {
                         panic!()
                     }
                 };
-                player_actions.push((player_uuid, player_info_update_action));
+                actions.push(player_info_update_action);
             }
+            player_actions.push((player_uuid, actions));
         }
         Self {
             actions_mask: actions_mask,
@@ -223,27 +263,29 @@ impl PacketWritable for PlayerInfoUpdates {
     fn write(&self, stream: &mut impl std::io::Write) {
         self.actions_mask.write(stream);
         types::VarInt::from_i32(self.player_actions.len() as i32).write(stream);
-        for (player_uuid, player_info_update_action) in &self.player_actions {
+        for (player_uuid, actions) in &self.player_actions {
             player_uuid.write(stream);
-            match player_info_update_action {
-                PlayerInfoUpdateAction::AddPlayer { name, properties } => {
-                    name.write(stream);
-                    properties.write(stream);
-                }
-                PlayerInfoUpdateAction::InitializeChat { signature_data } => {
-                    signature_data.write(stream);
-                }
-                PlayerInfoUpdateAction::GameMode(var_int) => {
-                    var_int.write(stream);
-                }
-                PlayerInfoUpdateAction::Listed(boolean) => {
-                    boolean.write(stream);
-                }
-                PlayerInfoUpdateAction::Ping(var_int) => {
-                    var_int.write(stream);
-                }
-                PlayerInfoUpdateAction::DisplayName(optional) => {
-                    optional.write(stream);
+            for player_info_update_action in actions {
+                match player_info_update_action {
+                    PlayerInfoUpdateAction::AddPlayer { name, properties } => {
+                        name.write(stream);
+                        properties.write(stream);
+                    }
+                    PlayerInfoUpdateAction::InitializeChat { signature_data } => {
+                        signature_data.write(stream);
+                    }
+                    PlayerInfoUpdateAction::GameMode(var_int) => {
+                        var_int.write(stream);
+                    }
+                    PlayerInfoUpdateAction::Listed(boolean) => {
+                        boolean.write(stream);
+                    }
+                    PlayerInfoUpdateAction::Ping(var_int) => {
+                        var_int.write(stream);
+                    }
+                    PlayerInfoUpdateAction::DisplayName(optional) => {
+                        optional.write(stream);
+                    }
                 }
             }
         }
@@ -294,8 +336,8 @@ pub enum EntityMetadataValue {
     VarLong(types::VarLong),
     Float(types::Float),
     String(types::String),
-    TextComponent(types::DataNBT),
-    OptionalTextComponent(types::Optional<types::DataNBT>),
+    TextComponent(types::NetworkNBT),
+    OptionalTextComponent(types::Optional<types::NetworkNBT>),
     Slot(types::Slot),
     Boolean(types::Boolean),
     Rotations(types::FloatVec3),
@@ -305,7 +347,7 @@ pub enum EntityMetadataValue {
     OptionalUUID(types::Optional<types::UUID>),
     BlockState(types::VarInt),
     OptionalBlockState(types::VarInt), // 0 for absent (air is unrepresentable)
-    NBT(types::DataNBT),
+    NBT(types::NetworkNBT),
     Particle(types::ParticleEnum),
     Particles(types::Array<types::ParticleEnum>),
     VillagerData {
@@ -417,7 +459,7 @@ pub enum UpdateObjectivesAction {
         number_format: types::Optional<ScoreboardNumberFormat>,
     }
 }
-#[derive(PacketSerde, Debug, Clone)]
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Formatting {
     Black,
     DarkBlue,
@@ -443,14 +485,108 @@ pub enum Formatting {
     Reset,
 }
 
+// the colors of the team specific sidebars, in the order of their display positions
+const TEAM_SIDEBAR_COLORS: [Formatting; 16] = [
+    Formatting::Black,
+    Formatting::DarkBlue,
+    Formatting::DarkGreen,
+    Formatting::DarkAqua,
+    Formatting::DarkRed,
+    Formatting::DarkPurple,
+    Formatting::Gold,
+    Formatting::Gray,
+    Formatting::DarkGray,
+    Formatting::Blue,
+    Formatting::Green,
+    Formatting::Aqua,
+    Formatting::Red,
+    Formatting::LightPurple,
+    Formatting::Yellow,
+    Formatting::White,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreboardPosition {
+    List,
+    Sidebar,
+    BelowName,
+    TeamSidebar(Formatting), // shown to the members of the team with this color
+}
+
+impl PacketReadable for ScoreboardPosition {
+    fn read(stream: &mut impl std::io::Read) -> Self {
+        let position = types::VarInt::read(stream).get_value();
+        match position {
+            0 => Self::List,
+            1 => Self::Sidebar,
+            2 => Self::BelowName,
+            3..=18 => Self::TeamSidebar(TEAM_SIDEBAR_COLORS[(position - 3) as usize]),
+            val => panic!("Invalid scoreboard position: {}", val),
+        }
+    }
+}
+
+impl PacketWritable for ScoreboardPosition {
+    fn write(&self, stream: &mut impl std::io::Write) {
+        let position = match self {
+            Self::List => 0,
+            Self::Sidebar => 1,
+            Self::BelowName => 2,
+            Self::TeamSidebar(color) => {
+                let index = TEAM_SIDEBAR_COLORS
+                    .iter()
+                    .position(|c| c == color)
+                    .expect("only colors have a team sidebar");
+                3 + index as i32
+            }
+        };
+        types::VarInt::from(position).write(stream);
+    }
+}
+
+impl PacketSerde for ScoreboardPosition {}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+#[discriminant_type(types::String)]
+pub enum NameTagVisibility {
+    #[discriminant("always")]
+    Always,
+    #[discriminant("hideForOtherTeams")]
+    HideForOtherTeams,
+    #[discriminant("hideForOwnTeam")]
+    HideForOwnTeam,
+    #[discriminant("never")]
+    Never,
+}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+#[discriminant_type(types::String)]
+pub enum CollisionRule {
+    #[discriminant("always")]
+    Always,
+    #[discriminant("pushOtherTeams")]
+    PushOtherTeams,
+    #[discriminant("pushOwnTeam")]
+    PushOwnTeam,
+    #[discriminant("never")]
+    Never,
+}
+
+types::bit_flags! {
+    pub struct TeamFlags: types::Byte as i8 {
+        const ALLOW_FRIENDLY_FIRE = 0x01;
+        const SEE_INVISIBLE_TEAMMATES = 0x02;
+    }
+}
+
 #[derive(PacketSerde, Debug, Clone)]
 #[discriminant_type(types::Byte)]
 pub enum UpdateTeamsAction {
     CreateTeam {
         team_display_name: types::NetworkNBT,
-        friendly_flags: types::Byte, // Bit mask. 0x01: Allow friendly fire, 0x02: can see invisible players on same team.
-        name_tag_visibility: types::String,  // Enum: always, hideForOtherTeams, hideForOwnTeam, never.
-        collision_rule: types::String,  // Enum: always, pushOtherTeams, pushOwnTeam, never.
+        friendly_flags: TeamFlags,
+        name_tag_visibility: NameTagVisibility,
+        collision_rule: CollisionRule,
         team_color: Formatting,  // Used to color the name of players on the team.
         team_prefix: types::NetworkNBT,  // Displayed before the names of players that are part of this team.
         team_suffix: types::NetworkNBT,  // Displayed after the names of players that are part of this team.
@@ -459,9 +595,9 @@ pub enum UpdateTeamsAction {
     RemoveTeam,
     UpdateTeamInfo {
         team_display_name: types::NetworkNBT,
-        friendly_flags: types::Byte, // Bit mask. 0x01: Allow friendly fire, 0x02: can see invisible players on same team.
-        name_tag_visibility: types::String,  // Enum: always, hideForOtherTeams, hideForOwnTeam, never.
-        collision_rule: types::String,  // Enum: always, pushOtherTeams, pushOwnTeam, never.
+        friendly_flags: TeamFlags,
+        name_tag_visibility: NameTagVisibility,
+        collision_rule: CollisionRule,
         team_color: Formatting,  // Used to color the name of players on the team.
         team_prefix: types::NetworkNBT,  // Displayed before the names of players that are part of this team.
         team_suffix: types::NetworkNBT,  // Displayed after the names of players that are part of this team.
@@ -496,13 +632,692 @@ pub enum SoundCategoryAction {
     Voice,
 }
 
-// ###### Generic Clientbound Play Packet ######
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+#[discriminant_type(types::UnsignedByte)]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    // game modes are sent as floats by the game event packet
+    pub fn from_id(id: i32) -> Option<GameMode> {
+        match id {
+            0 => Some(Self::Survival),
+            1 => Some(Self::Creative),
+            2 => Some(Self::Adventure),
+            3 => Some(Self::Spectator),
+            _ => None,
+        }
+    }
+}
+
+// the previous game mode is sent as a signed byte, -1 when there is none
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+#[discriminant_type(types::Byte)]
+pub enum PreviousGameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+    Undefined = -1,
+}
+
+impl PreviousGameMode {
+    pub fn get_game_mode(&self) -> Option<GameMode> {
+        match self {
+            Self::Survival => Some(GameMode::Survival),
+            Self::Creative => Some(GameMode::Creative),
+            Self::Adventure => Some(GameMode::Adventure),
+            Self::Spectator => Some(GameMode::Spectator),
+            Self::Undefined => None,
+        }
+    }
+}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+#[discriminant_type(types::UnsignedByte)]
+pub enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+// the meaning of the value sent with each event is noted next to it
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq, Eq)]
+#[discriminant_type(types::UnsignedByte)]
+pub enum GameEventType {
+    NoRespawnBlockAvailable,
+    BeginRaining,
+    EndRaining,
+    ChangeGameMode,        // the new game mode id
+    WinGame,               // 0: respawn, 1: roll the credits and respawn
+    DemoEvent,             // 0: welcome screen, 101-104: movement controls, 105: screenshot key
+    ArrowHitPlayer,
+    RainLevelChange,       // 0 to 1
+    ThunderLevelChange,    // 0 to 1
+    PlayPufferfishStingSound,
+    PlayElderGuardianMobAppearance,
+    EnableRespawnScreen,   // 0: enable, 1: immediately respawn
+    LimitedCrafting,       // 0: disable, 1: enable
+    StartWaitingForLevelChunks,
+}
+
+types::bit_flags! {
+    pub struct PlayerAbilityFlags: types::Byte as i8 {
+        const INVULNERABLE = 0x01;
+        const FLYING = 0x02;
+        const ALLOW_FLYING = 0x04;
+        const CREATIVE_MODE = 0x08; // instant break
+    }
+}
+
+// a set flag makes the field relative to the current position or rotation
+types::bit_flags! {
+    pub struct TeleportFlags: types::Byte as i8 {
+        const RELATIVE_X = 0x01;
+        const RELATIVE_Y = 0x02;
+        const RELATIVE_Z = 0x04;
+        const RELATIVE_YAW = 0x08;
+        const RELATIVE_PITCH = 0x10;
+    }
+}
+
+types::bit_flags! {
+    pub struct RespawnDataKept: types::Byte as i8 {
+        const ATTRIBUTES = 0x01;
+        const METADATA = 0x02;
+    }
+}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq)]
+pub enum AdvancementFrameType {
+    Task,
+    Challenge,
+    Goal,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdvancementDisplay {
+    pub title: types::NetworkNBT,       // text component
+    pub description: types::NetworkNBT, // text component
+    pub icon: types::Slot,
+    pub frame_type: AdvancementFrameType,
+    pub flags: types::Int, // 0x01: has background texture, 0x02: show toast, 0x04: hidden
+    pub background_texture: Option<types::Identifier>, // only present if the flags contain 0x01
+    pub x: types::Float, // position of the advancement in its tab
+    pub y: types::Float,
+}
+
+impl PacketReadable for AdvancementDisplay {
+    fn read(stream: &mut impl std::io::Read) -> Self {
+        let title = types::NetworkNBT::read(stream);
+        let description = types::NetworkNBT::read(stream);
+        let icon = types::Slot::read(stream);
+        let frame_type = AdvancementFrameType::read(stream);
+        let flags = types::Int::read(stream);
+        let background_texture = if flags.get_value() & 0x01 != 0 {
+            Some(types::Identifier::read(stream))
+        } else {
+            None
+        };
+        Self {
+            title,
+            description,
+            icon,
+            frame_type,
+            flags,
+            background_texture,
+            x: types::Float::read(stream),
+            y: types::Float::read(stream),
+        }
+    }
+}
+
+impl PacketWritable for AdvancementDisplay {
+    fn write(&self, stream: &mut impl std::io::Write) {
+        self.title.write(stream);
+        self.description.write(stream);
+        self.icon.write(stream);
+        self.frame_type.write(stream);
+        // the background texture bit follows the presence of the texture
+        let mut flags = self.flags.get_value() & !0x01;
+        if self.background_texture.is_some() {
+            flags |= 0x01;
+        }
+        types::Int::new(flags).write(stream);
+        if let Some(background_texture) = &self.background_texture {
+            background_texture.write(stream);
+        }
+        self.x.write(stream);
+        self.y.write(stream);
+    }
+}
+
+impl PacketSerde for AdvancementDisplay {}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct Advancement {
+    pub parent_id: types::Optional<types::Identifier>,
+    pub display: types::Optional<AdvancementDisplay>,
+    // criteria names, the advancement is done when every group has at least one criterion done
+    pub requirements: types::Array<types::Array<types::String>>,
+    pub sends_telemetry_data: types::Boolean,
+}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct AdvancementMapping {
+    pub id: types::Identifier,
+    pub advancement: Advancement,
+}
 
 #[derive(PacketSerde, Debug, Clone)]
-pub struct PlaceholderPacket {
-    pub data: types::UnsizedByteArray,
+pub struct CriterionProgress {
+    pub criterion: types::String, // the name of the criterion, not namespaced
+    pub date_of_achieving: types::Optional<types::Long>, // milliseconds since the epoch, if achieved
 }
 
+#[derive(PacketSerde, Debug, Clone)]
+pub struct AdvancementProgressMapping {
+    pub id: types::Identifier,
+    pub criteria: types::Array<CriterionProgress>,
+}
+
+// min and max of the brigadier number parsers, present if the bits 0x01 and 0x02 of the flags are set
+#[derive(Debug, Clone)]
+pub struct CommandNumberRange<T: PacketSerde> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T: PacketSerde> PacketReadable for CommandNumberRange<T> {
+    fn read(stream: &mut impl std::io::Read) -> Self {
+        let flags = types::Byte::read(stream).get_u8();
+        let min = if flags & 0x01 != 0 { Some(T::read(stream)) } else { None };
+        let max = if flags & 0x02 != 0 { Some(T::read(stream)) } else { None };
+        Self { min, max }
+    }
+}
+
+impl<T: PacketSerde> PacketWritable for CommandNumberRange<T> {
+    fn write(&self, stream: &mut impl std::io::Write) {
+        let mut flags = 0u8;
+        if self.min.is_some() {
+            flags |= 0x01;
+        }
+        if self.max.is_some() {
+            flags |= 0x02;
+        }
+        types::Byte::from_u8(flags).write(stream);
+        if let Some(min) = &self.min {
+            min.write(stream);
+        }
+        if let Some(max) = &self.max {
+            max.write(stream);
+        }
+    }
+}
+
+impl<T: PacketSerde> PacketSerde for CommandNumberRange<T> {}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq)]
+pub enum CommandStringKind {
+    SingleWord,     // a single word
+    QuotablePhrase, // a word, or a phrase in double quotes
+    GreedyPhrase,   // the rest of the input
+}
+
+// the argument parsers, in the order of the minecraft:command_argument_type registry
+#[derive(PacketSerde, Debug, Clone)]
+pub enum CommandParser {
+    Bool,
+    Float(CommandNumberRange<types::Float>),
+    Double(CommandNumberRange<types::Double>),
+    Integer(CommandNumberRange<types::Int>),
+    Long(CommandNumberRange<types::Long>),
+    String(CommandStringKind),
+    Entity {
+        flags: types::Byte, // 0x01: only a single entity, 0x02: only players
+    },
+    GameProfile,
+    BlockPos,
+    ColumnPos,
+    Vec3,
+    Vec2,
+    BlockState,
+    BlockPredicate,
+    ItemStack,
+    ItemPredicate,
+    Color,
+    Component,
+    Style,
+    Message,
+    Nbt,
+    NbtTag,
+    NbtPath,
+    Objective,
+    ObjectiveCriteria,
+    Operation,
+    Particle,
+    Angle,
+    Rotation,
+    ScoreboardSlot,
+    ScoreHolder {
+        flags: types::Byte, // 0x01: allow multiple score holders
+    },
+    Swizzle,
+    Team,
+    ItemSlot,
+    ItemSlots,
+    ResourceLocation,
+    Function,
+    EntityAnchor,
+    IntRange,
+    FloatRange,
+    Dimension,
+    Gamemode,
+    Time {
+        min: types::Int, // the minimum duration in ticks
+    },
+    ResourceOrTag {
+        registry: types::Identifier,
+    },
+    ResourceOrTagKey {
+        registry: types::Identifier,
+    },
+    Resource {
+        registry: types::Identifier,
+    },
+    ResourceKey {
+        registry: types::Identifier,
+    },
+    TemplateMirror,
+    TemplateRotation,
+    Heightmap,
+    LootTable,
+    LootPredicate,
+    LootModifier,
+    Uuid,
+}
+
+impl CommandParser {
+    // the name of the parser in the minecraft:command_argument_type registry
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CommandParser::Bool => "brigadier:bool",
+            CommandParser::Float(_) => "brigadier:float",
+            CommandParser::Double(_) => "brigadier:double",
+            CommandParser::Integer(_) => "brigadier:integer",
+            CommandParser::Long(_) => "brigadier:long",
+            CommandParser::String(_) => "brigadier:string",
+            CommandParser::Entity { .. } => "minecraft:entity",
+            CommandParser::GameProfile => "minecraft:game_profile",
+            CommandParser::BlockPos => "minecraft:block_pos",
+            CommandParser::ColumnPos => "minecraft:column_pos",
+            CommandParser::Vec3 => "minecraft:vec3",
+            CommandParser::Vec2 => "minecraft:vec2",
+            CommandParser::BlockState => "minecraft:block_state",
+            CommandParser::BlockPredicate => "minecraft:block_predicate",
+            CommandParser::ItemStack => "minecraft:item_stack",
+            CommandParser::ItemPredicate => "minecraft:item_predicate",
+            CommandParser::Color => "minecraft:color",
+            CommandParser::Component => "minecraft:component",
+            CommandParser::Style => "minecraft:style",
+            CommandParser::Message => "minecraft:message",
+            CommandParser::Nbt => "minecraft:nbt_compound_tag",
+            CommandParser::NbtTag => "minecraft:nbt_tag",
+            CommandParser::NbtPath => "minecraft:nbt_path",
+            CommandParser::Objective => "minecraft:objective",
+            CommandParser::ObjectiveCriteria => "minecraft:objective_criteria",
+            CommandParser::Operation => "minecraft:operation",
+            CommandParser::Particle => "minecraft:particle",
+            CommandParser::Angle => "minecraft:angle",
+            CommandParser::Rotation => "minecraft:rotation",
+            CommandParser::ScoreboardSlot => "minecraft:scoreboard_slot",
+            CommandParser::ScoreHolder { .. } => "minecraft:score_holder",
+            CommandParser::Swizzle => "minecraft:swizzle",
+            CommandParser::Team => "minecraft:team",
+            CommandParser::ItemSlot => "minecraft:item_slot",
+            CommandParser::ItemSlots => "minecraft:item_slots",
+            CommandParser::ResourceLocation => "minecraft:resource_location",
+            CommandParser::Function => "minecraft:function",
+            CommandParser::EntityAnchor => "minecraft:entity_anchor",
+            CommandParser::IntRange => "minecraft:int_range",
+            CommandParser::FloatRange => "minecraft:float_range",
+            CommandParser::Dimension => "minecraft:dimension",
+            CommandParser::Gamemode => "minecraft:gamemode",
+            CommandParser::Time { .. } => "minecraft:time",
+            CommandParser::ResourceOrTag { .. } => "minecraft:resource_or_tag",
+            CommandParser::ResourceOrTagKey { .. } => "minecraft:resource_or_tag_key",
+            CommandParser::Resource { .. } => "minecraft:resource",
+            CommandParser::ResourceKey { .. } => "minecraft:resource_key",
+            CommandParser::TemplateMirror => "minecraft:template_mirror",
+            CommandParser::TemplateRotation => "minecraft:template_rotation",
+            CommandParser::Heightmap => "minecraft:heightmap",
+            CommandParser::LootTable => "minecraft:loot_table",
+            CommandParser::LootPredicate => "minecraft:loot_predicate",
+            CommandParser::LootModifier => "minecraft:loot_modifier",
+            CommandParser::Uuid => "minecraft:uuid",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandNodeKind {
+    Root,
+    Literal {
+        name: types::String,
+    },
+    Argument {
+        name: types::String,
+        parser: CommandParser,
+        suggestions: Option<types::Identifier>, // asks the server for suggestions, e.g. minecraft:ask_server
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandNode {
+    pub kind: CommandNodeKind,
+    pub is_executable: bool, // the command can be run if the input ends at this node
+    pub children: types::Array<types::VarInt>, // indices of the children in the node list
+    pub redirect: Option<types::VarInt>, // the node that parses the rest of the input, e.g. `execute run`
+}
+
+impl PacketReadable for CommandNode {
+    fn read(stream: &mut impl std::io::Read) -> Self {
+        // bits 0-1: node type, 0x04: executable, 0x08: has redirect, 0x10: has suggestions type
+        let flags = types::Byte::read(stream).get_u8();
+        let children = types::Array::<types::VarInt>::read(stream);
+        let redirect = if flags & 0x08 != 0 {
+            Some(types::VarInt::read(stream))
+        } else {
+            None
+        };
+        let kind = match flags & 0x03 {
+            0 => CommandNodeKind::Root,
+            1 => CommandNodeKind::Literal {
+                name: types::String::read(stream),
+            },
+            2 => {
+                let name = types::String::read(stream);
+                let parser = CommandParser::read(stream);
+                let suggestions = if flags & 0x10 != 0 {
+                    Some(types::Identifier::read(stream))
+                } else {
+                    None
+                };
+                CommandNodeKind::Argument {
+                    name,
+                    parser,
+                    suggestions,
+                }
+            }
+            node_type => panic!("Invalid command node type: {}", node_type),
+        };
+        Self {
+            kind,
+            is_executable: flags & 0x04 != 0,
+            children,
+            redirect,
+        }
+    }
+}
+
+impl PacketWritable for CommandNode {
+    fn write(&self, stream: &mut impl std::io::Write) {
+        let mut flags = match &self.kind {
+            CommandNodeKind::Root => 0x00,
+            CommandNodeKind::Literal { .. } => 0x01,
+            CommandNodeKind::Argument { suggestions, .. } => {
+                if suggestions.is_some() {
+                    0x12
+                } else {
+                    0x02
+                }
+            }
+        };
+        if self.is_executable {
+            flags |= 0x04;
+        }
+        if self.redirect.is_some() {
+            flags |= 0x08;
+        }
+        types::Byte::from_u8(flags).write(stream);
+        self.children.write(stream);
+        if let Some(redirect) = &self.redirect {
+            redirect.write(stream);
+        }
+        match &self.kind {
+            CommandNodeKind::Root => {}
+            CommandNodeKind::Literal { name } => name.write(stream),
+            CommandNodeKind::Argument {
+                name,
+                parser,
+                suggestions,
+            } => {
+                name.write(stream);
+                parser.write(stream);
+                if let Some(suggestions) = suggestions {
+                    suggestions.write(stream);
+                }
+            }
+        }
+    }
+}
+
+impl PacketSerde for CommandNode {}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq)]
+pub enum BossBarColor {
+    Pink,
+    Blue,
+    Red,
+    Green,
+    Yellow,
+    Purple,
+    White,
+}
+
+#[derive(PacketSerde, Debug, Clone, Copy, PartialEq)]
+pub enum BossBarDivision {
+    NoDivision,
+    SixNotches,
+    TenNotches,
+    TwelveNotches,
+    TwentyNotches,
+}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub enum BossBarAction {
+    Add {
+        title: types::NetworkNBT, // text component
+        health: types::Float,     // from 0 to 1
+        color: BossBarColor,
+        division: BossBarDivision,
+        flags: types::UnsignedByte, // 0x01: darken sky, 0x02: dragon bar (plays end music), 0x04: create fog
+    },
+    Remove,
+    UpdateHealth {
+        health: types::Float,
+    },
+    UpdateTitle {
+        title: types::NetworkNBT,
+    },
+    UpdateStyle {
+        color: BossBarColor,
+        division: BossBarDivision,
+    },
+    UpdateFlags {
+        flags: types::UnsignedByte,
+    },
+}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct AttributeModifierData {
+    pub id: types::Identifier,
+    pub amount: types::Double,
+    pub operation: types::Byte, // 0: add, 1: add a percentage of the base value, 2: multiply the total
+}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct AttributeProperty {
+    pub id: types::VarInt, // id in the minecraft:attribute registry
+    pub value: types::Double, // the base value
+    pub modifiers: types::Array<AttributeModifierData>,
+}
+
+// the items accepted in a slot of a recipe
+#[derive(PacketSerde, Debug, Clone)]
+pub struct Ingredient {
+    pub items: types::Array<types::Slot>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShapedRecipe {
+    pub group: types::String, // recipes with the same group are shown as one in the recipe book
+    pub category: types::VarInt, // 0: building, 1: redstone, 2: equipment, 3: misc
+    pub width: types::VarInt,
+    pub height: types::VarInt,
+    pub ingredients: Vec<Ingredient>, // width * height ingredients, row by row, without a length prefix
+    pub result: types::Slot,
+    pub show_notification: types::Boolean,
+}
+
+impl PacketReadable for ShapedRecipe {
+    fn read(stream: &mut impl std::io::Read) -> Self {
+        let group = types::String::read(stream);
+        let category = types::VarInt::read(stream);
+        let width = types::VarInt::read(stream);
+        let height = types::VarInt::read(stream);
+        let count = (width.get_value() * height.get_value()).max(0) as usize;
+        let mut ingredients = Vec::with_capacity(count);
+        for _ in 0..count {
+            ingredients.push(Ingredient::read(stream));
+        }
+        Self {
+            group,
+            category,
+            width,
+            height,
+            ingredients,
+            result: types::Slot::read(stream),
+            show_notification: types::Boolean::read(stream),
+        }
+    }
+}
+
+impl PacketWritable for ShapedRecipe {
+    fn write(&self, stream: &mut impl std::io::Write) {
+        self.group.write(stream);
+        self.category.write(stream);
+        self.width.write(stream);
+        self.height.write(stream);
+        for ingredient in &self.ingredients {
+            ingredient.write(stream);
+        }
+        self.result.write(stream);
+        self.show_notification.write(stream);
+    }
+}
+
+impl PacketSerde for ShapedRecipe {}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct CookingRecipe {
+    pub group: types::String,
+    pub category: types::VarInt, // 0: food, 1: blocks, 2: misc
+    pub ingredient: Ingredient,
+    pub result: types::Slot,
+    pub experience: types::Float,
+    pub cooking_time: types::VarInt, // in ticks
+}
+
+// the data of a recipe, in the order of the minecraft:recipe_serializer registry
+#[derive(PacketSerde, Debug, Clone)]
+pub enum RecipeData {
+    CraftingShaped(ShapedRecipe),
+    CraftingShapeless {
+        group: types::String,
+        category: types::VarInt, // 0: building, 1: redstone, 2: equipment, 3: misc
+        ingredients: types::Array<Ingredient>,
+        result: types::Slot,
+    },
+    // the special crafting recipes only send their category
+    ArmorDye {
+        category: types::VarInt,
+    },
+    BookCloning {
+        category: types::VarInt,
+    },
+    MapCloning {
+        category: types::VarInt,
+    },
+    MapExtending {
+        category: types::VarInt,
+    },
+    FireworkRocket {
+        category: types::VarInt,
+    },
+    FireworkStar {
+        category: types::VarInt,
+    },
+    FireworkStarFade {
+        category: types::VarInt,
+    },
+    TippedArrow {
+        category: types::VarInt,
+    },
+    BannerDuplicate {
+        category: types::VarInt,
+    },
+    ShieldDecoration {
+        category: types::VarInt,
+    },
+    ShulkerBoxColoring {
+        category: types::VarInt,
+    },
+    SuspiciousStew {
+        category: types::VarInt,
+    },
+    RepairItem {
+        category: types::VarInt,
+    },
+    Smelting(CookingRecipe),
+    Blasting(CookingRecipe),
+    Smoking(CookingRecipe),
+    CampfireCooking(CookingRecipe),
+    Stonecutting {
+        group: types::String,
+        ingredient: Ingredient,
+        result: types::Slot,
+    },
+    SmithingTransform {
+        template: Ingredient,
+        base: Ingredient,
+        addition: Ingredient,
+        result: types::Slot,
+    },
+    SmithingTrim {
+        template: Ingredient,
+        base: Ingredient,
+        addition: Ingredient,
+    },
+    DecoratedPot {
+        category: types::VarInt,
+    },
+}
+
+#[derive(PacketSerde, Debug, Clone)]
+pub struct Recipe {
+    pub id: types::Identifier,
+    pub data: RecipeData,
+}
+
+// ###### Generic Clientbound Play Packet ######
+
 #[derive(PacketSerde, GenericPacket, Debug, Clone)]
 pub enum ClientboundPlayPacket {
     BundleDelimiter,
@@ -510,12 +1325,12 @@ pub enum ClientboundPlayPacket {
         entity_id: types::VarInt, // A unique integer ID mostly used in the protocol to identify the entity.
         entity_uuid: types::UUID, // A unique identifier that is mostly used in persistence and places where the uniqueness matters more.
         entity_type: types::VarInt, // ID in the minecraft:entity_type registry.
-        position: types::FloatVec3, // entity x y z position encoded as float
+        position: types::DoubleVec3, // entity x y z position encoded as double
         pitch: types::Angle, // To get the real pitch, you must divide this by (256.0F / 360.0F)
         yaw: types::Angle,   // To get the real yaw, you must divide this by (256.0F / 360.0F)
         head_yaw: types::Angle, // Only used by living entities, where the head of the entity may differ from the general body rotation.
         data: types::VarInt, // Meaning dependent on the value of the Type field, see Object Data for details.
-        velocity: types::ShortVec3, // entity x y z velocity encoded as float
+        velocity: types::ShortVec3, // entity x y z velocity in units of 1/8000 of a block per tick
     },
     SpawnExperienceOrb {
         entity_id: types::VarInt, // A unique integer ID mostly used in the protocol to identify the entity.
@@ -554,11 +1369,10 @@ pub enum ClientboundPlayPacket {
     },
     BossBar {
         uuid: types::UUID,
-        action: types::VarInt,
-        action_data: types::UnsizedByteArray, // TODO: this data should be parsed based on the value of action
+        action: BossBarAction,
     },
     ChangeDifficulty {
-        difficulty: types::UnsignedByte, // 0: peaceful, 1: easy, 2: normal, 3: hard.
+        difficulty: Difficulty,
         is_locked: types::Boolean,
     },
     ChunkBatchFinished {
@@ -578,9 +1392,8 @@ pub enum ClientboundPlayPacket {
         matches: types::Array<CommandSuggestionMatch>,
     },
     Commands {
-        //reset: types::Array<GraphNode>, // TODO implelemnt GraphNode
-        //root_index: types::VarInt,
-        data: types::UnsizedByteArray,
+        nodes: types::Array<CommandNode>,
+        root_index: types::VarInt, // index of the root node in the node list
     },
     CloseContainer {
         window_id: types::UnsignedByte,
@@ -592,12 +1405,13 @@ pub enum ClientboundPlayPacket {
         carriedi_item: types::Slot, // Item being dragged with the mouse.
     },
     SetContainerProperty {
-        window_id: types::VarInt,
+        window_id: types::UnsignedByte,
         property: types::Short, // The meaning of the Property field depends on the type of the window.
         value: types::Short,
     },
     SetContainerSlot {
-        window_id: types::UnsignedByte,
+        // -1 with slot -1 sets the carried item, -2 updates any slot of the player inventory
+        window_id: types::Byte,
         state_id: types::VarInt,
         slot: types::Short, // The slot that should be updated.
         slot_data: types::Slot,
@@ -636,7 +1450,7 @@ pub enum ClientboundPlayPacket {
     },
     DisguisedChatMessage {
         message: types::NetworkNBT, // Text Component: This is used as the content parameter when formatting the message on the client.
-        chat_type: types::VarInt, // The type of chat in the minecraft:chat_type registry, defined by the Registry Data packet.
+        chat_type: types::IdOr<ChatType>, // The type of chat in the minecraft:chat_type registry, or an inline definition.
         sender_name: types::NetworkNBT, // This is used as the sender parameter when formatting the message on the client.
         target_name: types::Optional<types::NetworkNBT>,
     },
@@ -662,7 +1476,7 @@ pub enum ClientboundPlayPacket {
         chunk_z: types::Int, // Block coordinate divided by 16 (rounded down)
     },
     GameEvent {
-        event: types::UnsignedByte,
+        event: GameEventType,
         value: types::Float,
     },
     OpenHorseScreen {
@@ -709,19 +1523,12 @@ pub enum ClientboundPlayPacket {
         data: types::Int,
         disable_relative_volume: types::Boolean,
     },
-    // TODO: figure out why this is happening when recieving this packet:
-    // Error `Invalid enum discriminant: 244`
-    // Particle(
-    //        ID=0x29,
-    //        DATA=[00 3f ec 9c eb b6 73 9c e9 40 53 40 00 00 00 00 00 40 17 60 c2 f3 fb df 82 00 00 00 00 00 00 00 00 00 00 00 00 3e 19 99 9a 00 00 00 3c 01 f4 01]
-    // )
     Particle {
         long_distance: types::Boolean, // If true, particle distance increases from 256 to 65536.
         position: types::DoubleVec3,
         offset: types::FloatVec3, // This is added to the X position after being multiplied by random.nextGaussian().
         max_speed: types::Float,
         particle_count: types::Int, // The number of particles to create.
-        disable_relative_volume: types::Boolean,
         particle: types::ParticleEnum,
     },
     UpdateLight {
@@ -747,8 +1554,8 @@ pub enum ClientboundPlayPacket {
         dimension_type: types::VarInt,                                // The ID of the type of dimension in the `minecraft:dimension_type` registry, defined by the Registry Data packet.
         dimension_name: types::String,                                // Name of the dimension being spawned into.
         hashed_seed: types::Long,                                     // First 8 bytes of the SHA-256 hash of the world's seed. Used client side for biome noise
-        game_mode: types::UnsignedByte,                               // 0: Survival, 1: Creative, 2: Adventure, 3: Spectator.
-        previous_game_mode: types::Byte,                              // -1: Undefined (null), 0: Survival, 1: Creative, 2: Adventure, 3: Spectator. The previous game mode. Vanilla client uses this for the debug (F3 + N & F3 + F4) game mode switch. (More information needed)
+        game_mode: GameMode,
+        previous_game_mode: PreviousGameMode,                         // The previous game mode. Vanilla client uses this for the debug (F3 + N & F3 + F4) game mode switch.
         is_debug: types::Boolean,                                     // True if the world is a debug mode world; debug mode worlds cannot be modified and have predefined blocks.
         is_flat: types::Boolean,                                     // True if the world is a superflat world; flat worlds have different void fog and a horizon at y=0 instead of y=63.
         death_dimension_name_and_location: types::Optional<(types::Identifier, types::Position)>,     // Name and Location of the dimension the player died in.
@@ -820,7 +1627,7 @@ pub enum ClientboundPlayPacket {
         recipe: types::Identifier,
     },
     PlayerAbilities {
-        flags: types::UnsignedByte, // 0x01: Invulnerable, 0x02: Flying, 0x04: Allow Flying, 0x08: Creative Mode (Instant Break)	.
+        flags: PlayerAbilityFlags,
         flying_speed: types::Float, // 0.05 by default.
         field_of_view_modifier: types::Float, // Modifies the field of view, like a speed potion. A Notchian server will use the same value as the movement speed sent in the Update Attributes packet, which defaults to 0.1 for players.
     },
@@ -839,7 +1646,7 @@ pub enum ClientboundPlayPacket {
         unsigned_content: types::Optional<types::NetworkNBT>,
         filter_type: FilterType,
         // Chat Formatting
-        chat_type: types::VarInt,
+        chat_type: types::IdOr<ChatType>, // The type of chat in the minecraft:chat_type registry, or an inline definition.
         sender_name: types::NetworkNBT,
         target_name: types::Optional<types::NetworkNBT>,
     },
@@ -864,7 +1671,7 @@ pub enum ClientboundPlayPacket {
     },
     SynchronizePlayerPosition {
         location: types::Location,  // contains the location of a player
-        flags: types::Byte, // When the value of the this byte masked is zero the field is absolute, otherwise relative.
+        flags: TeleportFlags,
         teleport_id: types::VarInt, // VarInt: the client should respond with the same id
     },
     UpdateRecipeBook {
@@ -895,13 +1702,13 @@ pub enum ClientboundPlayPacket {
         dimension_type: types::VarInt,
         dimention_name: types::Identifier,
         hashed_seed: types::Long,
-        game_mode: types::UnsignedByte,
-        prev_game_mode: types::UnsignedByte,
+        game_mode: GameMode,
+        prev_game_mode: PreviousGameMode,
         is_debug: types::Boolean,
         is_flat: types::Boolean,
         death_location: types::Optional<(types::Identifier, types::Position)>,
         portal_cooldown: types::VarInt,
-        data_kept: types::Byte,
+        data_kept: RespawnDataKept,
     },
     SetHeadRotation {
         entity_id: types::VarInt,
@@ -959,12 +1766,7 @@ pub enum ClientboundPlayPacket {
         angle: types::Float,
     },
     DisplayObjective {
-        // The position of the scoreboard.
-        //   0: list,
-        //   1: sidebar,
-        //   2: below name,
-        //   3 - 18: team specific sidebar, indexed as 3 + team color.
-        position: types::VarInt,
+        position: ScoreboardPosition, // The position of the scoreboard.
         // The unique name for the scoreboard to be displayed.
         score_name: types::String,
     },
@@ -1077,7 +1879,9 @@ pub enum ClientboundPlayPacket {
     },
     TeleportEntity {
         entity_id: types::VarInt,
-        location: types::ShortVec3,
+        position: types::DoubleVec3,
+        yaw: types::Angle,
+        pitch: types::Angle,
         on_ground: types::Boolean,
     },
     SetTickingState {
@@ -1091,21 +1895,183 @@ pub enum ClientboundPlayPacket {
         host: types::String,
         port: types::VarInt
     },
-    /*UpdateAdvancements {
-        reset: types::Boolean,
-        advancement_mapping: types::Array<(types::Identifier, Advancement)>,
-        progress_mapping: types::Array<(types::Identifier, AdvancementProgress)>,
-    },*/
     UpdateAdvancements {
-        data: types::UnsizedByteArray
-    },
-    // TODO: implement the rest of the packets
-    PlaceholderPacket75(PlaceholderPacket),
-    PlaceholderPacket76(PlaceholderPacket),
-    PlaceholderPacket77(PlaceholderPacket),
-    PlaceholderPacket78(PlaceholderPacket),
-    PlaceholderPacket79(PlaceholderPacket),
-    PlaceholderPacket7A(PlaceholderPacket),
-    PlaceholderPacket7B(PlaceholderPacket),
-    PlaceholderPacket7C(PlaceholderPacket),
-}
\ No newline at end of file
+        reset: types::Boolean, // Whether to remove all advancements (and their progress) before applying the rest of the packet.
+        advancements: types::Array<AdvancementMapping>,
+        removed: types::Array<types::Identifier>,
+        progress: types::Array<AdvancementProgressMapping>,
+    },
+    UpdateAttributes {
+        entity_id: types::VarInt,
+        properties: types::Array<AttributeProperty>,
+    },
+    EntityEffect {
+        entity_id: types::VarInt,
+        effect_id: types::VarInt, // id in the minecraft:mob_effect registry
+        amplifier: types::VarInt, // the level of the effect minus one
+        duration: types::VarInt, // in ticks, -1 for infinite
+        flags: types::Byte, // 0x01: ambient, 0x02: show particles, 0x04: show icon, 0x08: blend (darkness)
+    },
+    UpdateRecipes {
+        recipes: types::Array<Recipe>,
+    },
+    UpdateTags {
+        tags: types::Array<super::configuration::RegistryTagMap>,
+    },
+    ProjectilePower {
+        entity_id: types::VarInt,
+        power: types::Double,
+    },
+    CustomReportDetails {
+        details: types::Array<super::configuration::CustomReportDetail>,
+    },
+    ServerLinks {
+        links: types::Array<super::configuration::ServerLink>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the id of every clientbound play packet of protocol 767 (1.21.1)
+    const PACKET_IDS: [(i32, &str); 124] = [
+        (0x00, "BundleDelimiter"),
+        (0x01, "SpawnEntity"),
+        (0x02, "SpawnExperienceOrb"),
+        (0x03, "EntityAnimation"),
+        (0x04, "AwardStatistics"),
+        (0x05, "AcknowledgeBlockChange"),
+        (0x06, "SetBlockDestroyStage"),
+        (0x07, "BlockEntityData"),
+        (0x08, "BlockAction"),
+        (0x09, "BlockUpdate"),
+        (0x0A, "BossBar"),
+        (0x0B, "ChangeDifficulty"),
+        (0x0C, "ChunkBatchFinished"),
+        (0x0D, "ChunkBatchStart"),
+        (0x0E, "ChunkBiomes"),
+        (0x0F, "ClearTitles"),
+        (0x10, "CommandSuggestionsResponse"),
+        (0x11, "Commands"),
+        (0x12, "CloseContainer"),
+        (0x13, "SetContainerContent"),
+        (0x14, "SetContainerProperty"),
+        (0x15, "SetContainerSlot"),
+        (0x16, "CookieRequest"),
+        (0x17, "SetCooldown"),
+        (0x18, "ChatSuggestions"),
+        (0x19, "ClientboundPluginMessage"),
+        (0x1A, "DamageEvent"),
+        (0x1B, "DebugSample"),
+        (0x1C, "DeleteMessage"),
+        (0x1D, "Disconnect"),
+        (0x1E, "DisguisedChatMessage"),
+        (0x1F, "EntityEvent"),
+        (0x20, "Explosion"),
+        (0x21, "UnloadChunk"),
+        (0x22, "GameEvent"),
+        (0x23, "OpenHorseScreen"),
+        (0x24, "HurtAnimation"),
+        (0x25, "InitializeWorldBorder"),
+        (0x26, "KeepAlive"),
+        (0x27, "ChunkDataAndUpdateLight"),
+        (0x28, "WorldEvent"),
+        (0x29, "Particle"),
+        (0x2A, "UpdateLight"),
+        (0x2B, "Login"),
+        (0x2C, "MapData"),
+        (0x2D, "MerchantOffers"),
+        (0x2E, "UpdateEntityPosition"),
+        (0x2F, "UpdateEntityPositionAndRotation"),
+        (0x30, "UpdateEntityRotation"),
+        (0x31, "MoveVehicle"),
+        (0x32, "OpenBook"),
+        (0x33, "OpenScreen"),
+        (0x34, "OpenSignEditor"),
+        (0x35, "Ping"),
+        (0x36, "PingResponse"),
+        (0x37, "PlaceGhostRecipe"),
+        (0x38, "PlayerAbilities"),
+        (0x39, "PlayerChatMessage"),
+        (0x3A, "EndCombat"),
+        (0x3B, "EnterCombat"),
+        (0x3C, "CombatDeath"),
+        (0x3D, "PlayerInfoRemove"),
+        (0x3E, "PlayerInfoUpdate"),
+        (0x3F, "LookAt"),
+        (0x40, "SynchronizePlayerPosition"),
+        (0x41, "UpdateRecipeBook"),
+        (0x42, "RemoveEntities"),
+        (0x43, "RemoveEntityEffect"),
+        (0x44, "ResetScore"),
+        (0x45, "RemoveResourcePack"),
+        (0x46, "AddResourcePack"),
+        (0x47, "Respawn"),
+        (0x48, "SetHeadRotation"),
+        (0x49, "UpdateSectionBlocks"),
+        (0x4A, "SelectAdvancementsTab"),
+        (0x4B, "ServerData"),
+        (0x4C, "SetActionBarText"),
+        (0x4D, "SetBorderCenter"),
+        (0x4E, "SetBorderLerpSize"),
+        (0x4F, "SetBorderSize"),
+        (0x50, "SetBorderWarningDelay"),
+        (0x51, "SetBorderWarningDistance"),
+        (0x52, "SetCamera"),
+        (0x53, "SetHeldItem"),
+        (0x54, "SetCenterChunk"),
+        (0x55, "SetRenderDistance"),
+        (0x56, "SetDefaultSpawnPosition"),
+        (0x57, "DisplayObjective"),
+        (0x58, "SetEntityMetadata"),
+        (0x59, "LinkEntities"),
+        (0x5A, "SetEntityVelocity"),
+        (0x5B, "SetEquipment"),
+        (0x5C, "SetExperience"),
+        (0x5D, "SetHealth"),
+        (0x5E, "UpdateObjectives"),
+        (0x5F, "SetPassengers"),
+        (0x60, "UpdateTeams"),
+        (0x61, "UpdateScore"),
+        (0x62, "SetSimulationDistance"),
+        (0x63, "SetSubtitleText"),
+        (0x64, "UpdateTime"),
+        (0x65, "SetTitleText"),
+        (0x66, "SetTitleAnimationTimes"),
+        (0x67, "EntitySoundEffect"),
+        (0x68, "SoundEffect"),
+        (0x69, "StartConfiguration"),
+        (0x6A, "StopSound"),
+        (0x6B, "StoreCookie"),
+        (0x6C, "SystemChatMessage"),
+        (0x6D, "SetTabListHeaderAndFooter"),
+        (0x6E, "TagQueryResponse"),
+        (0x6F, "PickupItem"),
+        (0x70, "TeleportEntity"),
+        (0x71, "SetTickingState"),
+        (0x72, "StepTick"),
+        (0x73, "Transfer"),
+        (0x74, "UpdateAdvancements"),
+        (0x75, "UpdateAttributes"),
+        (0x76, "EntityEffect"),
+        (0x77, "UpdateRecipes"),
+        (0x78, "UpdateTags"),
+        (0x79, "ProjectilePower"),
+        (0x7A, "CustomReportDetails"),
+        (0x7B, "ServerLinks"),
+    ];
+
+    #[test]
+    fn packet_ids_match_protocol() {
+        for (id, name) in PACKET_IDS {
+            assert_eq!(ClientboundPlayPacket::get_name_by_id(id), name, "packet id {:#04x}", id);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_packet_after_last_id() {
+        ClientboundPlayPacket::get_name_by_id(PACKET_IDS.len() as i32);
+    }
+}
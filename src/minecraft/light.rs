@@ -0,0 +1,325 @@
+// light data format based on https://minecraft.wiki/w/Java_Edition_protocol/Chunk_format#Light
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use super::chunk::{SECTION_HEIGHT, SECTION_WIDTH};
+use super::clientbound::ClientboundPlayPacket;
+use super::types;
+use crate::utils::logging::get_logger;
+
+pub const NIBBLE_ARRAY_SIZE: usize = 2048; // 4096 entries, 4 bits each
+pub const MAX_LIGHT_LEVEL: u8 = 15;
+
+// light levels of a 16x16x16 section, two entries per byte (lower 4 bits first)
+#[derive(Clone, PartialEq, Eq)]
+pub struct NibbleArray {
+    data: Vec<u8>,
+}
+
+impl Debug for NibbleArray {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format!("NibbleArray([{} bytes])", self.data.len()))
+    }
+}
+
+impl NibbleArray {
+    pub fn new(data: Vec<u8>) -> Self {
+        if data.len() != NIBBLE_ARRAY_SIZE {
+            panic!(
+                "NibbleArray: expected {} bytes but got {}",
+                NIBBLE_ARRAY_SIZE,
+                data.len()
+            );
+        }
+        Self { data }
+    }
+
+    pub fn new_filled(level: u8) -> Self {
+        let level = level.min(MAX_LIGHT_LEVEL);
+        Self::new(vec![level | (level << 4); NIBBLE_ARRAY_SIZE])
+    }
+
+    #[inline]
+    fn get_index(x: usize, y: usize, z: usize) -> usize {
+        (y << 8) | (z << 4) | x
+    }
+
+    // x, y and z are relative to the section (0-15)
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        let index = Self::get_index(x, y, z);
+        (self.data[index >> 1] >> ((index & 1) * 4)) & 0x0F
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, level: u8) {
+        let index = Self::get_index(x, y, z);
+        let shift = (index & 1) * 4;
+        let byte = &mut self.data[index >> 1];
+        *byte = (*byte & !(0x0F << shift)) | ((level.min(MAX_LIGHT_LEVEL)) << shift);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.iter().all(|b| *b == 0)
+    }
+
+    pub fn get_data(&self) -> &Vec<u8> {
+        &self.data
+    }
+}
+
+// sky and block light of a chunk column, there is one more section below and one more
+// section above the world, so there are two more light sections than chunk sections.
+// a section without light data (`None`) is not sent and keeps the light the client already has
+#[derive(Debug, Clone)]
+pub struct ChunkLight {
+    min_y: i32, // lowest block y of the dimension
+    sky_light: Vec<Option<NibbleArray>>,
+    block_light: Vec<Option<NibbleArray>>,
+}
+
+// checks a light mask bit without panicking on masks shorter than the section count
+#[inline]
+fn is_bit_set(mask: &types::BitSet, index: usize) -> bool {
+    index < mask.get_bit_count() && mask.get_bit(index)
+}
+
+impl ChunkLight {
+    // `min_y` and `height` are the lowest block y and the height of the dimension
+    pub fn new(min_y: i32, height: i32) -> Self {
+        let section_count = (height / SECTION_HEIGHT).max(0) as usize + 2;
+        Self {
+            min_y,
+            sky_light: vec![None; section_count],
+            block_light: vec![None; section_count],
+        }
+    }
+
+    pub fn get_section_count(&self) -> usize {
+        self.sky_light.len()
+    }
+
+    // `index` 0 is the section below the world
+    pub fn get_sky_light_section(&self, index: usize) -> Option<&NibbleArray> {
+        self.sky_light.get(index)?.as_ref()
+    }
+
+    pub fn get_block_light_section(&self, index: usize) -> Option<&NibbleArray> {
+        self.block_light.get(index)?.as_ref()
+    }
+
+    pub fn set_sky_light_section(&mut self, index: usize, light: Option<NibbleArray>) {
+        self.sky_light[index] = light;
+    }
+
+    pub fn set_block_light_section(&mut self, index: usize, light: Option<NibbleArray>) {
+        self.block_light[index] = light;
+    }
+
+    // applies the light sections of a packet, sections in `mask` are replaced by the next array,
+    // sections in `empty_mask` are set to zero and all other sections are left untouched
+    fn apply_sections(
+        sections: &mut [Option<NibbleArray>],
+        mask: &types::BitSet,
+        empty_mask: &types::BitSet,
+        arrays: &types::Array<types::ByteArray>,
+    ) {
+        let mut arrays = arrays.iter();
+        for (index, section) in sections.iter_mut().enumerate() {
+            if is_bit_set(mask, index) {
+                match arrays.next() {
+                    Some(array) if array.len() == NIBBLE_ARRAY_SIZE => {
+                        *section = Some(NibbleArray::new(array.to_vec()));
+                    }
+                    _ => get_logger().warn(format!(
+                        "ChunkLight: missing or invalid light array for section {}",
+                        index
+                    )),
+                }
+            } else if is_bit_set(empty_mask, index) {
+                *section = Some(NibbleArray::new_filled(0));
+            }
+        }
+    }
+
+    pub fn apply_sky_light(
+        &mut self,
+        mask: &types::BitSet,
+        empty_mask: &types::BitSet,
+        arrays: &types::Array<types::ByteArray>,
+    ) {
+        Self::apply_sections(&mut self.sky_light, mask, empty_mask, arrays);
+    }
+
+    pub fn apply_block_light(
+        &mut self,
+        mask: &types::BitSet,
+        empty_mask: &types::BitSet,
+        arrays: &types::Array<types::ByteArray>,
+    ) {
+        Self::apply_sections(&mut self.block_light, mask, empty_mask, arrays);
+    }
+
+    // light section index and section relative coordinates of a block,
+    // returns None if y is not covered by the light sections
+    fn locate(&self, x: i32, y: i32, z: i32) -> Option<(usize, usize, usize, usize)> {
+        // index 0 is the section below the world
+        let section = (y - self.min_y).div_euclid(SECTION_HEIGHT) + 1;
+        if section < 0 || section as usize >= self.sky_light.len() {
+            return None;
+        }
+        Some((
+            section as usize,
+            x.rem_euclid(SECTION_WIDTH) as usize,
+            y.rem_euclid(SECTION_HEIGHT) as usize,
+            z.rem_euclid(SECTION_WIDTH) as usize,
+        ))
+    }
+
+    // sky light level at the given block coordinates, None if the level is not known
+    pub fn sky_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        let (section, x, y, z) = self.locate(x, y, z)?;
+        Some(self.sky_light[section].as_ref()?.get(x, y, z))
+    }
+
+    pub fn block_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        let (section, x, y, z) = self.locate(x, y, z)?;
+        Some(self.block_light[section].as_ref()?.get(x, y, z))
+    }
+
+    // (mask, empty mask, arrays) of the given light sections, a section is in the empty mask
+    // when it only has zero light levels and is not sent as an array
+    fn encode_sections(
+        sections: &[Option<NibbleArray>],
+    ) -> (types::BitSet, types::BitSet, types::Array<types::ByteArray>) {
+        let mut mask = types::BitSet::empty();
+        let mut empty_mask = types::BitSet::empty();
+        let mut arrays: Vec<types::ByteArray> = Vec::new();
+        for section in sections {
+            match section {
+                Some(light) if !light.is_empty() => {
+                    mask.push_bit(true);
+                    empty_mask.push_bit(false);
+                    arrays.push(types::ByteArray::new(light.get_data().clone()));
+                }
+                Some(_) => {
+                    mask.push_bit(false);
+                    empty_mask.push_bit(true);
+                }
+                None => {
+                    mask.push_bit(false);
+                    empty_mask.push_bit(false);
+                }
+            }
+        }
+        (mask, empty_mask, types::Array::new(arrays))
+    }
+
+    // (sky light mask, empty sky light mask, sky light arrays)
+    pub fn encode_sky_light(
+        &self,
+    ) -> (types::BitSet, types::BitSet, types::Array<types::ByteArray>) {
+        Self::encode_sections(&self.sky_light)
+    }
+
+    // (block light mask, empty block light mask, block light arrays)
+    pub fn encode_block_light(
+        &self,
+    ) -> (types::BitSet, types::BitSet, types::Array<types::ByteArray>) {
+        Self::encode_sections(&self.block_light)
+    }
+}
+
+// light of every loaded chunk, keyed by chunk coordinates
+#[derive(Debug, Clone)]
+pub struct LightStorage {
+    min_y: i32,
+    height: i32,
+    chunks: HashMap<(i32, i32), ChunkLight>,
+}
+
+impl LightStorage {
+    pub fn new(min_y: i32, height: i32) -> Self {
+        Self {
+            min_y,
+            height,
+            chunks: HashMap::new(),
+        }
+    }
+
+    // the light of a chunk, an empty one is created if the chunk has no light yet
+    pub fn get_chunk_mut(&mut self, chunk_x: i32, chunk_z: i32) -> &mut ChunkLight {
+        let (min_y, height) = (self.min_y, self.height);
+        self.chunks
+            .entry((chunk_x, chunk_z))
+            .or_insert_with(|| ChunkLight::new(min_y, height))
+    }
+
+    // applies the light of `ChunkDataAndUpdateLight` and `UpdateLight` packets, other packets
+    // are ignored. `UpdateLight` is applied on top of the light that is already stored
+    pub fn apply_packet(&mut self, packet: &ClientboundPlayPacket) {
+        match packet {
+            ClientboundPlayPacket::ChunkDataAndUpdateLight {
+                chunk_x,
+                chunk_z,
+                sky_light_mask,
+                block_light_mask,
+                empty_sky_light_mask,
+                empty_block_light_mask,
+                sky_light_arrays,
+                block_light_arrays,
+                ..
+            } => {
+                // a new chunk replaces all the light of the previous one
+                self.unload_chunk(chunk_x.get_value(), chunk_z.get_value());
+                let light = self.get_chunk_mut(chunk_x.get_value(), chunk_z.get_value());
+                light.apply_sky_light(sky_light_mask, empty_sky_light_mask, sky_light_arrays);
+                light.apply_block_light(
+                    block_light_mask,
+                    empty_block_light_mask,
+                    block_light_arrays,
+                );
+            }
+            ClientboundPlayPacket::UpdateLight {
+                chunk_x,
+                chunk_z,
+                sky_light_mask,
+                block_light_mask,
+                empty_sky_light_mask,
+                empty_block_light_mask,
+                sky_light_arrays,
+                block_light_arrays,
+            } => {
+                let light = self.get_chunk_mut(chunk_x.get_value(), chunk_z.get_value());
+                light.apply_sky_light(sky_light_mask, empty_sky_light_mask, sky_light_arrays);
+                light.apply_block_light(
+                    block_light_mask,
+                    empty_block_light_mask,
+                    block_light_arrays,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn unload_chunk(&mut self, chunk_x: i32, chunk_z: i32) {
+        self.chunks.remove(&(chunk_x, chunk_z));
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    pub fn get_chunk(&self, chunk_x: i32, chunk_z: i32) -> Option<&ChunkLight> {
+        self.chunks.get(&(chunk_x, chunk_z))
+    }
+
+    // sky light level at the given block coordinates, None if the chunk or section is not loaded
+    pub fn sky_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        self.get_chunk(x.div_euclid(SECTION_WIDTH), z.div_euclid(SECTION_WIDTH))?
+            .sky_light(x, y, z)
+    }
+
+    pub fn block_light(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        self.get_chunk(x.div_euclid(SECTION_WIDTH), z.div_euclid(SECTION_WIDTH))?
+            .block_light(x, y, z)
+    }
+}
@@ -0,0 +1,5 @@
+// registries that map the numeric ids sent over the network to names
+pub mod blocks;
+pub mod builtin;
+pub mod dynamic;
+pub mod tags;
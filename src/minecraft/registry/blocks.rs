@@ -0,0 +1,640 @@
+// block state registry loaded from the `blocks.json` report of the vanilla data generator
+// (java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports)
+#[cfg(test)]
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde_json::Value as JsonValue;
+
+// tags used by `is_air` and `is_solid`, their blocks can be changed with `set_tag`
+pub const AIR_TAG: &str = "rusty_packets:air";
+pub const NON_SOLID_TAG: &str = "rusty_packets:non_solid";
+
+const DEFAULT_AIR_BLOCKS: [&str; 3] = ["minecraft:air", "minecraft:cave_air", "minecraft:void_air"];
+
+// blocks without a collision box, blocks missing from this list are solid until collision
+// shapes are loaded
+const DEFAULT_NON_SOLID_BLOCKS: [&str; 72] = [
+    // fluids
+    "minecraft:water",
+    "minecraft:lava",
+    "minecraft:bubble_column",
+    // plants
+    "minecraft:short_grass",
+    "minecraft:tall_grass",
+    "minecraft:fern",
+    "minecraft:large_fern",
+    "minecraft:dead_bush",
+    "minecraft:seagrass",
+    "minecraft:tall_seagrass",
+    "minecraft:kelp",
+    "minecraft:kelp_plant",
+    "minecraft:sugar_cane",
+    "minecraft:vine",
+    "minecraft:glow_lichen",
+    "minecraft:hanging_roots",
+    "minecraft:brown_mushroom",
+    "minecraft:red_mushroom",
+    "minecraft:crimson_fungus",
+    "minecraft:warped_fungus",
+    "minecraft:crimson_roots",
+    "minecraft:warped_roots",
+    "minecraft:nether_sprouts",
+    "minecraft:wheat",
+    "minecraft:carrots",
+    "minecraft:potatoes",
+    "minecraft:beetroots",
+    "minecraft:sweet_berry_bush",
+    // flowers
+    "minecraft:dandelion",
+    "minecraft:poppy",
+    "minecraft:blue_orchid",
+    "minecraft:allium",
+    "minecraft:azure_bluet",
+    "minecraft:red_tulip",
+    "minecraft:orange_tulip",
+    "minecraft:white_tulip",
+    "minecraft:pink_tulip",
+    "minecraft:oxeye_daisy",
+    "minecraft:cornflower",
+    "minecraft:lily_of_the_valley",
+    "minecraft:wither_rose",
+    "minecraft:torchflower",
+    "minecraft:sunflower",
+    "minecraft:lilac",
+    "minecraft:rose_bush",
+    "minecraft:peony",
+    // saplings
+    "minecraft:oak_sapling",
+    "minecraft:spruce_sapling",
+    "minecraft:birch_sapling",
+    "minecraft:jungle_sapling",
+    "minecraft:acacia_sapling",
+    "minecraft:dark_oak_sapling",
+    "minecraft:cherry_sapling",
+    "minecraft:mangrove_propagule",
+    // torches
+    "minecraft:torch",
+    "minecraft:wall_torch",
+    "minecraft:soul_torch",
+    "minecraft:soul_wall_torch",
+    "minecraft:redstone_torch",
+    "minecraft:redstone_wall_torch",
+    // redstone and rails
+    "minecraft:redstone_wire",
+    "minecraft:lever",
+    "minecraft:tripwire",
+    "minecraft:tripwire_hook",
+    "minecraft:rail",
+    "minecraft:powered_rail",
+    "minecraft:detector_rail",
+    "minecraft:activator_rail",
+    // others
+    "minecraft:cobweb",
+    "minecraft:fire",
+    "minecraft:soul_fire",
+    "minecraft:nether_portal",
+];
+
+// a box inside a block, (min x, min y, min z, max x, max y, max z) from 0 to 1
+pub type ShapeBox = [f64; 6];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockState {
+    id: i32,
+    name: String, // e.g. "minecraft:oak_stairs"
+    properties: BTreeMap<String, String>,
+    is_default: bool,
+}
+
+impl BlockState {
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_properties(&self) -> &BTreeMap<String, String> {
+        &self.properties
+    }
+
+    pub fn get_property(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(|v| v.as_str())
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.is_default
+    }
+
+    // formats the state the same way as commands, e.g. "minecraft:oak_stairs[facing=north,half=bottom]"
+    pub fn to_state_string(&self) -> String {
+        if self.properties.is_empty() {
+            return self.name.clone();
+        }
+        let properties: Vec<String> = self
+            .properties
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        format!("{}[{}]", self.name, properties.join(","))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BlockRegistry {
+    states: HashMap<i32, BlockState>,
+    blocks: HashMap<String, Vec<i32>>, // block name to all of its state ids
+    default_states: HashMap<String, i32>,
+    tags: HashMap<String, HashSet<String>>, // tag name to block names
+    collision_shapes: HashMap<i32, Vec<ShapeBox>>, // empty until collision shapes are loaded
+    hardness: HashMap<String, f32>,         // empty until block data is loaded
+    requires_tool: HashSet<String>,         // blocks that drop nothing when broken by hand
+}
+
+impl BlockRegistry {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: JsonValue = serde_json::from_str(json)?;
+        let blocks = match value.as_object() {
+            Some(o) => o,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "blocks report must be a json object",
+                ));
+            }
+        };
+
+        let mut registry = Self::default();
+        for (name, block) in blocks {
+            let states = match block.get("states").and_then(|v| v.as_array()) {
+                Some(v) => v,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("block {:?} does not have any states", name),
+                    ));
+                }
+            };
+            for state in states {
+                let id = match state.get("id").and_then(|v| v.as_i64()) {
+                    Some(v) => v as i32,
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("a state of block {:?} does not have an id", name),
+                        ));
+                    }
+                };
+                let mut properties = BTreeMap::new();
+                if let Some(map) = state.get("properties").and_then(|v| v.as_object()) {
+                    for (k, v) in map {
+                        properties.insert(k.clone(), v.as_str().unwrap_or_default().to_string());
+                    }
+                }
+                let is_default = state.get("default").and_then(|v| v.as_bool()) == Some(true);
+                registry.insert(BlockState {
+                    id,
+                    name: name.clone(),
+                    properties,
+                    is_default,
+                });
+            }
+        }
+
+        registry.set_tag(AIR_TAG, &DEFAULT_AIR_BLOCKS);
+        registry.set_tag(NON_SOLID_TAG, &DEFAULT_NON_SOLID_BLOCKS);
+        Ok(registry)
+    }
+
+    pub fn from_file(filepath: &str) -> Result<Self, Error> {
+        Self::from_json(&read_file(filepath)?)
+    }
+
+    // loads the collision boxes of every state from `blockCollisionShapes.json` of minecraft-data
+    // (https://github.com/PrismarineJS/minecraft-data), blocks without any box in any of their
+    // states are added to the non-solid tag
+    pub fn load_collision_shapes(&mut self, json: &str) -> Result<(), Error> {
+        let value: JsonValue = serde_json::from_str(json)?;
+        let invalid = |message: &str| Error::new(ErrorKind::InvalidData, message.to_string());
+        let blocks = value
+            .get("blocks")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| invalid("collision shapes must have a \"blocks\" object"))?;
+        let shapes = value
+            .get("shapes")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| invalid("collision shapes must have a \"shapes\" object"))?;
+        let get_shape = |id: &JsonValue| -> Result<Vec<ShapeBox>, Error> {
+            let id = id
+                .as_i64()
+                .ok_or_else(|| invalid("shape ids must be numbers"))?;
+            let boxes = shapes
+                .get(&id.to_string())
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| invalid(&format!("shape {} does not exist", id)))?;
+            boxes
+                .iter()
+                .map(|part| {
+                    let part: Vec<f64> = part
+                        .as_array()
+                        .map(|v| v.iter().filter_map(|v| v.as_f64()).collect())
+                        .unwrap_or_default();
+                    <ShapeBox>::try_from(part).map_err(|_| invalid("boxes must have 6 numbers"))
+                })
+                .collect()
+        };
+
+        let mut collision_shapes = HashMap::new();
+        for (name, shape_ids) in blocks {
+            let name = if name.contains(':') {
+                name.clone()
+            } else {
+                format!("minecraft:{}", name)
+            };
+            // blocks of another version are skipped
+            let mut state_ids = match self.blocks.get(&name) {
+                Some(state_ids) => state_ids.clone(),
+                None => continue,
+            };
+            state_ids.sort();
+            match shape_ids.as_array() {
+                // one shape per state, in state id order
+                Some(shape_ids) => {
+                    for (state, id) in state_ids.iter().zip(shape_ids) {
+                        collision_shapes.insert(*state, get_shape(id)?);
+                    }
+                }
+                None => {
+                    let shape = get_shape(shape_ids)?;
+                    for state in state_ids {
+                        collision_shapes.insert(state, shape.clone());
+                    }
+                }
+            }
+        }
+        self.collision_shapes = collision_shapes;
+
+        let non_solid: HashSet<String> = self
+            .blocks
+            .iter()
+            .filter(|(name, _)| !DEFAULT_AIR_BLOCKS.contains(&name.as_str()))
+            .filter(|(_, state_ids)| {
+                state_ids.iter().all(|id| {
+                    self.collision_shapes
+                        .get(id)
+                        .is_some_and(|shape| shape.is_empty())
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.tags
+            .entry(NON_SOLID_TAG.to_string())
+            .or_default()
+            .extend(non_solid);
+        Ok(())
+    }
+
+    pub fn load_collision_shapes_file(&mut self, filepath: &str) -> Result<(), Error> {
+        self.load_collision_shapes(&read_file(filepath)?)
+    }
+
+    // loads the hardness of every block from `blocks.json` of minecraft-data, blocks without
+    // a hardness can not be broken and get -1 like in the game, blocks with harvest tools only
+    // drop items when they are broken with one of them
+    pub fn load_hardness(&mut self, json: &str) -> Result<(), Error> {
+        let value: JsonValue = serde_json::from_str(json)?;
+        let blocks = match value.as_array() {
+            Some(v) => v,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "block data must be a json array",
+                ));
+            }
+        };
+        let mut hardness = HashMap::with_capacity(blocks.len());
+        let mut requires_tool = HashSet::new();
+        for block in blocks {
+            let name = match block.get("name").and_then(|v| v.as_str()) {
+                Some(name) if name.contains(':') => name.to_string(),
+                Some(name) => format!("minecraft:{}", name),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "a block does not have a name",
+                    ));
+                }
+            };
+            let value = match block.get("hardness").and_then(|v| v.as_f64()) {
+                Some(value) if value >= 0.0 => value as f32,
+                _ => -1.0,
+            };
+            if block.get("harvestTools").is_some_and(|v| v.is_object()) {
+                requires_tool.insert(name.clone());
+            }
+            hardness.insert(name, value);
+        }
+        self.hardness = hardness;
+        self.requires_tool = requires_tool;
+        Ok(())
+    }
+
+    pub fn load_hardness_file(&mut self, filepath: &str) -> Result<(), Error> {
+        self.load_hardness(&read_file(filepath)?)
+    }
+
+    // how long the block takes to break, -1 for unbreakable blocks, None when the block data
+    // was not loaded or the block is unknown
+    pub fn get_hardness(&self, name: &str) -> Option<f32> {
+        self.hardness.get(name).copied()
+    }
+
+    // whether the block drops nothing unless it is broken with the correct tool, the tools
+    // themselves come from the tags of the server
+    pub fn requires_correct_tool(&self, name: &str) -> bool {
+        self.requires_tool.contains(name)
+    }
+
+    pub fn has_collision_shapes(&self) -> bool {
+        !self.collision_shapes.is_empty()
+    }
+
+    // the collision boxes of the state, None when they were not loaded or the state is unknown
+    pub fn get_collision_shape(&self, id: i32) -> Option<&Vec<ShapeBox>> {
+        self.collision_shapes.get(&id)
+    }
+
+    fn insert(&mut self, state: BlockState) {
+        self.blocks
+            .entry(state.name.clone())
+            .or_default()
+            .push(state.id);
+        if state.is_default {
+            self.default_states.insert(state.name.clone(), state.id);
+        }
+        self.states.insert(state.id, state);
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn get_state(&self, id: i32) -> Option<&BlockState> {
+        self.states.get(&id)
+    }
+
+    pub fn get_block_name(&self, id: i32) -> Option<&str> {
+        self.get_state(id).map(|s| s.get_name())
+    }
+
+    pub fn get_state_ids(&self, name: &str) -> Option<&Vec<i32>> {
+        self.blocks.get(name)
+    }
+
+    pub fn get_default_state_id(&self, name: &str) -> Option<i32> {
+        self.default_states.get(name).copied()
+    }
+
+    // state id of a block with the given properties, missing properties use the default state
+    pub fn get_state_id(&self, name: &str, properties: &BTreeMap<String, String>) -> Option<i32> {
+        let default = self.get_state(self.get_default_state_id(name)?)?;
+        let mut wanted = default.properties.clone();
+        for (k, v) in properties {
+            if !wanted.contains_key(k) {
+                return None;
+            }
+            wanted.insert(k.clone(), v.clone());
+        }
+        self.blocks
+            .get(name)?
+            .iter()
+            .find(|id| self.states[*id].properties == wanted)
+            .copied()
+    }
+
+    // parses states formatted like "minecraft:oak_stairs[facing=north]" or "stone"
+    pub fn get_state_id_from_string(&self, state: &str) -> Option<i32> {
+        let (name, properties) = match state.split_once('[') {
+            Some((name, rest)) => (name, rest.strip_suffix(']')?),
+            None => (state, ""),
+        };
+        let name = if name.contains(':') {
+            name.to_string()
+        } else {
+            format!("minecraft:{}", name)
+        };
+        let mut map = BTreeMap::new();
+        for property in properties.split(',').filter(|p| !p.is_empty()) {
+            let (k, v) = property.split_once('=')?;
+            map.insert(k.trim().to_string(), v.trim().to_string());
+        }
+        self.get_state_id(&name, &map)
+    }
+
+    // human readable form of a state id, unknown ids are formatted as "#<id>"
+    pub fn describe(&self, id: i32) -> String {
+        match self.get_state(id) {
+            Some(state) => state.to_state_string(),
+            None => format!("#{}", id),
+        }
+    }
+
+    // replaces the blocks of a tag
+    pub fn set_tag(&mut self, tag: &str, blocks: &[&str]) {
+        self.tags.insert(
+            tag.to_string(),
+            blocks.iter().map(|b| b.to_string()).collect(),
+        );
+    }
+
+    pub fn get_tag(&self, tag: &str) -> Option<&HashSet<String>> {
+        self.tags.get(tag)
+    }
+
+    pub fn is_in_tag(&self, id: i32, tag: &str) -> bool {
+        match (self.get_block_name(id), self.tags.get(tag)) {
+            (Some(name), Some(blocks)) => blocks.contains(name),
+            _ => false,
+        }
+    }
+
+    pub fn is_air(&self, id: i32) -> bool {
+        self.is_in_tag(id, AIR_TAG)
+    }
+
+    // whether the state has a collision box, unknown state ids are considered solid
+    pub fn is_solid(&self, id: i32) -> bool {
+        if self.is_air(id) || self.is_in_tag(id, NON_SOLID_TAG) {
+            return false;
+        }
+        match self.get_collision_shape(id) {
+            Some(shape) => !shape.is_empty(),
+            None => true,
+        }
+    }
+}
+
+fn read_file(filepath: &str) -> Result<String, Error> {
+    let path = Path::new(filepath);
+    if !path.is_file() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("{:?} does not exist or is a directory.", filepath),
+        ));
+    }
+    let mut data = String::new();
+    File::open(path)?.read_to_string(&mut data)?;
+    Ok(data)
+}
+
+static BLOCK_REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+
+// sets the registry used by the client, it can only be set once
+pub fn set_block_registry(registry: BlockRegistry) -> bool {
+    BLOCK_REGISTRY.set(registry).is_ok()
+}
+
+// the registry of the running test, tests never set the registry of the client
+#[cfg(test)]
+thread_local! {
+    static TEST_BLOCK_REGISTRY: Cell<Option<&'static BlockRegistry>> = const { Cell::new(None) };
+}
+
+pub fn get_block_registry() -> Option<&'static BlockRegistry> {
+    #[cfg(test)]
+    if let Some(registry) = TEST_BLOCK_REGISTRY.get() {
+        return Some(registry);
+    }
+    BLOCK_REGISTRY.get()
+}
+
+// makes `get_block_registry` return the registry in the current test
+#[cfg(test)]
+pub fn set_test_block_registry(registry: BlockRegistry) -> &'static BlockRegistry {
+    let registry = Box::leak(Box::new(registry));
+    TEST_BLOCK_REGISTRY.set(Some(registry));
+    registry
+}
+
+// human readable form of a state id using the global registry
+pub fn describe_block_state(id: i32) -> String {
+    match get_block_registry() {
+        Some(registry) => registry.describe(id),
+        None => format!("#{}", id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCKS: &str = r#"{
+        "minecraft:air": {"states": [{"id": 0, "default": true}]},
+        "minecraft:stone": {"states": [{"id": 1, "default": true}]},
+        "minecraft:oak_sapling": {"states": [
+            {"id": 2, "default": true, "properties": {"stage": "0"}},
+            {"id": 3, "properties": {"stage": "1"}}
+        ]},
+        "minecraft:oak_slab": {"states": [
+            {"id": 5, "properties": {"type": "bottom"}},
+            {"id": 4, "default": true, "properties": {"type": "top"}}
+        ]},
+        "minecraft:water": {"states": [{"id": 6, "default": true}]}
+    }"#;
+
+    const SHAPES: &str = r#"{
+        "blocks": {"air": 0, "stone": 1, "oak_sapling": 0, "oak_slab": [2, 3], "water": 0},
+        "shapes": {
+            "0": [],
+            "1": [[0, 0, 0, 1, 1, 1]],
+            "2": [[0, 0.5, 0, 1, 1, 1]],
+            "3": [[0, 0, 0, 1, 0.5, 1]]
+        }
+    }"#;
+
+    #[test]
+    fn solid_blocks_without_collision_shapes() {
+        let registry = BlockRegistry::from_json(BLOCKS).unwrap();
+        assert!(!registry.has_collision_shapes());
+        assert!(!registry.is_solid(0));
+        assert!(registry.is_solid(1));
+        assert!(!registry.is_solid(2));
+        assert!(registry.is_solid(4));
+        assert!(!registry.is_solid(6));
+    }
+
+    #[test]
+    fn solid_blocks_from_collision_shapes() {
+        let mut registry = BlockRegistry::from_json(BLOCKS).unwrap();
+        registry.load_collision_shapes(SHAPES).unwrap();
+        assert!(registry.is_solid(1));
+        assert!(!registry.is_solid(2));
+        assert!(!registry.is_solid(3));
+        assert!(registry.is_air(0));
+
+        let non_solid = registry.get_tag(NON_SOLID_TAG).unwrap();
+        assert!(non_solid.contains("minecraft:oak_sapling"));
+        assert!(non_solid.contains("minecraft:water"));
+        assert!(!non_solid.contains("minecraft:air"));
+        assert!(!non_solid.contains("minecraft:stone"));
+
+        // per-state shapes follow the state ids, not the order of the report
+        assert_eq!(
+            registry.get_collision_shape(4),
+            Some(&vec![[0.0, 0.5, 0.0, 1.0, 1.0, 1.0]])
+        );
+        assert_eq!(
+            registry.get_collision_shape(5),
+            Some(&vec![[0.0, 0.0, 0.0, 1.0, 0.5, 1.0]])
+        );
+    }
+
+    #[test]
+    fn non_solid_tag_is_kept_with_collision_shapes() {
+        let mut registry = BlockRegistry::from_json(BLOCKS).unwrap();
+        registry.set_tag(NON_SOLID_TAG, &["minecraft:oak_slab"]);
+        registry.load_collision_shapes(SHAPES).unwrap();
+        let non_solid = registry.get_tag(NON_SOLID_TAG).unwrap();
+        assert!(non_solid.contains("minecraft:oak_slab"));
+        assert!(non_solid.contains("minecraft:oak_sapling"));
+        // the tag wins over the loaded shapes
+        assert!(!registry.is_solid(4));
+        assert!(!registry.is_solid(5));
+        assert!(registry.is_solid(1));
+    }
+
+    #[test]
+    fn hardness_from_block_data() {
+        let mut registry = BlockRegistry::from_json(BLOCKS).unwrap();
+        assert_eq!(registry.get_hardness("minecraft:stone"), None);
+        registry
+            .load_hardness(
+                r#"[
+                    {"name": "stone", "hardness": 1.5, "harvestTools": {"792": true}},
+                    {"name": "oak_sapling", "hardness": 0},
+                    {"name": "dripstone_block", "hardness": 1.5, "harvestTools": {"792": true}},
+                    {"name": "bedrock", "hardness": null},
+                    {"name": "barrier", "hardness": -1}
+                ]"#,
+            )
+            .unwrap();
+        assert_eq!(registry.get_hardness("minecraft:stone"), Some(1.5));
+        assert_eq!(registry.get_hardness("minecraft:oak_sapling"), Some(0.0));
+        assert_eq!(registry.get_hardness("minecraft:bedrock"), Some(-1.0));
+        assert_eq!(registry.get_hardness("minecraft:barrier"), Some(-1.0));
+        assert_eq!(registry.get_hardness("minecraft:water"), None);
+        assert!(registry.requires_correct_tool("minecraft:stone"));
+        assert!(registry.requires_correct_tool("minecraft:dripstone_block"));
+        assert!(!registry.requires_correct_tool("minecraft:oak_sapling"));
+    }
+}
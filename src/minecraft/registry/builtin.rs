@@ -0,0 +1,271 @@
+// built-in registries (items, entity types, sounds, ...) loaded from the `registries.json` report
+// of the vanilla data generator, these registries are not sent by the server
+#[cfg(test)]
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde_json::Value as JsonValue;
+
+pub const ITEM: &str = "minecraft:item";
+pub const ENTITY_TYPE: &str = "minecraft:entity_type";
+pub const SOUND_EVENT: &str = "minecraft:sound_event";
+pub const PARTICLE_TYPE: &str = "minecraft:particle_type";
+pub const MENU: &str = "minecraft:menu";
+pub const BLOCK: &str = "minecraft:block";
+pub const MOB_EFFECT: &str = "minecraft:mob_effect";
+pub const DATA_COMPONENT_TYPE: &str = "minecraft:data_component_type";
+
+#[derive(Debug, Clone, Default)]
+pub struct BuiltinRegistry {
+    default: Option<String>,
+    ids: HashMap<String, i32>,
+    names: HashMap<i32, String>,
+}
+
+impl BuiltinRegistry {
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        let mut registry = Self {
+            default: value
+                .get("default")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string()),
+            ..Default::default()
+        };
+        for (name, entry) in value.get("entries")?.as_object()? {
+            let id = entry.get("protocol_id")?.as_i64()? as i32;
+            registry.ids.insert(name.clone(), id);
+            registry.names.insert(id, name.clone());
+        }
+        Some(registry)
+    }
+
+    pub fn get_default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<i32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn get_name(&self, id: i32) -> Option<&str> {
+        self.names.get(&id).map(|v| v.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BuiltinRegistries {
+    registries: HashMap<String, BuiltinRegistry>,
+    max_stack_sizes: HashMap<i32, i32>, // item id -> default max_stack_size component
+}
+
+impl BuiltinRegistries {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: JsonValue = serde_json::from_str(json)?;
+        let object = match value.as_object() {
+            Some(o) => o,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "registries report must be a json object",
+                ));
+            }
+        };
+        let mut registries = HashMap::with_capacity(object.len());
+        for (name, registry) in object {
+            match BuiltinRegistry::from_json(registry) {
+                Some(r) => registries.insert(name.clone(), r),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("registry {:?} is invalid", name),
+                    ));
+                }
+            };
+        }
+        Ok(Self {
+            registries,
+            ..Default::default()
+        })
+    }
+
+    pub fn from_file(filepath: &str) -> Result<Self, Error> {
+        Self::from_json(&read_file(filepath)?)
+    }
+
+    // loads the default components of every item from the `items.json` report of the vanilla
+    // data generator, only max_stack_size is kept, requires the item registry to be loaded
+    pub fn load_item_components(&mut self, json: &str) -> Result<(), Error> {
+        let value: JsonValue = serde_json::from_str(json)?;
+        let items = match value.as_object() {
+            Some(o) => o,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "items report must be a json object",
+                ));
+            }
+        };
+        let mut max_stack_sizes = HashMap::with_capacity(items.len());
+        for (name, item) in items {
+            let size = item
+                .get("components")
+                .and_then(|v| v.get("minecraft:max_stack_size"))
+                .and_then(|v| v.as_i64());
+            if let Some(id) = self.get_id(ITEM, name)
+                && let Some(size) = size
+            {
+                max_stack_sizes.insert(id, size as i32);
+            }
+        }
+        self.max_stack_sizes = max_stack_sizes;
+        Ok(())
+    }
+
+    pub fn load_item_components_file(&mut self, filepath: &str) -> Result<(), Error> {
+        self.load_item_components(&read_file(filepath)?)
+    }
+
+    // the default max_stack_size component of an item, None if item components are not loaded
+    pub fn get_max_stack_size(&self, item_id: i32) -> Option<i32> {
+        self.max_stack_sizes.get(&item_id).copied()
+    }
+
+    pub fn get(&self, registry: &str) -> Option<&BuiltinRegistry> {
+        self.registries.get(registry)
+    }
+
+    pub fn get_id(&self, registry: &str, name: &str) -> Option<i32> {
+        self.get(registry)?.get_id(name)
+    }
+
+    pub fn get_name(&self, registry: &str, id: i32) -> Option<&str> {
+        self.get(registry)?.get_name(id)
+    }
+}
+
+fn read_file(filepath: &str) -> Result<String, Error> {
+    let path = Path::new(filepath);
+    if !path.is_file() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("{:?} does not exist or is a directory.", filepath),
+        ));
+    }
+    let mut data = String::new();
+    File::open(path)?.read_to_string(&mut data)?;
+    Ok(data)
+}
+
+static BUILTIN_REGISTRIES: OnceLock<BuiltinRegistries> = OnceLock::new();
+
+// sets the registries used by the client, they can only be set once
+pub fn set_builtin_registries(registries: BuiltinRegistries) -> bool {
+    BUILTIN_REGISTRIES.set(registries).is_ok()
+}
+
+// the registries of the running test, tests never set the registries of the client
+#[cfg(test)]
+thread_local! {
+    static TEST_BUILTIN_REGISTRIES: Cell<Option<&'static BuiltinRegistries>> =
+        const { Cell::new(None) };
+}
+
+pub fn get_builtin_registries() -> Option<&'static BuiltinRegistries> {
+    #[cfg(test)]
+    if let Some(registries) = TEST_BUILTIN_REGISTRIES.get() {
+        return Some(registries);
+    }
+    BUILTIN_REGISTRIES.get()
+}
+
+// makes `get_builtin_registries` return the registries in the current test
+#[cfg(test)]
+pub fn set_test_builtin_registries(registries: BuiltinRegistries) -> &'static BuiltinRegistries {
+    let registries = Box::leak(Box::new(registries));
+    TEST_BUILTIN_REGISTRIES.set(Some(registries));
+    registries
+}
+
+// name of an id in one of the global registries, None if they are not loaded
+pub fn get_name(registry: &str, id: i32) -> Option<&'static str> {
+    get_builtin_registries()?.get_name(registry, id)
+}
+
+pub fn get_id(registry: &str, name: &str) -> Option<i32> {
+    get_builtin_registries()?.get_id(registry, name)
+}
+
+pub fn get_item_name(id: i32) -> Option<&'static str> {
+    get_name(ITEM, id)
+}
+
+pub fn get_item_id(name: &str) -> Option<i32> {
+    get_id(ITEM, name)
+}
+
+pub fn get_item_max_stack_size(id: i32) -> Option<i32> {
+    get_builtin_registries()?.get_max_stack_size(id)
+}
+
+pub fn get_entity_type_name(id: i32) -> Option<&'static str> {
+    get_name(ENTITY_TYPE, id)
+}
+
+pub fn get_entity_type_id(name: &str) -> Option<i32> {
+    get_id(ENTITY_TYPE, name)
+}
+
+// human readable form of an id, e.g. "minecraft:diamond_sword", unknown ids are formatted as "#<id>"
+pub fn describe(registry: &str, id: i32) -> String {
+    match get_name(registry, id) {
+        Some(name) => name.to_string(),
+        None => format!("#{}", id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_stack_sizes_from_item_components() {
+        let mut registries = BuiltinRegistries::from_json(
+            r#"{"minecraft:item": {"default": "minecraft:air", "entries": {
+                "minecraft:air": {"protocol_id": 0},
+                "minecraft:red_bed": {"protocol_id": 1},
+                "minecraft:goat_horn": {"protocol_id": 2},
+                "minecraft:ender_pearl": {"protocol_id": 3}
+            }}}"#,
+        )
+        .unwrap();
+        assert_eq!(registries.get_max_stack_size(1), None);
+
+        registries
+            .load_item_components(
+                r#"{
+                    "minecraft:air": {"components": {}},
+                    "minecraft:red_bed": {"components": {"minecraft:max_stack_size": 1}},
+                    "minecraft:goat_horn": {"components": {"minecraft:max_stack_size": 1}},
+                    "minecraft:ender_pearl": {"components": {"minecraft:max_stack_size": 16}},
+                    "minecraft:unknown": {"components": {"minecraft:max_stack_size": 1}}
+                }"#,
+            )
+            .unwrap();
+        assert_eq!(registries.get_max_stack_size(0), None);
+        assert_eq!(registries.get_max_stack_size(1), Some(1));
+        assert_eq!(registries.get_max_stack_size(2), Some(1));
+        assert_eq!(registries.get_max_stack_size(3), Some(16));
+    }
+}
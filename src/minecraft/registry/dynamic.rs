@@ -0,0 +1,642 @@
+// registries sent by the server in the configuration state (dimension types, biomes, chat types, ...)
+// the index of an entry is its network id, entries of packs known to both sides may not have data
+use std::collections::HashMap;
+
+use serde_json::Value as JsonValue;
+
+use crate::minecraft::clientbound::ClientboundConfigurationPacket;
+use crate::minecraft::clientbound::configuration::{RegistryEntry, RegistryTagMap};
+use crate::minecraft::packet::PacketSerde;
+use crate::minecraft::types;
+
+use super::builtin;
+use super::tags::TagStore;
+
+pub const DIMENSION_TYPE: &str = "minecraft:dimension_type";
+pub const CHAT_TYPE: &str = "minecraft:chat_type";
+pub const DAMAGE_TYPE: &str = "minecraft:damage_type";
+pub const BIOME: &str = "minecraft:worldgen/biome";
+pub const WOLF_VARIANT: &str = "minecraft:wolf_variant";
+pub const PAINTING_VARIANT: &str = "minecraft:painting_variant";
+pub const TRIM_MATERIAL: &str = "minecraft:trim_material";
+pub const ENCHANTMENT: &str = "minecraft:enchantment";
+
+// NBT booleans are bytes
+fn get_bool(value: &JsonValue, key: &str) -> Option<bool> {
+    let v = value.get(key)?;
+    v.as_bool().or_else(|| v.as_i64().map(|v| v != 0))
+}
+
+fn get_i32(value: &JsonValue, key: &str) -> Option<i32> {
+    value.get(key)?.as_i64().map(|v| v as i32)
+}
+
+fn get_f32(value: &JsonValue, key: &str) -> Option<f32> {
+    value.get(key)?.as_f64().map(|v| v as f32)
+}
+
+fn get_string(value: &JsonValue, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(|v| v.to_string())
+}
+
+// a typed view of the entries of a registry
+pub trait RegistryValue: Sized {
+    const REGISTRY: &'static str;
+
+    // `data` is None if the server did not send the data of the entry
+    fn from_data(name: &str, data: Option<&JsonValue>) -> Option<Self>;
+}
+
+// registry values that can also be sent inline, as the value of an `IdOr<T>`
+pub trait InlineRegistryValue<T>: RegistryValue {
+    fn from_inline(value: &T, registries: &RegistryStore) -> Self;
+}
+
+#[derive(Debug, Clone)]
+pub struct DynamicRegistryEntry {
+    name: String,
+    data: Option<types::NetworkNBT>,
+}
+
+impl DynamicRegistryEntry {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_data(&self) -> Option<&types::NetworkNBT> {
+        self.data.as_ref()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DynamicRegistry {
+    entries: Vec<DynamicRegistryEntry>,
+    ids: HashMap<String, i32>,
+}
+
+impl DynamicRegistry {
+    pub fn from_entries(entries: &[RegistryEntry]) -> Self {
+        let mut registry = Self::default();
+        for entry in entries {
+            let name = entry.id.to_string();
+            registry
+                .ids
+                .insert(name.clone(), registry.entries.len() as i32);
+            registry.entries.push(DynamicRegistryEntry {
+                name,
+                data: match &entry.data {
+                    types::Optional::Some(data) => Some(data.clone()),
+                    types::Optional::None => None,
+                },
+            });
+        }
+        registry
+    }
+
+    pub fn get(&self, id: i32) -> Option<&DynamicRegistryEntry> {
+        self.entries.get(usize::try_from(id).ok()?)
+    }
+
+    pub fn get_id(&self, name: &str) -> Option<i32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn get_name(&self, id: i32) -> Option<&str> {
+        self.get(id).map(|e| e.get_name())
+    }
+
+    pub fn get_data(&self, id: i32) -> Option<&types::NetworkNBT> {
+        self.get(id)?.get_data()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DynamicRegistryEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RegistryStore {
+    registries: HashMap<String, DynamicRegistry>,
+    tags: TagStore,
+}
+
+impl RegistryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.registries.clear();
+        self.tags.clear();
+    }
+
+    // applies a configuration packet to the store, returns true if the packet was used
+    pub fn apply_packet(&mut self, packet: &ClientboundConfigurationPacket) -> bool {
+        match packet {
+            ClientboundConfigurationPacket::RegistryData {
+                registry_id,
+                entries,
+            } => {
+                self.registries.insert(
+                    registry_id.to_string(),
+                    DynamicRegistry::from_entries(entries),
+                );
+                true
+            }
+            ClientboundConfigurationPacket::UpdateTags { tags } => {
+                self.tags.update(tags);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_tags(&self) -> &TagStore {
+        &self.tags
+    }
+
+    // tags are also sent in the play state, e.g. after /reload
+    pub fn update_tags(&mut self, tags: &[RegistryTagMap]) {
+        self.tags.update(tags);
+    }
+
+    pub fn get(&self, registry: &str) -> Option<&DynamicRegistry> {
+        self.registries.get(registry)
+    }
+
+    pub fn get_registry_names(&self) -> Vec<&str> {
+        self.registries.keys().map(|k| k.as_str()).collect()
+    }
+
+    pub fn get_id(&self, registry: &str, name: &str) -> Option<i32> {
+        self.get(registry)?.get_id(name)
+    }
+
+    pub fn get_name(&self, registry: &str, id: i32) -> Option<&str> {
+        self.get(registry)?.get_name(id)
+    }
+
+    pub fn get_value<T: RegistryValue>(&self, id: i32) -> Option<T> {
+        let entry = self.get(T::REGISTRY)?.get(id)?;
+        let data = entry.get_data().map(|d| d.to_json());
+        T::from_data(entry.get_name(), data.as_ref())
+    }
+
+    pub fn get_value_by_name<T: RegistryValue>(&self, name: &str) -> Option<T> {
+        self.get_value(self.get_id(T::REGISTRY, name)?)
+    }
+
+    // every entry of a registry that could be read, in network id order
+    pub fn get_values<T: RegistryValue>(&self) -> Vec<(String, T)> {
+        let registry = match self.get(T::REGISTRY) {
+            Some(r) => r,
+            None => return Vec::new(),
+        };
+        (0..registry.len() as i32)
+            .filter_map(|id| Some((registry.get_name(id)?.to_string(), self.get_value(id)?)))
+            .collect()
+    }
+
+    // the value of an `IdOr<T>` field, either from the registry or the inline definition
+    pub fn resolve<T: InlineRegistryValue<P>, P: PacketSerde>(
+        &self,
+        value: &types::IdOr<P>,
+    ) -> Option<T> {
+        match value {
+            types::IdOr::Id(id) => self.get_value(*id),
+            types::IdOr::Value(value) => Some(T::from_inline(value, self)),
+        }
+    }
+
+    pub fn get_dimension_type(&self, id: i32) -> Option<DimensionType> {
+        self.get_value(id)
+    }
+
+    pub fn get_damage_type(&self, id: i32) -> Option<DamageType> {
+        self.get_value(id)
+    }
+
+    pub fn get_biome(&self, id: i32) -> Option<Biome> {
+        self.get_value(id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DimensionType {
+    min_y: i32,
+    height: i32,         // number of blocks from min_y to the top of the world
+    logical_height: i32, // the max height portals and chorus fruits can teleport to
+    has_skylight: bool,
+    has_ceiling: bool,
+    ambient_light: f32,
+}
+
+// dimension types of the vanilla data pack
+// (name, min_y, height, logical_height, has_skylight, has_ceiling, ambient_light)
+const VANILLA_DIMENSION_TYPES: [(&str, i32, i32, i32, bool, bool, f32); 4] = [
+    ("minecraft:overworld", -64, 384, 384, true, false, 0.0),
+    ("minecraft:overworld_caves", -64, 384, 384, true, true, 0.0),
+    ("minecraft:the_nether", 0, 256, 128, false, true, 0.1),
+    ("minecraft:the_end", 0, 256, 256, false, false, 0.0),
+];
+
+impl DimensionType {
+    pub fn get_min_y(&self) -> i32 {
+        self.min_y
+    }
+
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn get_logical_height(&self) -> i32 {
+        self.logical_height
+    }
+
+    pub fn has_skylight(&self) -> bool {
+        self.has_skylight
+    }
+
+    pub fn has_ceiling(&self) -> bool {
+        self.has_ceiling
+    }
+
+    pub fn get_ambient_light(&self) -> f32 {
+        self.ambient_light
+    }
+}
+
+impl RegistryValue for DimensionType {
+    const REGISTRY: &'static str = DIMENSION_TYPE;
+
+    fn from_data(name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        match data {
+            Some(data) => Some(Self {
+                min_y: get_i32(data, "min_y")?,
+                height: get_i32(data, "height")?,
+                logical_height: get_i32(data, "logical_height")?,
+                has_skylight: get_bool(data, "has_skylight").unwrap_or(true),
+                has_ceiling: get_bool(data, "has_ceiling").unwrap_or(false),
+                ambient_light: get_f32(data, "ambient_light").unwrap_or(0.0),
+            }),
+            None => VANILLA_DIMENSION_TYPES
+                .iter()
+                .find(|(n, ..)| *n == name)
+                .map(
+                    |(
+                        _,
+                        min_y,
+                        height,
+                        logical_height,
+                        has_skylight,
+                        has_ceiling,
+                        ambient_light,
+                    )| {
+                        Self {
+                            min_y: *min_y,
+                            height: *height,
+                            logical_height: *logical_height,
+                            has_skylight: *has_skylight,
+                            has_ceiling: *has_ceiling,
+                            ambient_light: *ambient_light,
+                        }
+                    },
+                ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageType {
+    message_id: String, // used in the death message, e.g. "death.attack.<message_id>"
+    scaling: String,    // "never", "always" or "when_caused_by_living_non_player"
+    exhaustion: f32,
+    effects: String, // "hurt", "thorns", "drowning", "burning", "poking" or "freezing"
+    death_message_type: String, // "default", "fall_variants" or "intentional_game_design"
+}
+
+impl DamageType {
+    pub fn get_message_id(&self) -> &str {
+        &self.message_id
+    }
+
+    pub fn get_scaling(&self) -> &str {
+        &self.scaling
+    }
+
+    pub fn get_exhaustion(&self) -> f32 {
+        self.exhaustion
+    }
+
+    pub fn get_effects(&self) -> &str {
+        &self.effects
+    }
+
+    pub fn get_death_message_type(&self) -> &str {
+        &self.death_message_type
+    }
+}
+
+impl RegistryValue for DamageType {
+    const REGISTRY: &'static str = DAMAGE_TYPE;
+
+    fn from_data(_name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        let data = data?;
+        Some(Self {
+            message_id: get_string(data, "message_id")?,
+            scaling: get_string(data, "scaling")?,
+            exhaustion: get_f32(data, "exhaustion")?,
+            effects: get_string(data, "effects").unwrap_or("hurt".to_string()),
+            death_message_type: get_string(data, "death_message_type")
+                .unwrap_or("default".to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Biome {
+    has_precipitation: bool,
+    temperature: f32,
+    downfall: f32,
+    fog_color: i32, // RGB
+    sky_color: i32,
+    water_color: i32,
+    water_fog_color: i32,
+    grass_color: Option<i32>, // computed from the temperature and downfall if not set
+    foliage_color: Option<i32>,
+}
+
+impl Biome {
+    pub fn has_precipitation(&self) -> bool {
+        self.has_precipitation
+    }
+
+    pub fn get_temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    pub fn get_downfall(&self) -> f32 {
+        self.downfall
+    }
+
+    pub fn get_fog_color(&self) -> i32 {
+        self.fog_color
+    }
+
+    pub fn get_sky_color(&self) -> i32 {
+        self.sky_color
+    }
+
+    pub fn get_water_color(&self) -> i32 {
+        self.water_color
+    }
+
+    pub fn get_water_fog_color(&self) -> i32 {
+        self.water_fog_color
+    }
+
+    pub fn get_grass_color(&self) -> Option<i32> {
+        self.grass_color
+    }
+
+    pub fn get_foliage_color(&self) -> Option<i32> {
+        self.foliage_color
+    }
+}
+
+impl RegistryValue for Biome {
+    const REGISTRY: &'static str = BIOME;
+
+    fn from_data(_name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        let data = data?;
+        let effects = data.get("effects")?;
+        Some(Self {
+            has_precipitation: get_bool(data, "has_precipitation")?,
+            temperature: get_f32(data, "temperature")?,
+            downfall: get_f32(data, "downfall")?,
+            fog_color: get_i32(effects, "fog_color")?,
+            sky_color: get_i32(effects, "sky_color")?,
+            water_color: get_i32(effects, "water_color")?,
+            water_fog_color: get_i32(effects, "water_fog_color")?,
+            grass_color: get_i32(effects, "grass_color"),
+            foliage_color: get_i32(effects, "foliage_color"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WolfVariant {
+    wild_texture: String,
+    tame_texture: String,
+    angry_texture: String,
+    biomes: Vec<String>, // biome names, or a single tag name starting with '#'
+    unnamed_biomes: Vec<i32>, // inline biome ids missing from the biome registry
+}
+
+impl WolfVariant {
+    pub fn get_wild_texture(&self) -> &str {
+        &self.wild_texture
+    }
+
+    pub fn get_tame_texture(&self) -> &str {
+        &self.tame_texture
+    }
+
+    pub fn get_angry_texture(&self) -> &str {
+        &self.angry_texture
+    }
+
+    pub fn get_biomes(&self) -> &Vec<String> {
+        &self.biomes
+    }
+
+    // the network ids of the biomes the variant spawns in, with tags resolved
+    pub fn get_biome_ids(&self, registries: &RegistryStore) -> Vec<i32> {
+        let mut ids = Vec::new();
+        for biome in &self.biomes {
+            match biome.strip_prefix('#') {
+                Some(tag) => {
+                    if let Some(tag) = registries.get_tags().get_tag(BIOME, tag) {
+                        ids.extend(tag.iter().copied());
+                    }
+                }
+                None => ids.extend(registries.get_id(BIOME, biome)),
+            }
+        }
+        ids.extend(self.unnamed_biomes.iter().copied());
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+impl RegistryValue for WolfVariant {
+    const REGISTRY: &'static str = WOLF_VARIANT;
+
+    fn from_data(_name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        let data = data?;
+        let biomes = match data.get("biomes") {
+            Some(JsonValue::String(biome)) => vec![biome.clone()],
+            Some(JsonValue::Array(biomes)) => biomes
+                .iter()
+                .filter_map(|b| b.as_str().map(|b| b.to_string()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        Some(Self {
+            wild_texture: get_string(data, "wild_texture")?,
+            tame_texture: get_string(data, "tame_texture")?,
+            angry_texture: get_string(data, "angry_texture")?,
+            biomes,
+            unnamed_biomes: Vec::new(),
+        })
+    }
+}
+
+impl InlineRegistryValue<types::WolfVariant> for WolfVariant {
+    fn from_inline(value: &types::WolfVariant, registries: &RegistryStore) -> Self {
+        let mut biomes = Vec::new();
+        let mut unnamed_biomes = Vec::new();
+        match &value.biomes {
+            types::IdSet::Tag(tag) => biomes.push(format!("#{}", tag)),
+            types::IdSet::Ids(ids) => {
+                for id in ids.iter() {
+                    match registries.get_name(BIOME, *id) {
+                        Some(name) => biomes.push(name.to_string()),
+                        None => unnamed_biomes.push(*id),
+                    }
+                }
+            }
+        }
+        Self {
+            wild_texture: value.wild_texture.to_string(),
+            tame_texture: value.tame_texture.to_string(),
+            angry_texture: value.angry_texture.to_string(),
+            biomes,
+            unnamed_biomes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaintingVariant {
+    asset_id: String,
+    width: i32, // in blocks
+    height: i32,
+    title: Option<JsonValue>, // text components
+    author: Option<JsonValue>,
+}
+
+impl PaintingVariant {
+    pub fn get_asset_id(&self) -> &str {
+        &self.asset_id
+    }
+
+    pub fn get_width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn get_title(&self) -> Option<&JsonValue> {
+        self.title.as_ref()
+    }
+
+    pub fn get_author(&self) -> Option<&JsonValue> {
+        self.author.as_ref()
+    }
+}
+
+impl RegistryValue for PaintingVariant {
+    const REGISTRY: &'static str = PAINTING_VARIANT;
+
+    fn from_data(_name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        let data = data?;
+        Some(Self {
+            asset_id: get_string(data, "asset_id")?,
+            width: get_i32(data, "width")?,
+            height: get_i32(data, "height")?,
+            title: data.get("title").cloned(),
+            author: data.get("author").cloned(),
+        })
+    }
+}
+
+impl InlineRegistryValue<types::PaintingVariant> for PaintingVariant {
+    fn from_inline(value: &types::PaintingVariant, _registries: &RegistryStore) -> Self {
+        let to_json = |v: &types::Optional<types::NetworkNBT>| match v {
+            types::Optional::Some(v) => Some(v.to_json()),
+            types::Optional::None => None,
+        };
+        Self {
+            asset_id: value.asset_id.to_string(),
+            width: value.width.get_value(),
+            height: value.height.get_value(),
+            title: to_json(&value.title),
+            author: to_json(&value.author),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimMaterial {
+    asset_name: String,
+    ingredient: String, // name of the item used in the smithing table
+    item_model_index: f32,
+    description: JsonValue, // text component
+}
+
+impl TrimMaterial {
+    pub fn get_asset_name(&self) -> &str {
+        &self.asset_name
+    }
+
+    pub fn get_ingredient(&self) -> &str {
+        &self.ingredient
+    }
+
+    pub fn get_item_model_index(&self) -> f32 {
+        self.item_model_index
+    }
+
+    pub fn get_description(&self) -> &JsonValue {
+        &self.description
+    }
+}
+
+impl RegistryValue for TrimMaterial {
+    const REGISTRY: &'static str = TRIM_MATERIAL;
+
+    fn from_data(_name: &str, data: Option<&JsonValue>) -> Option<Self> {
+        let data = data?;
+        Some(Self {
+            asset_name: get_string(data, "asset_name")?,
+            ingredient: get_string(data, "ingredient")?,
+            item_model_index: get_f32(data, "item_model_index")?,
+            description: data.get("description")?.clone(),
+        })
+    }
+}
+
+impl InlineRegistryValue<types::TrimMaterial> for TrimMaterial {
+    fn from_inline(value: &types::TrimMaterial, _registries: &RegistryStore) -> Self {
+        // the ingredient is sent as an id in the minecraft:item registry
+        let ingredient = value.ingredient.get_value();
+        Self {
+            asset_name: value.asset_name.get_value(),
+            ingredient: builtin::describe(builtin::ITEM, ingredient),
+            item_model_index: value.item_model_index.get_value(),
+            description: value.description.to_json(),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+// tags sent by the server in the configuration and play states, keyed by registry
+// (minecraft:block, minecraft:item, minecraft:fluid, minecraft:entity_type, minecraft:game_event, ...)
+use std::collections::{HashMap, HashSet};
+
+use crate::minecraft::clientbound::configuration::RegistryTagMap;
+use crate::minecraft::types;
+
+use super::builtin;
+
+pub const FLUID: &str = "minecraft:fluid";
+pub const GAME_EVENT: &str = "minecraft:game_event";
+
+// tag names are sent without the '#' prefix used in data packs and commands
+fn strip_prefix(tag: &str) -> &str {
+    tag.strip_prefix('#').unwrap_or(tag)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RegistryTags {
+    tags: HashMap<String, HashSet<i32>>,
+}
+
+impl RegistryTags {
+    pub fn get(&self, tag: &str) -> Option<&HashSet<i32>> {
+        self.tags.get(strip_prefix(tag))
+    }
+
+    pub fn get_tag_names(&self) -> Vec<&str> {
+        self.tags.keys().map(|k| k.as_str()).collect()
+    }
+
+    // names of the tags that contain the id
+    pub fn get_tags_of(&self, id: i32) -> Vec<&str> {
+        self.tags
+            .iter()
+            .filter(|(_, ids)| ids.contains(&id))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    pub fn contains(&self, tag: &str, id: i32) -> bool {
+        self.get(tag).is_some_and(|ids| ids.contains(&id))
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TagStore {
+    registries: HashMap<String, RegistryTags>,
+}
+
+impl TagStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.registries.clear();
+    }
+
+    // the tags of every registry in the packet replace the previous ones,
+    // registries that are not in the packet are left untouched
+    pub fn update(&mut self, tags: &[RegistryTagMap]) {
+        for registry in tags {
+            let tags = registry
+                .tagsmap
+                .iter()
+                .map(|tag| {
+                    let ids = tag.entries.iter().map(|id| id.get_value()).collect();
+                    (tag.name.to_string(), ids)
+                })
+                .collect();
+            self.registries
+                .insert(registry.registry.to_string(), RegistryTags { tags });
+        }
+    }
+
+    pub fn get(&self, registry: &str) -> Option<&RegistryTags> {
+        self.registries.get(registry)
+    }
+
+    pub fn get_registry_names(&self) -> Vec<&str> {
+        self.registries.keys().map(|k| k.as_str()).collect()
+    }
+
+    pub fn get_tag(&self, registry: &str, tag: &str) -> Option<&HashSet<i32>> {
+        self.get(registry)?.get(tag)
+    }
+
+    pub fn is_in_tag(&self, registry: &str, tag: &str, id: i32) -> bool {
+        self.get(registry)
+            .is_some_and(|tags| tags.contains(tag, id))
+    }
+
+    // the ids of an id set, None if it names a tag the server did not send
+    pub fn resolve(&self, registry: &str, value: &types::IdSet) -> Option<Vec<i32>> {
+        match value {
+            types::IdSet::Tag(tag) => {
+                let mut ids: Vec<i32> = self.get_tag(registry, tag)?.iter().copied().collect();
+                ids.sort_unstable();
+                Some(ids)
+            }
+            types::IdSet::Ids(ids) => Some(ids.clone()),
+        }
+    }
+
+    pub fn contains(&self, registry: &str, value: &types::IdSet, id: i32) -> bool {
+        match value {
+            types::IdSet::Tag(tag) => self.is_in_tag(registry, tag, id),
+            types::IdSet::Ids(ids) => ids.contains(&id),
+        }
+    }
+
+    // the blocks a predicate of the CanPlaceOn and CanBreak components accepts,
+    // None if it accepts every block
+    pub fn get_predicate_blocks(&self, predicate: &types::BlockPredicate) -> Option<Vec<i32>> {
+        match &predicate.blocks {
+            types::Optional::Some(blocks) => {
+                Some(self.resolve(builtin::BLOCK, blocks).unwrap_or_default())
+            }
+            types::Optional::None => None,
+        }
+    }
+}
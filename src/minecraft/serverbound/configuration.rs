@@ -18,6 +18,18 @@ pub enum ClientMainHand {
     Right,
 }
 
+types::bit_flags! {
+    pub struct SkinParts: types::UnsignedByte as u8 {
+        const CAPE = 0x01;
+        const JACKET = 0x02;
+        const LEFT_SLEEVE = 0x04;
+        const RIGHT_SLEEVE = 0x08;
+        const LEFT_PANTS_LEG = 0x10;
+        const RIGHT_PANTS_LEG = 0x20;
+        const HAT = 0x40;
+    }
+}
+
 #[derive(PacketSerde, Clone, Debug)]
 pub struct ServerboundKnownPack {
     pub namespace: types::String,
@@ -42,7 +54,7 @@ pub enum ServerboundConfigurationPacket {
         view_distance: types::Byte,             // Byte: for some reason this HAD TO BE SIGNED
         chat_mode: ClientChatMode,              // VarInt Enum: 0: enabled, 1: commands only, 2: hidden
         chat_colors: types::Boolean,            // Boolean: can the chat be colored?
-        skin_parts: types::UnsignedByte,        // Unsigned Byte: parts of skin that are visible (7 bit bitflag)
+        skin_parts: SkinParts,                  // Unsigned Byte: parts of skin that are visible (7 bit bitflag)
         main_hand: ClientMainHand,              // VarInt Enum: 0: left, 1: right
         text_filtering: types::Boolean,         // Boolean: Enables filtering of text on signs and written book titles
         allow_server_listings: types::Boolean,  // Boolean: Servers usually list online players, this option should let you not show up in that list
@@ -23,16 +23,27 @@ pub struct SessionPublicKey {
 
 #[derive(PacketSerde, Clone, Debug)]
 pub struct ChangedSlot {
-    pub slot_number: types::Byte,
+    pub slot_number: types::Short,
     pub slot: types::Slot, // New data for this slot, in the client's opinion.
 }
 
-#[derive(PacketSerde, Clone, Debug)]
+#[derive(PacketSerde, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum HandEnum {
     MainHand,
     OffHand,
 }
 
+#[derive(PacketSerde, Clone, Debug)]
+pub enum ClickContainerMode {
+    Pickup,     // left or right click
+    QuickMove,  // shift + click
+    Swap,       // number keys or F, the button is the hotbar slot (0-8) or 40 for the offhand
+    Clone,      // middle click, only in creative mode
+    Throw,      // Q or ctrl + Q
+    QuickCraft, // dragging the carried item over slots
+    PickupAll,  // double click
+}
+
 #[derive(PacketSerde, Clone, Debug)]
 pub enum InteractionEnum {
     Interact {
@@ -83,6 +94,86 @@ pub enum DifficultyEnum {
     Hard
 }
 
+#[derive(PacketSerde, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerCommandAction {
+    StartSneaking,
+    StopSneaking,
+    LeaveBed,
+    StartSprinting,
+    StopSprinting,
+    StartJumpWithHorse,
+    StopJumpWithHorse,
+    OpenVehicleInventory,
+    StartFlyingWithElytra,
+}
+
+#[derive(PacketSerde, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClientStatusAction {
+    PerformRespawn,
+    RequestStats,
+}
+
+#[derive(PacketSerde, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerActionStatus {
+    StartedDigging,
+    CancelledDigging,
+    FinishedDigging,
+    DropItemStack,
+    DropItem,
+    ShootArrowOrFinishEating, // also used when releasing any item that is being used
+    SwapItemInHand,
+}
+
+// the face of a block, sent as a Byte by the player action packet and as a VarInt by use item on
+#[derive(PacketSerde, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[discriminant_type(types::Byte)]
+pub enum BlockFace {
+    Bottom, // -Y
+    Top,    // +Y
+    North,  // -Z
+    South,  // +Z
+    West,   // -X
+    East,   // +X
+}
+
+impl BlockFace {
+    pub const ALL: [BlockFace; 6] = [
+        BlockFace::Bottom,
+        BlockFace::Top,
+        BlockFace::North,
+        BlockFace::South,
+        BlockFace::West,
+        BlockFace::East,
+    ];
+
+    pub fn get_id(&self) -> i32 {
+        *self as i32
+    }
+
+    // the direction the face points to
+    pub fn get_offset(&self) -> (i32, i32, i32) {
+        match self {
+            BlockFace::Bottom => (0, -1, 0),
+            BlockFace::Top => (0, 1, 0),
+            BlockFace::North => (0, 0, -1),
+            BlockFace::South => (0, 0, 1),
+            BlockFace::West => (-1, 0, 0),
+            BlockFace::East => (1, 0, 0),
+        }
+    }
+
+    pub fn get_opposite(&self) -> BlockFace {
+        match self {
+            BlockFace::Bottom => BlockFace::Top,
+            BlockFace::Top => BlockFace::Bottom,
+            BlockFace::North => BlockFace::South,
+            BlockFace::South => BlockFace::North,
+            BlockFace::West => BlockFace::East,
+            BlockFace::East => BlockFace::West,
+        }
+    }
+}
+
 #[derive(PacketSerde, Clone, Debug)]
 pub enum ChatModeEnum {
     Enabled,
@@ -136,15 +227,15 @@ pub enum ServerboundPlayPacket {
         chunk_per_tick: types::Float, // Desired chunks per tick.
     },
     ClientStatus {
-        action_id: types::VarInt, // 0: perform respawn, 1: request stats
+        action: ClientStatusAction,
     },
     ClientInformation {
         locale: types::String,                 // String: max 16 characters
         view_distance: types::Byte,            // Byte: for some reason this HAD TO BE SIGNED
         chat_mode: ChatModeEnum, // VarInt Enum: 0: enabled, 1: commands only, 2: hidden
         chat_colors: types::Boolean, // Boolean: can the chat be colored?
-        skin_parts: types::UnsignedByte, // Unsigned Byte: parts of skin that are visible (7 bit bitflag)
-        main_hand: types::VarInt,        // VarInt Enum: 0: left, 1: right
+        skin_parts: super::configuration::SkinParts, // Unsigned Byte: parts of skin that are visible (7 bit bitflag)
+        main_hand: super::configuration::ClientMainHand, // VarInt Enum: 0: left, 1: right
         text_filtering: types::Boolean, // Boolean: Enables filtering of text on signs and written book titles
         allow_server_listings: types::Boolean, // Boolean: Servers usually list online players, this option should let you not show up in that list
     },
@@ -163,10 +254,10 @@ pub enum ServerboundPlayPacket {
         // The ID of the window which was clicked. 0 for player inventory.
         // The server ignores any packets targeting a Window ID other than the current one, including ignoring 0 when any other window is open.
         window_id: types::Byte,
-        state_id: types::Byte, // The last received State ID from either a Set Container Slot or a Set Container Content packet.
-        slot: types::Short,    // The clicked slot number
-        button: types::Byte,   // The button used in the click
-        mode: types::VarInt,
+        state_id: types::VarInt, // The last received State ID from either a Set Container Slot or a Set Container Content packet.
+        slot: types::Short,      // The clicked slot number, -999 for clicks outside the window
+        button: types::Byte,     // The button used in the click, its meaning depends on the mode
+        mode: ClickContainerMode,
         changed_slots: types::Array<ChangedSlot>, // Maximum length for Notchian server is 128 slots.
         carried_item: types::Slot, // Item carried by the cursor. Has to be empty (item ID = -1) for drop mode, otherwise nothing will happen.
     },
@@ -254,17 +345,17 @@ pub enum ServerboundPlayPacket {
         make_all: types::Boolean,
     },
     PlayerAbilities {
-        flags: types::Byte, // Bit mask. 0x02: is flying.
+        flags: crate::minecraft::clientbound::play::PlayerAbilityFlags, // only FLYING is read by the server
     },
     PlayerAction {
-        status: types::VarInt,     // The action the player is taking against the block
-        location: types::Position, // Block position
-        face: types::Byte,         // The face being hit
+        status: PlayerActionStatus, // The action the player is taking against the block
+        location: types::Position,  // Block position
+        face: BlockFace,            // The face being hit
         sequence: types::VarInt,   // Block change sequence number
     },
     PlayerCommand {
         entity_id: types::VarInt,  // Player ID
-        action_id: types::VarInt,  // The ID of the action.
+        action: PlayerCommandAction,
         jump_boost: types::VarInt, // Only used by the “start jump with horse” action, in which case it ranges from 0 to 100. In all other cases it is 0.
     },
     PlayerInput {
@@ -359,7 +450,7 @@ pub enum ServerboundPlayPacket {
     UseItemOn {
         hand: HandEnum, // The hand from which the block is placed; 0: main hand, 1: off hand.
         location: types::Position,
-        face: types::VarInt,
+        face: types::VarInt, // the id of a `BlockFace`
         // The position of the crosshair on the block, from 0 to 1 increasing from west to east, bottom to top and north to south.
         cursor_position: types::FloatVec3,
         inside_block: types::Boolean, // True when the player's head is inside of a block.
@@ -372,3 +463,83 @@ pub enum ServerboundPlayPacket {
         pitch: types::Float, // Player head rotation along the X-Axis.
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the id of every serverbound play packet of protocol 767 (1.21.1)
+    const PACKET_IDS: [(i32, &str); 58] = [
+        (0x00, "ConfirmTeleportation"),
+        (0x01, "QueryBlockEntityTag"),
+        (0x02, "ChangeDifficulty"),
+        (0x03, "AcknowledgeMessage"),
+        (0x04, "ChatCommand"),
+        (0x05, "SignedChatCommand"),
+        (0x06, "ChatMessage"),
+        (0x07, "PlayerSession"),
+        (0x08, "ChunkBatchReceived"),
+        (0x09, "ClientStatus"),
+        (0x0A, "ClientInformation"),
+        (0x0B, "CommandSuggestionsRequest"),
+        (0x0C, "AcknowledgeConfiguration"),
+        (0x0D, "ClickContainerButton"),
+        (0x0E, "ClickContainer"),
+        (0x0F, "CloseContainer"),
+        (0x10, "ChangeContainerSlotState"),
+        (0x11, "CookieResponse"),
+        (0x12, "ServerboundPluginMessage"),
+        (0x13, "DebugSampleSubscription"),
+        (0x14, "EditBook"),
+        (0x15, "QueryEntityTag"),
+        (0x16, "Interact"),
+        (0x17, "JigsawGenerate"),
+        (0x18, "KeepAlive"),
+        (0x19, "LockDifficulty"),
+        (0x1A, "SetPlayerPosition"),
+        (0x1B, "SetPlayerPositionAndRotation"),
+        (0x1C, "SetPlayerRotation"),
+        (0x1D, "SetPlayerOnGround"),
+        (0x1E, "MoveVehicle"),
+        (0x1F, "PaddleBoat"),
+        (0x20, "PickItem"),
+        (0x21, "PingRequest"),
+        (0x22, "PlaceRecipe"),
+        (0x23, "PlayerAbilities"),
+        (0x24, "PlayerAction"),
+        (0x25, "PlayerCommand"),
+        (0x26, "PlayerInput"),
+        (0x27, "Pong"),
+        (0x28, "ChangeRecipeBookSettings"),
+        (0x29, "SetSeenRecipe"),
+        (0x2A, "RenameItem"),
+        (0x2B, "ResourcePackResponse"),
+        (0x2C, "SeenAdvancements"),
+        (0x2D, "SelectTrade"),
+        (0x2E, "SetBeaconEffect"),
+        (0x2F, "SetHeldItem"),
+        (0x30, "ProgramCommandBlock"),
+        (0x31, "ProgramCommandBlockMinecart"),
+        (0x32, "SetCreativeModeSlot"),
+        (0x33, "ProgramJigsawBlock"),
+        (0x34, "ProgramStructureBlock"),
+        (0x35, "UpdateSign"),
+        (0x36, "SwingArm"),
+        (0x37, "TeleportToEntity"),
+        (0x38, "UseItemOn"),
+        (0x39, "UseItem"),
+    ];
+
+    #[test]
+    fn packet_ids_match_protocol() {
+        for (id, name) in PACKET_IDS {
+            assert_eq!(ServerboundPlayPacket::get_name_by_id(id), name, "packet id {:#04x}", id);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_packet_after_last_id() {
+        ServerboundPlayPacket::get_name_by_id(PACKET_IDS.len() as i32);
+    }
+}
@@ -1,6 +1,7 @@
 use cesu8;
 use flate2::bufread::GzDecoder;
 use regex::Regex;
+use serde_json::{Map, Value as JsonValue};
 
 use std::f32::consts::PI;
 use std::fmt::Debug;
@@ -9,6 +10,7 @@ use std::ops::Deref;
 use packet_serde_derive::PacketSerde;
 
 use super::packet::{PacketReadable, PacketSerde, PacketWritable};
+use super::registry::builtin::get_item_name;
 use crate::utils::{logging::get_logger, read_bytes, read_n_bytes};
 
 use std::{
@@ -1052,46 +1054,18 @@ impl Position {
 
     #[inline]
     pub fn from_u64(value: u64) -> Self {
-        let x = value.wrapping_shr(38) & 0x3FFFFFF;
-        let y = value & 0xFFF;
-        let z = value.wrapping_shr(12) & 0x3FFFFFF;
-
-        Self::new(
-            if x > 0x1FFFFFF {
-                -(x as i32 - 0x1FFFFFF)
-            } else {
-                x as i32
-            }, // handle two's complement
-            if y > 0x7FF {
-                -(y as i16 - 0x7FF)
-            } else {
-                y as i16
-            }, // handle two's complement
-            if z > 0x1FFFFFF {
-                -(z as i32 - 0x1FFFFFF)
-            } else {
-                z as i32
-            }, // handle two's complement
-        )
+        // shift each field to the top of the long and back, so the sign is extended
+        let value = value as i64;
+        let x = value >> 38;
+        let y = (value << 52) >> 52;
+        let z = (value << 26) >> 38;
+        Self::new(x as i32, y as i16, z as i32)
     }
 
     pub fn to_u64(&self) -> u64 {
-        let x: u32 = if self.x >= 0 {
-            self.x.unsigned_abs()
-        } else {
-            self.x.unsigned_abs() + 0x1FFFFFF
-        };
-        let y: u16 = if self.y >= 0 {
-            self.y.unsigned_abs()
-        } else {
-            self.y.unsigned_abs() + 0x7FF
-        };
-        let z: u32 = if self.z >= 0 {
-            self.z.unsigned_abs()
-        } else {
-            self.z.unsigned_abs() + 0x1FFFFFF
-        };
-        (((x as u64) & 0x3FFFFFF) << 38) | ((y as u64) & 0xFFF) | (((z as u64) & 0x3FFFFFF) << 12)
+        (((self.x as u64) & 0x3FFFFFF) << 38)
+            | ((self.y as u64) & 0xFFF)
+            | (((self.z as u64) & 0x3FFFFFF) << 12)
     }
 
     pub fn get_x(&self) -> i32 {
@@ -2024,6 +1998,132 @@ impl_from_tuple!(D0, D1, D2, D3; S0, S1, S2, S3; 0, 1, 2, 3);
 impl_from_tuple!(D0, D1, D2, D3, D4; S0, S1, S2, S3, S4; 0, 1, 2, 3, 4);
 impl_from_tuple!(D0, D1, D2, D3, D4, D5; S0, S1, S2, S3, S4, S5; 0, 1, 2, 3, 4, 5);
 
+// bit masks sent as a Byte or UnsignedByte, each flag becomes an associated constant:
+//
+//   bit_flags! {
+//       pub struct TeamFlags: types::Byte as i8 {
+//           const ALLOW_FRIENDLY_FIRE = 0x01;
+//       }
+//   }
+//
+// unknown bits are kept so that a mask can be written back unchanged
+macro_rules! bit_flags {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: $repr:ty as $bits:ty {
+            $(
+                $(#[$flag_meta:meta])*
+                const $flag:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        $vis struct $name {
+            bits: $bits,
+        }
+
+        impl $name {
+            $(
+                $(#[$flag_meta])*
+                pub const $flag: Self = Self { bits: $value };
+            )*
+
+            pub const fn empty() -> Self {
+                Self { bits: 0 }
+            }
+
+            pub const fn all() -> Self {
+                Self { bits: 0 $( | $value )* }
+            }
+
+            pub const fn from_bits(bits: $bits) -> Self {
+                Self { bits }
+            }
+
+            pub const fn get_bits(&self) -> $bits {
+                self.bits
+            }
+
+            pub const fn is_empty(&self) -> bool {
+                self.bits == 0
+            }
+
+            pub const fn contains(&self, other: Self) -> bool {
+                self.bits & other.bits == other.bits
+            }
+
+            pub fn insert(&mut self, other: Self) {
+                self.bits |= other.bits;
+            }
+
+            pub fn remove(&mut self, other: Self) {
+                self.bits &= !other.bits;
+            }
+
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                Self { bits: self.bits | other.bits }
+            }
+        }
+
+        impl std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: Self) {
+                self.insert(other);
+            }
+        }
+
+        impl std::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, other: Self) -> Self {
+                Self { bits: self.bits & other.bits }
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let mut names: Vec<&str> = Vec::new();
+                $(
+                    if self.contains(Self::$flag) && Self::$flag.bits != 0 {
+                        names.push(stringify!($flag));
+                    }
+                )*
+                write!(f, "{}({})", stringify!($name), names.join(" | "))
+            }
+        }
+
+        impl $crate::minecraft::packet::PacketReadable for $name {
+            fn read(stream: &mut impl std::io::Read) -> Self {
+                Self {
+                    bits: <$repr as $crate::minecraft::packet::PacketReadable>::read(stream).into(),
+                }
+            }
+        }
+
+        impl $crate::minecraft::packet::PacketWritable for $name {
+            fn write(&self, stream: &mut impl std::io::Write) {
+                <$repr as $crate::minecraft::packet::PacketWritable>::write(&<$repr>::from(self.bits), stream);
+            }
+        }
+
+        impl $crate::minecraft::packet::PacketSerde for $name {}
+    };
+}
+
+pub(crate) use bit_flags;
+
 // ------------ NBT Implentation Start ------------
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -2143,7 +2243,8 @@ impl NBTValue {
             }
             NBTValue::String(v) => {
                 let bytes = cesu8::to_java_cesu8(v.as_str()).into_owned();
-                stream.write_all(&(bytes.len() as i32).to_be_bytes()).unwrap();
+                // the length is an unsigned short, same as `read_string`
+                stream.write_all(&(bytes.len() as u16).to_be_bytes()).unwrap();
                 stream.write_all(&bytes).unwrap();
             }
             NBTValue::IntArray(vs) => {
@@ -2295,6 +2396,35 @@ impl NBTValue {
         }
     }
 
+    // converts the value to json, text components are sent as NBT since 1.20.3
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            Self::Null => JsonValue::Null,
+            Self::Byte(v) => JsonValue::from(*v),
+            Self::Short(v) => JsonValue::from(*v),
+            Self::Int(v) => JsonValue::from(*v),
+            Self::Long(v) => JsonValue::from(*v),
+            Self::Float(v) => JsonValue::from(*v),
+            Self::Double(v) => JsonValue::from(*v),
+            Self::ByteArray(vs) => JsonValue::from(vs.clone()),
+            Self::String(v) => JsonValue::from(v.clone()),
+            Self::IntArray(vs) => JsonValue::from(vs.clone()),
+            Self::LongArray(vs) => JsonValue::from(vs.clone()),
+            Self::List(vs) => JsonValue::Array(vs.iter().map(|v| v.to_json()).collect()),
+            Self::Compound(_, vs) => {
+                // lists with mixed types wrap their values in a compound with an empty key
+                if let (1, Some(v)) = (vs.len(), vs.get("")) {
+                    return v.to_json();
+                }
+                let mut map = Map::with_capacity(vs.len());
+                for (k, v) in vs {
+                    map.insert(k.clone(), v.to_json());
+                }
+                JsonValue::Object(map)
+            }
+        }
+    }
+
     pub fn from_stream(stream: &mut impl Read, read_root_name: bool) -> Self {
         let type_id = Self::read_type_id(stream);
         Self::read_value(type_id, stream, read_root_name)
@@ -2340,6 +2470,21 @@ pub struct NetworkNBT {
     inner: NBTValue,
 }
 
+impl NetworkNBT {
+    #[inline]
+    pub fn new(inner: NBTValue) -> Self {
+        Self { inner }
+    }
+
+    pub fn get_value(&self) -> &NBTValue {
+        &self.inner
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        self.inner.to_json()
+    }
+}
+
 impl PacketReadable for NetworkNBT {
     fn read(stream: &mut impl Read) -> Self {
         Self {
@@ -2361,6 +2506,21 @@ pub struct DataNBT {
     inner: NBTValue,
 }
 
+impl DataNBT {
+    #[inline]
+    pub fn new(inner: NBTValue) -> Self {
+        Self { inner }
+    }
+
+    pub fn get_value(&self) -> &NBTValue {
+        &self.inner
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        self.inner.to_json()
+    }
+}
+
 impl PacketReadable for DataNBT {
     fn read(stream: &mut impl Read) -> Self {
         Self {
@@ -2562,28 +2722,30 @@ impl<const N: usize> FixedSizeBitSet<N> {
     /// Gets the bit at the given index.
     /// Panics if the index is out of bounds.
     pub fn get_bit(&self, index: usize) -> bool {
-        if index >= N {
+        if index >= N * 8 {
             panic!(
                 "Bit index out of bounds: the len is {} but the index is {}",
-                N, index
+                N * 8,
+                index
             );
         }
         let byte_index = index / 8;
-        let bit_in_byte_index = 7 - (index % 8); // MSB-first
+        let bit_in_byte_index = index % 8; // LSB-first, same as java's BitSet.toByteArray
         (self.values[byte_index] >> bit_in_byte_index) & 1 != 0
     }
 
     /// Sets the bit at the given index to the given value.
     /// Panics if the index is out of bounds.
     pub fn set_bit(&mut self, index: usize, value: bool) {
-        if index >= N {
+        if index >= N * 8 {
             panic!(
                 "Bit index out of bounds: the len is {} but the index is {}",
-                N, index
+                N * 8,
+                index
             );
         }
         let byte_index = index / 8;
-        let bit_in_byte_index = 7 - (index % 8); // MSB-first
+        let bit_in_byte_index = index % 8; // LSB-first, same as java's BitSet.toByteArray
         if value {
             self.values[byte_index] |= 1 << bit_in_byte_index;
         } else {
@@ -2637,12 +2799,16 @@ impl BitSet {
         if values.len() * 64 < bit_count {
             panic!("BitSet: values length is too small for the given bit_count");
         }
-        if (values.len() + 1) * 64 >= bit_count {
+        if values.len() > bit_count.div_ceil(64) {
             panic!("BitSet: values length is too large for the given bit_count");
         }
         Self { values, bit_count }
     }
 
+    pub fn empty() -> Self {
+        Self::new(Vec::new(), 0)
+    }
+
     pub fn get_bit_count(&self) -> usize {
         self.values.len() * 64
     }
@@ -2671,7 +2837,7 @@ impl BitSet {
             );
         }
         let long_index = index / 64;
-        let bit_in_long_index = 63 - (index % 64); // MSB-first
+        let bit_in_long_index = index % 64; // LSB-first, same as java.util.BitSet
         if value {
             self.values[long_index] |= 1 << bit_in_long_index;
         } else {
@@ -2830,8 +2996,10 @@ impl PacketReadable for IdSet {
     fn read(stream: &mut impl Read) -> Self {
         let type_ = VarInt::read(stream);
         match type_.into() {
-            0 => Self::Tag(String::read(stream).get_value()),
-            length => {
+            // the type is 0 for a tag name, or the number of ids plus one
+            0 => Self::Tag(Identifier::read(stream).to_string()),
+            type_ => {
+                let length = type_ - 1;
                 let mut ids = Vec::with_capacity(length as usize);
                 for _ in 0..length {
                     ids.push(VarInt::read(stream).get_value());
@@ -2847,10 +3015,10 @@ impl PacketWritable for IdSet {
         match self {
             Self::Tag(tag) => {
                 VarInt::new(0).write(stream);
-                String::from_str(&tag).write(stream);
+                Identifier::from(tag.as_str()).write(stream);
             }
             Self::Ids(ids) => {
-                VarInt::new(ids.len() as i32).write(stream);
+                VarInt::new(ids.len() as i32 + 1).write(stream);
                 for id in ids {
                     VarInt::new(*id).write(stream);
                 }
@@ -2986,6 +3154,27 @@ pub struct ToolRule {
     correct_drop_for_blocks: Optional<Boolean>,
 }
 
+impl ToolRule {
+    // blocks of the minecraft:block registry
+    pub fn get_blocks(&self) -> &IdSet {
+        &self.blocks
+    }
+
+    pub fn get_speed(&self) -> Option<f32> {
+        match &self.speed {
+            Optional::Some(speed) => Some(speed.get_value()),
+            Optional::None => None,
+        }
+    }
+
+    pub fn is_correct_for_drops(&self) -> Option<bool> {
+        match &self.correct_drop_for_blocks {
+            Optional::Some(correct) => Some(correct.get_value()),
+            Optional::None => None,
+        }
+    }
+}
+
 #[derive(PacketSerde, Debug, Clone)]
 pub struct BookPageContent {
     pub raw_content: String,      // The raw text of the page.
@@ -3081,14 +3270,14 @@ pub struct HiveResidentBee {
 #[derive(PacketSerde, Debug, Clone)]
 #[discriminant_type(VarInt)]
 pub enum StructuredComponent {
-    CustomData(DataNBT),
+    CustomData(NetworkNBT),
     MaxStackSize(VarInt),
     MaxDamage(VarInt),
     Damage(VarInt),
     Unbreakable(Boolean),
-    CustomName(DataNBT),
-    ItemName(DataNBT),
-    Lore(Array<DataNBT>),
+    CustomName(NetworkNBT),
+    ItemName(NetworkNBT),
+    Lore(Array<NetworkNBT>),
     Rarity(Rarity),
     Enchantments {
         enchantments: Array<(VarInt, VarInt)>, // (enchantment id, level)
@@ -3137,7 +3326,7 @@ pub enum StructuredComponent {
     },
     MapColor(Int),
     MapId(VarInt),
-    MapDecorations(DataNBT),
+    MapDecorations(NetworkNBT),
     MapPostProcessing(VarInt),
     ChargedProjectiles {
         projectiles: Array<Slot>,
@@ -3166,17 +3355,17 @@ pub enum StructuredComponent {
         trim_material: IdOr<TrimMaterial>,
         show_in_tooltip: Boolean,
     },
-    DebugStickState(DataNBT), // States of previously interacted blocks. Always a Compound Tag.
-    EntityData(DataNBT),      // Always a Compound Tag.
-    BucketEntityData(DataNBT), // Always a Compound Tag.
-    BlockEntityData(DataNBT), // Always a Compound Tag.
+    DebugStickState(NetworkNBT), // States of previously interacted blocks. Always a Compound Tag.
+    EntityData(NetworkNBT),      // Always a Compound Tag.
+    BucketEntityData(NetworkNBT), // Always a Compound Tag.
+    BlockEntityData(NetworkNBT), // Always a Compound Tag.
     Instrument(IdOr<Instrument>),
     OminousBottleAmplifier(VarInt),
     JukeboxPlayable {
         jukebox_song: Or<IdOr<JukeboxSong>, Identifier>,
         show_in_tooltip: Boolean,
     },
-    Recipes(DataNBT), // Always a Compound Tag.
+    Recipes(NetworkNBT), // Always a Compound Tag.
     LodestoneTracker {
         global_position: Optional<GlobalPosition>,
         tracked: Boolean,
@@ -3200,30 +3389,120 @@ pub enum StructuredComponent {
     },
     BlockState(Array<(String, String)>),
     Bees(Array<HiveResidentBee>),
-    Lock(DataNBT),          // Always a String Tag.
-    ContainerLoot(DataNBT), // Always a Compound Tag.
+    Lock(NetworkNBT),          // Always a String Tag.
+    ContainerLoot(NetworkNBT), // Always a Compound Tag.
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Slot {
     pub item_count: i32,
-    pub item_id: i32,
+    pub item_id: i32, // ID in the minecraft:item registry
     pub components_to_add: Vec<StructuredComponent>,
-    pub components_to_remove: Vec<StructuredComponent>,
+    pub components_to_remove: Vec<i32>, // IDs in the minecraft:data_component_type registry
+}
+
+impl Debug for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("Slot(Empty)");
+        }
+        let mut debug = f.debug_struct("Slot");
+        match self.item_name() {
+            Some(name) => debug.field("item", &name),
+            None => debug.field("item_id", &self.item_id),
+        };
+        debug
+            .field("item_count", &self.item_count)
+            .field("components_to_add", &self.components_to_add)
+            .field("components_to_remove", &self.components_to_remove)
+            .finish()
+    }
 }
 
 impl Slot {
-    fn new_empty() -> Self {
+    pub fn new(item_id: i32, item_count: i32) -> Self {
         Self {
-            item_count: 0,
-            item_id: 0,
+            item_count,
+            item_id,
             components_to_add: Vec::new(),
             components_to_remove: Vec::new(),
         }
     }
 
-    fn is_empty(&self) -> bool {
-        self.item_count == 0
+    pub fn new_empty() -> Self {
+        Self::new(0, 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_count <= 0
+    }
+
+    // e.g. "minecraft:diamond_sword", requires the built-in registries to be loaded
+    pub fn item_name(&self) -> Option<&'static str> {
+        get_item_name(self.item_id)
+    }
+
+    // whether both slots hold the same item with the same components, i.e. they can be stacked
+    pub fn is_same_item(&self, other: &Self) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return self.is_empty() && other.is_empty();
+        }
+        self.item_id == other.item_id
+            && self.components_to_remove == other.components_to_remove
+            && self.components_to_add.len() == other.components_to_add.len()
+            && self
+                .components_to_add
+                .iter()
+                .zip(&other.components_to_add)
+                .all(|(a, b)| a.to_bytes() == b.to_bytes())
+    }
+
+    // the max_stack_size component if the server sent one
+    pub fn get_max_stack_size(&self) -> Option<i32> {
+        self.components_to_add.iter().find_map(|component| match component {
+            StructuredComponent::MaxStackSize(size) => Some(size.get_value()),
+            _ => None,
+        })
+    }
+
+    // the rules and default mining speed of the tool component if the server sent one,
+    // the components of tools are usually the defaults of the item and are not sent
+    pub fn get_tool(&self) -> Option<(&Vec<ToolRule>, f32)> {
+        self.components_to_add.iter().find_map(|component| match component {
+            StructuredComponent::Tool {
+                rules,
+                default_mining_speed,
+                ..
+            } => Some((&**rules, default_mining_speed.get_value())),
+            _ => None,
+        })
+    }
+
+    // (enchantment id, level) pairs, ids are in the minecraft:enchantment registry
+    pub fn get_enchantments(&self) -> Vec<(i32, i32)> {
+        self.components_to_add
+            .iter()
+            .find_map(|component| match component {
+                StructuredComponent::Enchantments { enchantments, .. } => Some(
+                    enchantments
+                        .iter()
+                        .map(|(id, level)| (id.get_value(), level.get_value()))
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    // a copy of this slot with another count, an empty slot if the count is not positive
+    pub fn with_count(&self, item_count: i32) -> Self {
+        if item_count <= 0 {
+            return Self::new_empty();
+        }
+        Self {
+            item_count,
+            ..self.clone()
+        }
     }
 }
 
@@ -3242,7 +3521,7 @@ impl PacketReadable for Slot {
             components_to_add.push(StructuredComponent::read(stream));
         }
         for _ in 0..num_components_to_remove {
-            components_to_remove.push(StructuredComponent::read(stream));
+            components_to_remove.push(VarInt::read(stream).get_value());
         }
         Self {
             item_count,
@@ -3266,7 +3545,7 @@ impl PacketWritable for Slot {
             component.write(stream);
         }
         for component in &self.components_to_remove {
-            component.write(stream);
+            VarInt::new(*component).write(stream);
         }
     }
 }
@@ -3475,3 +3754,40 @@ pub struct PaintingVariant {
     pub title: Optional<NetworkNBT>,
     pub author: Optional<NetworkNBT>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_packing_round_trip() {
+        let positions = [
+            (0, 0, 0),
+            (1, -1, -1),
+            (-1, 1, 1),
+            (18357644, 831, -20882616),
+            (-33554432, -2048, -33554432), // the smallest coordinates that fit
+            (33554431, 2047, 33554431),    // the largest coordinates that fit
+        ];
+        for (x, y, z) in positions {
+            let position = Position::new(x, y, z);
+            let unpacked = Position::from_u64(position.to_u64());
+            assert_eq!(
+                (unpacked.get_x(), unpacked.get_y(), unpacked.get_z()),
+                (x, y, z)
+            );
+        }
+    }
+
+    #[test]
+    fn position_packing_matches_protocol() {
+        // the example from the protocol documentation
+        let packed = 0x4607_632c_15b4_833f; // x: 26 bits, z: 26 bits, y: 12 bits
+        let position = Position::from_u64(packed);
+        assert_eq!(
+            (position.get_x(), position.get_y(), position.get_z()),
+            (18357644, 831, -20882616)
+        );
+        assert_eq!(position.to_u64(), packed);
+    }
+}
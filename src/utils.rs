@@ -1,9 +1,13 @@
 pub mod ansi;
+pub mod language;
 pub mod logging;
+pub mod text;
 
 use std::io::{self, Read};
 
 use ansi::{AnsiColor, AnsiGraphicMode, AnsiGraphics, string::AnsiString};
+use language::{Language, TranslationPart, get_language, parse_translation_format};
+use logging::get_logger;
 use serde_json::{Map, Value as JsonValue};
 
 #[inline]
@@ -32,32 +36,53 @@ pub fn read_n_bytes<S: Read, U: Into<usize>>(reader: &mut S, n: U) -> Result<Vec
     }
 }
 
+// the named text component colors, with their legacy formatting code and rgb value
+pub const NAMED_COLORS: [(&str, char, (u8, u8, u8)); 16] = [
+    ("black", '0', (0, 0, 0)),
+    ("dark_blue", '1', (0, 0, 170)),
+    ("dark_green", '2', (0, 170, 0)),
+    ("dark_aqua", '3', (0, 170, 170)),
+    ("dark_red", '4', (170, 0, 0)),
+    ("dark_purple", '5', (170, 0, 170)),
+    ("gold", '6', (255, 170, 0)),
+    ("gray", '7', (170, 170, 170)),
+    ("dark_gray", '8', (85, 85, 85)),
+    ("blue", '9', (85, 85, 255)),
+    ("green", 'a', (85, 255, 85)),
+    ("aqua", 'b', (85, 255, 255)),
+    ("red", 'c', (255, 85, 85)),
+    ("light_purple", 'd', (255, 85, 255)),
+    ("yellow", 'e', (255, 255, 85)),
+    ("white", 'f', (255, 255, 255)),
+];
+
+// parses a named color or a hex color in the `#RRGGBB` format
+pub fn parse_color_name(name: &str) -> Option<(u8, u8, u8)> {
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        return match u32::from_str_radix(hex, 16) {
+            Ok(v) => Some(((v >> 16) as u8, (v >> 8) as u8, v as u8)),
+            Err(_) => None,
+        };
+    }
+    NAMED_COLORS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, _, rgb)| *rgb)
+}
+
 pub fn parse_color(value: &Map<String, JsonValue>) -> Option<(u8, u8, u8)> {
     let c = value.get("color");
     match c {
         None => None,
         Some(color) => match color.as_str() {
             None => None,
-            Some(s) => match s {
-                "black" => Some((0, 0, 0)),
-                "dark_blue" => Some((0, 0, 170)),
-                "dark_green" => Some((0, 170, 0)),
-                "dark_aqua" => Some((0, 170, 170)),
-                "dark_red" => Some((170, 0, 0)),
-                "dark_purple" => Some((170, 0, 170)),
-                "gold" => Some((255, 170, 0)),
-                "gray" => Some((170, 170, 170)),
-                "dark_gray" => Some((85, 85, 85)),
-                "blue" => Some((85, 85, 255)),
-                "green" => Some((85, 255, 85)),
-                "aqua" => Some((85, 255, 255)),
-                "red" => Some((255, 85, 85)),
-                "light_purple" => Some((255, 85, 255)),
-                "yellow" => Some((255, 255, 85)),
-                "white" => Some((255, 255, 255)),
-
-                _ => {
-                    println!("WARNING: color {} is not implemented!", s);
+            Some(s) => match parse_color_name(s) {
+                Some(rgb) => Some(rgb),
+                None => {
+                    get_logger().warn(format!("Color {} is not implemented!", s));
                     None
                 }
             },
@@ -77,7 +102,8 @@ pub fn parse_style(value: &Map<String, JsonValue>) -> (Vec<AnsiGraphicMode>, Vec
         match c {
             None => {}
             Some(v) => {
-                if v.as_bool() == Some(true) {
+                // text components decoded from NBT store booleans as bytes
+                if v.as_bool() == Some(true) || v.as_i64() == Some(1) {
                     add_agraphics.push(agm);
                 } else {
                     remove_agraphics.push(agm);
@@ -113,7 +139,7 @@ pub fn parse_style(value: &Map<String, JsonValue>) -> (Vec<AnsiGraphicMode>, Vec
         &mut add_agraphics,
         &mut remove_agraphics,
         value,
-        "underline",
+        "underlined",
         AnsiGraphicMode::Underline,
     );
     apply(
@@ -142,44 +168,90 @@ fn ansicolor(color: (u8, u8, u8)) -> AnsiColor {
     AnsiColor::new(color.0, color.1, color.2)
 }
 
+fn styled_text(text: &str, color: Option<(u8, u8, u8)>, style: &AnsiGraphics) -> AnsiString {
+    let mut astr = AnsiString::new_colorless(text);
+    astr.set_graphics(style.clone());
+    if let Some(c) = color {
+        astr.set_foreground(ansicolor(c));
+    }
+    astr
+}
+
+// resolves a `translate` component, the arguments in `with` inherit the style of the component
+pub fn parse_translate(
+    value: &Map<String, JsonValue>,
+    language: Option<&Language>,
+    color: Option<(u8, u8, u8)>,
+    style: &AnsiGraphics,
+) -> Option<AnsiString> {
+    let key = value.get("translate")?.as_str()?;
+    // use the fallback (or the key itself) if the language does not have this translation
+    let format = match language.and_then(|l| l.get(key)) {
+        Some(f) => f,
+        None => match value.get("fallback").and_then(|f| f.as_str()) {
+            Some(f) => f,
+            None => key,
+        },
+    };
+    let args: &[JsonValue] = match value.get("with").and_then(|w| w.as_array()) {
+        Some(args) => args,
+        None => &[],
+    };
+
+    let mut result = AnsiString::empty();
+    for part in parse_translation_format(format) {
+        match part {
+            TranslationPart::Literal(s) => result = result + styled_text(&s, color, style),
+            TranslationPart::Argument(index) => {
+                if let Some(arg) = args.get(index) {
+                    result = result
+                        + render_text_component_json(arg, language, color, Some(style.clone()));
+                }
+            }
+        }
+    }
+    Some(result)
+}
+
 pub fn parce_text_component_json(
     value: &JsonValue,
     parentcolor: Option<(u8, u8, u8)>,
     parentstyle: Option<AnsiGraphics>,
+) -> AnsiString {
+    render_text_component_json(value, get_language(), parentcolor, parentstyle)
+}
+
+// renders a text component, `translate` components are resolved using `language` if provided
+pub fn render_text_component_json(
+    value: &JsonValue,
+    language: Option<&Language>,
+    parentcolor: Option<(u8, u8, u8)>,
+    parentstyle: Option<AnsiGraphics>,
 ) -> AnsiString {
     //println!("TextComponent: {}", value);
-    if value.is_string() {
-        return AnsiString::new_colorless(value.as_str().unwrap());
+    let parentgraphics = parentstyle.clone().unwrap_or_default();
+    match value {
+        JsonValue::String(s) => return styled_text(s, parentcolor, &parentgraphics),
+        JsonValue::Number(n) => return styled_text(&n.to_string(), parentcolor, &parentgraphics),
+        JsonValue::Bool(b) => return styled_text(&b.to_string(), parentcolor, &parentgraphics),
+        JsonValue::Array(values) => {
+            let mut result = AnsiString::empty();
+            for v in values {
+                result = result
+                    + render_text_component_json(v, language, parentcolor, parentstyle.clone());
+            }
+            return result;
+        }
+        _ => {}
     }
     if let Some(v) = value.as_object() {
         let mut result = AnsiString::empty();
-        // get text as AnsiString if exists
-        let text = parse_text(v);
-        // new color
-        let mut newcolor = None;
-        match parentcolor {
-            Some(pc) => {
-                newcolor = Some(pc);
-            }
-            None => {}
-        }
-        // overwrite color if exists
-        match parse_color(v) {
-            Some(c) => {
-                newcolor = Some(c);
-            }
-            None => {}
-        }
+        // new color, overwrite parent color if exists
+        let newcolor = parse_color(v).or(parentcolor);
         // get style overwrites
         let stylechanges = parse_style(v);
         // new style
-        let mut newstyle = AnsiGraphics::new();
-        match parentstyle {
-            Some(ps) => {
-                newstyle = ps.clone();
-            }
-            None => {}
-        }
+        let mut newstyle = parentgraphics;
         // add new styles
         for agm in stylechanges.0 {
             newstyle.add(agm)
@@ -188,37 +260,24 @@ pub fn parce_text_component_json(
         for agm in stylechanges.1 {
             newstyle.remove(agm)
         }
-        match text {
-            Some(t) => {
-                let mut astr = t.clone();
-                astr.set_graphics(newstyle.clone());
-                match newcolor {
-                    Some(c) => {
-                        astr.set_foreground(ansicolor(c));
-                    }
-                    None => {}
-                }
 
-                result = result + astr;
+        // get text as AnsiString if exists, otherwise try to translate
+        if let Some(t) = parse_text(v) {
+            let mut astr = t.clone();
+            astr.set_graphics(newstyle.clone());
+            if let Some(c) = newcolor {
+                astr.set_foreground(ansicolor(c));
             }
-            None => {}
+            result = result + astr;
+        } else if let Some(t) = parse_translate(v, language, newcolor, &newstyle) {
+            result = result + t;
         }
 
-        let extra = v.get("extra");
-        match extra {
-            None => {}
-            Some(array) => match array.as_array() {
-                None => {}
-                Some(val) => {
-                    for obj in val {
-                        let objstring = match obj {
-                            JsonValue::String(s) => AnsiString::new_colorless(s),
-                            _ => parce_text_component_json(obj, newcolor, Some(newstyle.clone())),
-                        };
-                        result = result + objstring;
-                    }
-                }
-            },
+        if let Some(val) = v.get("extra").and_then(|e| e.as_array()) {
+            for obj in val {
+                result = result
+                    + render_text_component_json(obj, language, newcolor, Some(newstyle.clone()));
+            }
         }
 
         result
@@ -226,3 +285,8 @@ pub fn parce_text_component_json(
         AnsiString::empty()
     }
 }
+
+// renders a text component without any colors or styles
+pub fn render_text_component_plain(value: &JsonValue, language: Option<&Language>) -> String {
+    render_text_component_json(value, language, None, None).to_plain_string()
+}
@@ -175,3 +175,9 @@ impl AnsiGraphics {
         AnsiGraphics { modes: Vec::new() }
     }
 }
+
+impl Default for AnsiGraphics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
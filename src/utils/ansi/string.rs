@@ -120,6 +120,11 @@ impl AnsiString {
         _string + "\x1b[0m"
     }
 
+    // the text without any colors or graphics
+    pub fn to_plain_string(&self) -> String {
+        self.vec.iter().map(|ac| ac.char).collect()
+    }
+
     pub fn split_at(&self, mid: usize) -> (AnsiString, AnsiString) {
         let vecs = self.vec.split_at(mid);
         (
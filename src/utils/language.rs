@@ -0,0 +1,248 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Error, Read},
+    path::Path,
+    sync::OnceLock,
+};
+
+use serde_json::Value as JsonValue;
+
+// a piece of a translation format string, e.g. "%2$s was slain by %1$s"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranslationPart {
+    Literal(String),
+    Argument(usize), // zero based index into the `with` array
+}
+
+// parses a java style translation format, supports `%s`, `%d`, `%1$s` and `%%`
+pub fn parse_translation_format(format: &str) -> Vec<TranslationPart> {
+    let mut parts: Vec<TranslationPart> = Vec::new();
+    let mut literal = String::new();
+    let mut next_argument: usize = 0;
+    let chars: Vec<char> = format.chars().collect();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c != '%' || i + 1 >= chars.len() {
+            literal.push(c);
+            i += 1;
+            continue;
+        }
+
+        // escaped percent sign
+        if chars[i + 1] == '%' {
+            literal.push('%');
+            i += 2;
+            continue;
+        }
+
+        // sequential argument
+        if chars[i + 1] == 's' || chars[i + 1] == 'd' {
+            if !literal.is_empty() {
+                parts.push(TranslationPart::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(TranslationPart::Argument(next_argument));
+            next_argument += 1;
+            i += 2;
+            continue;
+        }
+
+        // positional argument, the index is one based
+        let mut j = i + 1;
+        while j < chars.len() && chars[j].is_ascii_digit() {
+            j += 1;
+        }
+        if j > i + 1
+            && j + 1 < chars.len()
+            && chars[j] == '$'
+            && (chars[j + 1] == 's' || chars[j + 1] == 'd')
+        {
+            let index: String = chars[i + 1..j].iter().collect();
+            let index: usize = index.parse().unwrap_or(1);
+            if !literal.is_empty() {
+                parts.push(TranslationPart::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(TranslationPart::Argument(index.saturating_sub(1)));
+            i = j + 2;
+            continue;
+        }
+
+        // not a valid format specifier, keep it as is
+        literal.push(c);
+        i += 1;
+    }
+
+    if !literal.is_empty() {
+        parts.push(TranslationPart::Literal(literal));
+    }
+    parts
+}
+
+// stores the translations of a vanilla-format language file (e.g. `lang/en_us.json`)
+#[derive(Debug, Clone, Default)]
+pub struct Language {
+    translations: HashMap<String, String>,
+}
+
+impl Language {
+    #[inline]
+    pub fn new(translations: HashMap<String, String>) -> Self {
+        Self { translations }
+    }
+
+    #[inline]
+    pub fn empty() -> Self {
+        Self::new(HashMap::new())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: JsonValue = serde_json::from_str(json)?;
+        let object = match value.as_object() {
+            Some(o) => o,
+            None => {
+                return Err(Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "language file must be a json object",
+                ));
+            }
+        };
+        let mut translations = HashMap::with_capacity(object.len());
+        for (key, value) in object {
+            if let Some(s) = value.as_str() {
+                translations.insert(key.clone(), s.to_string());
+            }
+        }
+        Ok(Self::new(translations))
+    }
+
+    pub fn from_file(filepath: &str) -> Result<Self, Error> {
+        let path = Path::new(filepath);
+        if !path.is_file() {
+            return Err(Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{:?} does not exist or is a directory.", filepath),
+            ));
+        }
+        let mut data = String::new();
+        File::open(path)?.read_to_string(&mut data)?;
+        Self::from_json(&data)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.translations.get(key).map(|s| s.as_str())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.translations.contains_key(key)
+    }
+
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.translations.insert(key.to_string(), value.to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.translations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.translations.is_empty()
+    }
+}
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+// sets the language used to render translated text, it can only be set once
+pub fn set_language(language: Language) -> bool {
+    LANGUAGE.set(language).is_ok()
+}
+
+pub fn get_language() -> Option<&'static Language> {
+    LANGUAGE.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::utils::ansi::AnsiGraphics;
+    use crate::utils::parse_translate;
+
+    use TranslationPart::{Argument, Literal};
+
+    fn literal(text: &str) -> TranslationPart {
+        Literal(text.to_string())
+    }
+
+    #[test]
+    fn sequential_arguments() {
+        assert_eq!(
+            parse_translation_format("%s has %d points"),
+            vec![
+                Argument(0),
+                literal(" has "),
+                Argument(1),
+                literal(" points")
+            ]
+        );
+    }
+
+    #[test]
+    fn positional_arguments() {
+        assert_eq!(
+            parse_translation_format("%2$s was slain by %1$s"),
+            vec![Argument(1), literal(" was slain by "), Argument(0)]
+        );
+    }
+
+    #[test]
+    fn percent_signs() {
+        assert_eq!(
+            parse_translation_format("100%% of %s"),
+            vec![literal("100% of "), Argument(0)]
+        );
+        // a trailing percent sign and an unterminated specifier are kept as text
+        assert_eq!(parse_translation_format("50%"), vec![literal("50%")]);
+        assert_eq!(
+            parse_translation_format("%s %1$"),
+            vec![Argument(0), literal(" %1$")]
+        );
+    }
+
+    fn translate(component: JsonValue, language: Option<&Language>) -> String {
+        let value = component.as_object().unwrap();
+        parse_translate(value, language, None, &AnsiGraphics::default())
+            .unwrap()
+            .to_plain_string()
+    }
+
+    #[test]
+    fn translate_with_fallback() {
+        let mut language = Language::empty();
+        language.insert("chat.type.text", "<%s> %s");
+        let component = json!({
+            "translate": "chat.type.text",
+            "fallback": "%s says %s",
+            "with": ["Alex", {"text": "hi"}]
+        });
+        assert_eq!(translate(component.clone(), Some(&language)), "<Alex> hi");
+        // the fallback is used without the translation, then the key itself
+        assert_eq!(translate(component, None), "Alex says hi");
+        assert_eq!(
+            translate(json!({"translate": "unknown.key"}), None),
+            "unknown.key"
+        );
+    }
+
+    #[test]
+    fn translate_with_missing_arguments() {
+        let mut language = Language::empty();
+        language.insert("death.attack.player", "%1$s was slain by %2$s");
+        let component = json!({"translate": "death.attack.player", "with": ["Alex"]});
+        assert_eq!(translate(component, Some(&language)), "Alex was slain by ");
+        let component = json!({"translate": "death.attack.player"});
+        assert_eq!(translate(component, Some(&language)), " was slain by ");
+    }
+}
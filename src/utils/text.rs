@@ -0,0 +1,357 @@
+// renders text components to plain text, html and legacy formatting codes, and parses
+// legacy formatted strings (e.g. "§cHello §lWorld") back into text components
+use serde_json::{Map, Value as JsonValue};
+
+use super::ansi::{AnsiColor, AnsiGraphicMode, AnsiGraphics, string::AnsiString};
+use super::language::Language;
+use super::{NAMED_COLORS, render_text_component_json};
+
+pub const SECTION_SIGN: char = '§';
+pub const AMPERSAND: char = '&';
+
+// (legacy code, text component style name, graphic mode)
+const FORMATTING_CODES: [(char, &str, AnsiGraphicMode); 5] = [
+    ('k', "obfuscated", AnsiGraphicMode::Blinking),
+    ('l', "bold", AnsiGraphicMode::Bold),
+    ('m', "strikethrough", AnsiGraphicMode::Strike),
+    ('n', "underlined", AnsiGraphicMode::Underline),
+    ('o', "italic", AnsiGraphicMode::Italic),
+];
+
+// a run of characters that share the same color and graphics
+struct StyledRun {
+    text: String,
+    color: Option<AnsiColor>,
+    graphics: AnsiGraphics,
+}
+
+fn split_runs(astr: &AnsiString) -> Vec<StyledRun> {
+    let mut runs: Vec<StyledRun> = Vec::new();
+    for ac in &astr.vec {
+        if let Some(run) = runs.last_mut()
+            && run.color == ac.fore_color
+            && run.graphics.is_eq(&ac.graphics)
+        {
+            run.text.push(ac.char);
+            continue;
+        }
+        runs.push(StyledRun {
+            text: ac.char.to_string(),
+            color: ac.fore_color,
+            graphics: ac.graphics.clone(),
+        });
+    }
+    runs
+}
+
+fn has_mode(graphics: &AnsiGraphics, mode: AnsiGraphicMode) -> bool {
+    graphics.modes.contains(&mode)
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            '\n' => result.push_str("<br>"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+// converts an AnsiString to html, every styled run becomes a span with inline styles
+pub fn ansi_to_html(astr: &AnsiString) -> String {
+    let mut result = String::new();
+    for run in split_runs(astr) {
+        let mut styles: Vec<String> = Vec::new();
+        if let Some(c) = run.color {
+            styles.push(format!("color:#{:02x}{:02x}{:02x}", c.0, c.1, c.2));
+        }
+        if has_mode(&run.graphics, AnsiGraphicMode::Bold) {
+            styles.push("font-weight:bold".to_string());
+        }
+        if has_mode(&run.graphics, AnsiGraphicMode::Italic) {
+            styles.push("font-style:italic".to_string());
+        }
+        let mut decorations: Vec<&str> = Vec::new();
+        if has_mode(&run.graphics, AnsiGraphicMode::Underline) {
+            decorations.push("underline");
+        }
+        if has_mode(&run.graphics, AnsiGraphicMode::Strike) {
+            decorations.push("line-through");
+        }
+        if !decorations.is_empty() {
+            styles.push(format!("text-decoration:{}", decorations.join(" ")));
+        }
+        // there is no css equivalent of obfuscated text, blur it instead
+        if has_mode(&run.graphics, AnsiGraphicMode::Blinking) {
+            styles.push("filter:blur(2px)".to_string());
+        }
+
+        if styles.is_empty() {
+            result.push_str(&escape_html(&run.text));
+        } else {
+            result.push_str(&format!(
+                "<span style=\"{}\">{}</span>",
+                styles.join(";"),
+                escape_html(&run.text)
+            ));
+        }
+    }
+    result
+}
+
+fn legacy_color_codes(color: &AnsiColor, prefix: char) -> String {
+    let rgb = (color.0, color.1, color.2);
+    match NAMED_COLORS.iter().find(|(_, _, c)| *c == rgb) {
+        Some((_, code, _)) => format!("{}{}", prefix, code),
+        // hex colors use the "§x§R§R§G§G§B§B" format
+        None => {
+            let mut result = format!("{}x", prefix);
+            for c in format!("{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2).chars() {
+                result.push(prefix);
+                result.push(c);
+            }
+            result
+        }
+    }
+}
+
+// converts an AnsiString to a string formatted with legacy formatting codes
+pub fn ansi_to_legacy(astr: &AnsiString, prefix: char) -> String {
+    let mut result = String::new();
+    let mut previous: Option<(Option<AnsiColor>, AnsiGraphics)> = None;
+    for run in split_runs(astr) {
+        let unchanged = match &previous {
+            Some((color, graphics)) => *color == run.color && graphics.is_eq(&run.graphics),
+            None => run.color.is_none() && run.graphics.modes.is_empty(),
+        };
+        if !unchanged {
+            // color codes reset the formatting, so the formatting is always written after it
+            match &run.color {
+                Some(c) => result.push_str(&legacy_color_codes(c, prefix)),
+                None => {
+                    result.push(prefix);
+                    result.push('r');
+                }
+            }
+            for (code, _, mode) in FORMATTING_CODES {
+                if has_mode(&run.graphics, mode) {
+                    result.push(prefix);
+                    result.push(code);
+                }
+            }
+        }
+        result.push_str(&run.text);
+        previous = Some((run.color, run.graphics));
+    }
+    result
+}
+
+pub fn render_text_component_html(value: &JsonValue, language: Option<&Language>) -> String {
+    ansi_to_html(&render_text_component_json(value, language, None, None))
+}
+
+pub fn render_text_component_legacy(
+    value: &JsonValue,
+    language: Option<&Language>,
+    prefix: char,
+) -> String {
+    ansi_to_legacy(
+        &render_text_component_json(value, language, None, None),
+        prefix,
+    )
+}
+
+#[derive(Clone, Default)]
+struct LegacyStyle {
+    color: Option<String>,
+    formats: Vec<&'static str>,
+}
+
+impl LegacyStyle {
+    fn to_component(&self, text: String) -> JsonValue {
+        let mut map = Map::new();
+        map.insert("text".to_string(), JsonValue::String(text));
+        if let Some(color) = &self.color {
+            map.insert("color".to_string(), JsonValue::String(color.clone()));
+        }
+        for format in &self.formats {
+            map.insert(format.to_string(), JsonValue::Bool(true));
+        }
+        JsonValue::Object(map)
+    }
+}
+
+// reads a "§x§R§R§G§G§B§B" hex color, `start` is the index after the `x`
+fn parse_legacy_hex(chars: &[char], start: usize, prefix: char) -> Option<String> {
+    let mut hex = String::with_capacity(7);
+    hex.push('#');
+    for i in 0..6 {
+        let p = chars.get(start + i * 2)?;
+        let c = chars.get(start + i * 2 + 1)?;
+        if *p != prefix || !c.is_ascii_hexdigit() {
+            return None;
+        }
+        hex.push(c.to_ascii_lowercase());
+    }
+    Some(hex)
+}
+
+// parses a string formatted with legacy formatting codes into a text component,
+// `prefix` is usually `SECTION_SIGN` or `AMPERSAND`
+pub fn parse_legacy_text(text: &str, prefix: char) -> JsonValue {
+    let chars: Vec<char> = text.chars().collect();
+    let mut extra: Vec<JsonValue> = Vec::new();
+    let mut style = LegacyStyle::default();
+    let mut current = String::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != prefix || i + 1 >= chars.len() {
+            current.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let code = chars[i + 1].to_ascii_lowercase();
+        let mut new_style = style.clone();
+        let mut consumed = 2;
+        if let Some((name, _, _)) = NAMED_COLORS.iter().find(|(_, c, _)| *c == code) {
+            new_style = LegacyStyle {
+                color: Some(name.to_string()),
+                formats: Vec::new(),
+            };
+        } else if let Some((_, name, _)) = FORMATTING_CODES.iter().find(|(c, _, _)| *c == code) {
+            if !new_style.formats.contains(name) {
+                new_style.formats.push(name);
+            }
+        } else if code == 'r' {
+            new_style = LegacyStyle::default();
+        } else if code == 'x' {
+            match parse_legacy_hex(&chars, i + 2, prefix) {
+                Some(hex) => {
+                    new_style = LegacyStyle {
+                        color: Some(hex),
+                        formats: Vec::new(),
+                    };
+                    consumed += 12;
+                }
+                None => consumed = 0,
+            }
+        } else {
+            consumed = 0;
+        }
+
+        // not a formatting code, keep the prefix as text
+        if consumed == 0 {
+            current.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        if !current.is_empty() {
+            extra.push(style.to_component(std::mem::take(&mut current)));
+        }
+        style = new_style;
+        i += consumed;
+    }
+    if !current.is_empty() {
+        extra.push(style.to_component(current));
+    }
+
+    let mut root = Map::new();
+    root.insert("text".to_string(), JsonValue::String(String::new()));
+    if !extra.is_empty() {
+        root.insert("extra".to_string(), JsonValue::Array(extra));
+    }
+    JsonValue::Object(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn legacy_codes_with_both_prefixes() {
+        let expected = json!({"text": "", "extra": [
+            {"text": "Hello ", "color": "red"},
+            {"text": "World", "color": "red", "bold": true}
+        ]});
+        assert_eq!(parse_legacy_text("§cHello §lWorld", SECTION_SIGN), expected);
+        assert_eq!(parse_legacy_text("&cHello &LWorld", AMPERSAND), expected);
+        // the other prefix and unknown codes are kept as text
+        assert_eq!(
+            parse_legacy_text("&cHi §z", SECTION_SIGN),
+            json!({"text": "", "extra": [{"text": "&cHi §z"}]})
+        );
+    }
+
+    #[test]
+    fn legacy_hex_colors() {
+        assert_eq!(
+            parse_legacy_text("§x§1§2§A§b§5§6Hex", SECTION_SIGN),
+            json!({"text": "", "extra": [{"text": "Hex", "color": "#12ab56"}]})
+        );
+        // an incomplete hex color is text followed by the named color codes
+        assert_eq!(
+            parse_legacy_text("§x§1§2Hex", SECTION_SIGN),
+            json!({"text": "", "extra": [
+                {"text": "§x"},
+                {"text": "Hex", "color": "dark_green"}
+            ]})
+        );
+    }
+
+    #[test]
+    fn legacy_resets() {
+        // colors reset the formatting, `r` resets everything
+        assert_eq!(
+            parse_legacy_text("§l§nA§cB§oC§rD", SECTION_SIGN),
+            json!({"text": "", "extra": [
+                {"text": "A", "bold": true, "underlined": true},
+                {"text": "B", "color": "red"},
+                {"text": "C", "color": "red", "italic": true},
+                {"text": "D"}
+            ]})
+        );
+    }
+
+    #[test]
+    fn html_is_escaped_and_styled() {
+        let component = json!({"text": "", "extra": [
+            {"text": "<b>&\"", "color": "red", "bold": true},
+            {"text": "it's\nfine"}
+        ]});
+        assert_eq!(
+            render_text_component_html(&component, None),
+            "<span style=\"color:#ff5555;font-weight:bold\">&lt;b&gt;&amp;&quot;</span>it&#39;s<br>fine"
+        );
+        let component = json!({"text": "x", "underlined": true, "strikethrough": true});
+        assert_eq!(
+            render_text_component_html(&component, None),
+            "<span style=\"text-decoration:underline line-through\">x</span>"
+        );
+    }
+
+    #[test]
+    fn legacy_round_trip() {
+        let text = "§cHello §lWorld§r! §x§1§2§a§b§5§6§nHex";
+        let component = parse_legacy_text(text, SECTION_SIGN);
+        let legacy = render_text_component_legacy(&component, None, SECTION_SIGN);
+        // color codes are repeated before formatting codes
+        assert_eq!(legacy, "§cHello §c§lWorld§r! §x§1§2§a§b§5§6§nHex");
+        assert_eq!(parse_legacy_text(&legacy, SECTION_SIGN), component);
+        assert_eq!(
+            render_text_component_legacy(&component, None, AMPERSAND),
+            "&cHello &c&lWorld&r! &x&1&2&a&b&5&6&nHex"
+        );
+    }
+}